@@ -0,0 +1,117 @@
+//! Runtime cost of dispatching through the hooked `enum Rule`.
+//!
+//! One million nodes are dispatched through (a) a direct call on a concrete rule struct, (b) the hooked
+//! `enum Rule` and (c) `Box<dyn ParserInterface>`. After the measurements, (b) is checked to stay within a
+//! small factor of (a), so the generated glue cannot silently reintroduce indirect calls:
+//!
+//! ```sh
+//! cargo bench --bench dispatch
+//! ```
+
+use criterion::{black_box, criterion_group, Criterion};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use std::time::{Duration, Instant};
+
+const NODES: usize = 1_000_000;
+
+/// how many times slower than a direct call the enum dispatch may be, generous to tolerate noisy machines.
+const TOLERANCE: u32 = 8;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn weight(&self) -> u64;
+}
+
+#[pest_parser(grammar = "examples/grammar.pest", interface = "ParserInterface")]
+pub struct LanguageParser;
+
+macro_rules! weights {
+    ($($rule:ident => $weight:literal),* $(,)?) => {
+        $(impl ParserInterface for $rule {
+            fn weight(&self) -> u64 {
+                $weight
+            }
+        })*
+    };
+}
+
+weights! {
+    EOI => 0, Script => 1, Statement => 2, Command => 3, Arguments => 4,
+    Argument => 5, Strings => 6, Number => 7, Identifier => 8, WHITESPACE => 9,
+}
+
+fn direct(nodes: &[Number]) -> u64 {
+    nodes.iter().map(|node| black_box(node).weight()).sum()
+}
+
+fn hooked(nodes: &[Rule]) -> u64 {
+    nodes.iter().map(|node| black_box(node).weight()).sum()
+}
+
+fn boxed(nodes: &[Box<dyn ParserInterface>]) -> u64 {
+    nodes.iter().map(|node| black_box(node).weight()).sum()
+}
+
+struct Nodes {
+    direct: Vec<Number>,
+    hooked: Vec<Rule>,
+    boxed: Vec<Box<dyn ParserInterface>>,
+}
+
+impl Nodes {
+    fn new() -> Self {
+        let kinds = RuleKind::ALL.iter().copied().cycle().take(NODES);
+        Self {
+            direct: vec![Number; NODES],
+            hooked: kinds.clone().map(Rule::from).collect(),
+            boxed: kinds
+                .map(|kind| -> Box<dyn ParserInterface> { Box::new(Rule::from(kind)) })
+                .collect(),
+        }
+    }
+}
+
+fn dispatch(c: &mut Criterion) {
+    let nodes = Nodes::new();
+    let mut group = c.benchmark_group("dispatch 1M nodes");
+    group.bench_function("direct", |b| b.iter(|| direct(&nodes.direct)));
+    group.bench_function("hooked enum", |b| b.iter(|| hooked(&nodes.hooked)));
+    group.bench_function("Box<dyn>", |b| b.iter(|| boxed(&nodes.boxed)));
+    group.finish();
+}
+
+/// the fastest of a few runs, which is the least disturbed by the rest of the machine.
+fn fastest(mut run: impl FnMut() -> u64) -> Duration {
+    (0..10)
+        .map(|_| {
+            let start = Instant::now();
+            black_box(run());
+            start.elapsed()
+        })
+        .min()
+        .unwrap()
+}
+
+fn assert_zero_overhead() {
+    // unoptimized builds (e.g. `cargo test --benches`) don't inline anything, so there is nothing to check.
+    if cfg!(debug_assertions) {
+        return;
+    }
+    let nodes = Nodes::new();
+    let direct = fastest(|| direct(&nodes.direct));
+    let hooked = fastest(|| hooked(&nodes.hooked));
+    println!("direct: {direct:?}, hooked enum: {hooked:?}");
+    assert!(
+        hooked <= direct * TOLERANCE,
+        "dispatching through `enum Rule` is more than {TOLERANCE}x slower than a direct call"
+    );
+}
+
+criterion_group!(benches, dispatch);
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+    assert_zero_overhead();
+}
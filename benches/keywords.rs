@@ -0,0 +1,59 @@
+//! Parsing with a keyword-heavy grammar: one rule choosing between 1000 string literals.
+//!
+//! The point is mostly the build: long choices of literals are held out of `pest`'s optimizer, which is cubic in
+//! their length (see "Keyword-Heavy Grammars" in the README), and `enum Rule` is extracted without parsing the
+//! rule bodies. Before the measurement, every keyword is checked to parse, and a few non-keywords not to:
+//!
+//! ```sh
+//! cargo bench --bench keywords
+//! ```
+
+use criterion::{black_box, criterion_group, Criterion};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_rules;
+use pest::Parser;
+
+#[pest_rules(grammar = "benches/keywords.pest")]
+pub struct KeywordsParser;
+
+fn document() -> String {
+    (0..1000)
+        .map(|index| format!("kw{index}"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+fn assert_keywords_parse() {
+    let document = document();
+    let pairs = KeywordsParser::parse(RuleKind::keywords.into(), &document)
+        .expect("every keyword parses")
+        .next()
+        .unwrap()
+        .into_inner()
+        .filter(|pair| pair.as_rule() == RuleKind::keyword)
+        .map(|pair| pair.as_str().to_owned())
+        .collect::<Vec<_>>();
+    assert_eq!(pairs.len(), 1000);
+    assert_eq!(pairs[999], "kw999");
+    for input in ["kw1000", "kw", "kw0x", "KW0"] {
+        assert!(
+            KeywordsParser::parse(RuleKind::keyword.into(), input).is_err(),
+            "`{input}` is not a keyword"
+        );
+    }
+}
+
+fn parse_keywords(c: &mut Criterion) {
+    let document = document();
+    c.bench_function("parse 1000 keywords", |b| {
+        b.iter(|| KeywordsParser::parse(RuleKind::keywords.into(), black_box(&document)).unwrap())
+    });
+}
+
+criterion_group!(benches, parse_keywords);
+
+fn main() {
+    assert_keywords_parse();
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
@@ -0,0 +1,55 @@
+//! Cost of looking rules up by name on a 500-rule grammar.
+//!
+//! `RuleKind::from_name()` binary searches a table sorted while expanding the macro. It is compared with a
+//! linear scan over `RuleKind::name()`, which is what a `match` on the name string amounts to. That every name
+//! resolves to its rule is checked by `tests/rule_name.rs`.
+//!
+//! ```sh
+//! cargo bench --bench names
+//! ```
+
+use criterion::{black_box, criterion_group, Criterion};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_rules;
+
+#[pest_rules(grammar = "benches/names.pest")]
+pub struct NamesParser;
+
+fn linear(name: &str) -> Option<RuleKind> {
+    RuleKind::all()
+        .iter()
+        .copied()
+        .find(|kind| kind.name() == name)
+}
+
+fn names(c: &mut Criterion) {
+    let names = RuleKind::all()
+        .iter()
+        .map(|kind| kind.name())
+        .collect::<Vec<_>>();
+    let mut group = c.benchmark_group("look up all 500 names");
+    group.bench_function("linear", |b| {
+        b.iter(|| {
+            names
+                .iter()
+                .filter_map(|name| linear(black_box(name)))
+                .count()
+        })
+    });
+    group.bench_function("sorted table", |b| {
+        b.iter(|| {
+            names
+                .iter()
+                .filter_map(|name| RuleKind::from_name(black_box(name)))
+                .count()
+        })
+    });
+    group.finish();
+}
+
+criterion_group!(benches, names);
+
+fn main() {
+    benches();
+    Criterion::default().configure_from_args().final_summary();
+}
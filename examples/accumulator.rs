@@ -0,0 +1,69 @@
+//! Folding a parse tree into an accumulator with `walk_into()`.
+//!
+//! The accumulator implements the interface itself and collects a flat node list. Since every rule
+//! struct has to implement the interface as well, the walk method comes with a default body.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::{iterators::Pair, Parser};
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn collect(&mut self, _pair: &Pair<'_, Rule>) {}
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    walk_method = "collect"
+)]
+pub struct LanguageParser;
+
+#[derive(Debug)]
+struct Node {
+    kind: RuleKind,
+    text: String,
+}
+
+#[derive(Default)]
+struct NodeList(Vec<Node>);
+
+impl ParserInterface for NodeList {
+    fn collect(&mut self, pair: &Pair<'_, Rule>) {
+        self.0.push(Node {
+            kind: pair.as_rule().kind(),
+            text: pair.as_str().to_string(),
+        });
+    }
+}
+
+rule_structs!(ParserInterface);
+
+fn main() -> Result<()> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), "@print(\"hello\", 42);")?;
+    let mut nodes = NodeList::default();
+    walk_into(pairs, &mut nodes);
+    let kinds = nodes.0.iter().map(|node| node.kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            RuleKind::Script,
+            RuleKind::Statement,
+            RuleKind::Command,
+            RuleKind::Identifier,
+            RuleKind::Arguments,
+            RuleKind::Argument,
+            RuleKind::Strings,
+            RuleKind::Argument,
+            RuleKind::Number,
+        ]
+    );
+    for node in nodes.0 {
+        println!("{:?}: {}", node.kind, node.text);
+    }
+    Ok(())
+}
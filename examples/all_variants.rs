@@ -0,0 +1,50 @@
+//! Exhaustive matches over the rules with `all_variants!`.
+//!
+//! `all_variants!` is a `match` over `RuleKind` whose arms list rules, with `_rest` for the rules no other arm
+//! names. It has no `_` wildcard: without `_rest`, a rule added to the grammar fails to compile until it is
+//! handled, see `tests/ui/all_variants_unhandled.rs`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    generate = "iter, all_variants"
+)]
+pub struct LanguageParser;
+
+/// every rule has an arm, some of them shared.
+fn category(kind: RuleKind) -> &'static str {
+    all_variants!(kind, {
+        Script | Statement | Command => "structure",
+        Arguments | Argument => "arguments",
+        Strings | Number => "literal",
+        Identifier => "name",
+        EOI | WHITESPACE => "implicit",
+    })
+}
+
+/// the rules of no other arm share `_rest`.
+fn is_literal(kind: RuleKind) -> bool {
+    all_variants!(kind, {
+        Strings | Number => true,
+        _rest => false,
+    })
+}
+
+fn main() {
+    assert_eq!(category(RuleKind::Command), "structure");
+    assert_eq!(category(RuleKind::Number), "literal");
+    assert_eq!(category(RuleKind::EOI), "implicit");
+
+    let literals = RuleKind::iter()
+        .filter(|kind| is_literal(*kind))
+        .collect::<Vec<_>>();
+    assert_eq!(literals, [RuleKind::Strings, RuleKind::Number]);
+
+    println!("ok");
+}
@@ -0,0 +1,41 @@
+//! Testing the rules of a grammar on inline inputs with `assert_parses!`.
+//!
+//! The tests below, including the failing assertions, run with `cargo test --example assert_parses`.
+
+use enum_dispatch::enum_dispatch;
+
+#[macro_use]
+mod common;
+
+language_parser!(generate = "assert_parses");
+
+fn main() {
+    assert_parses!(Script, "@print(\"hello\", 42); @exit();");
+    let command = assert_parses!(Command, "@print(\"hello\", 42)");
+    assert_eq!(command.into_inner().count(), 2);
+    println!("ok");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn statements() {
+        assert_parses!(Statement, "@print(\"hello\", 42);");
+        let arguments = assert_parses!(Arguments, "1, \"two\", three");
+        assert_eq!(arguments.into_inner().count(), 3);
+    }
+
+    #[test]
+    #[should_panic(expected = "`Statement` does not parse \"@print(42)\"")]
+    fn statement_needs_semicolon() {
+        assert_parses!(Statement, "@print(42)");
+    }
+
+    #[test]
+    #[should_panic(expected = "`Number` only matches \"42\" of \"42abc\"")]
+    fn number_is_whole_input() {
+        assert_parses!(Number, "42abc");
+    }
+}
@@ -0,0 +1,57 @@
+//! Placing the rule structs in an existing module with `struct_path` and `rule_structs!`.
+//!
+//! An attribute macro cannot emit items into another module, so the parser only refers to the structs
+//! through `struct_path`, while `rule_structs!` emits them inside the module itself.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+mod ast {
+    enum_dispatch_pest_parser::rule_structs!(grammar = "examples/grammar.pest");
+
+    /// an already existing item of the module, next to the generated structs.
+    pub fn describe(rule: &super::Rule) -> String {
+        format!("{:?}", rule.kind())
+    }
+}
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn is_statement(&self) -> bool {
+        false
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    struct_path = "crate::ast"
+)]
+pub struct LanguageParser;
+
+impl ParserInterface for ast::Statement {
+    fn is_statement(&self) -> bool {
+        true
+    }
+}
+
+rule_structs!(
+    ParserInterface for ast: EOI, WHITESPACE, Script, Command, Arguments, Argument, Strings, Number,
+    Identifier
+);
+
+fn main() -> Result<()> {
+    let pairs = LanguageParser::parse(Rule::Script(ast::Script), "@a(1);@b(\"2\");")?;
+    let statements = pairs
+        .flatten()
+        .filter(|pair| pair.as_rule().is_statement())
+        .count();
+    assert_eq!(statements, 2);
+    println!("{}", ast::describe(&Rule::Statement(ast::Statement)));
+    Ok(())
+}
@@ -0,0 +1,54 @@
+//! Dispatching an `async_trait` based interface.
+//!
+//! `#[async_trait]` has to be placed *above* `#[enum_dispatch]`, so that `enum_dispatch` records the
+//! desugared (boxed future) signatures instead of the `async fn` ones.
+
+use anyhow::Result;
+use async_trait::async_trait;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[async_trait]
+#[enum_dispatch]
+pub trait ParserInterface {
+    async fn parse_rule(&self, arg: &str) -> Result<()>;
+}
+
+#[pest_parser(grammar = "examples/grammar.pest", interface = "ParserInterface")]
+pub struct LanguageParser;
+
+#[async_trait]
+impl ParserInterface for Command {
+    async fn parse_rule(&self, arg: &str) -> Result<()> {
+        println!("command: {arg}");
+        Ok(())
+    }
+}
+
+macro_rules! ignored_rules {
+    ($($rule:ident),*) => {
+        $(
+            #[async_trait]
+            impl ParserInterface for $rule {
+                async fn parse_rule(&self, _arg: &str) -> Result<()> {
+                    Ok(())
+                }
+            }
+        )*
+    };
+}
+
+ignored_rules!(
+    EOI, WHITESPACE, Script, Statement, Arguments, Argument, Strings, Number, Identifier
+);
+
+fn main() -> Result<()> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), "@print(\"hello\", 42);")?;
+    pollster::block_on(async {
+        for pair in pairs.flatten() {
+            pair.as_rule().parse_rule(pair.as_str()).await?;
+        }
+        Ok(())
+    })
+}
@@ -0,0 +1,57 @@
+//! Dispatching an interface method which returns an iterator borrowing from the input.
+//!
+//! `enum_dispatch` matches on `Rule` and returns the result of each rule struct, so every arm must have
+//! the same type: a method returning `impl Iterator<Item = ...> + 'i` does not dispatch, its boxed
+//! counterpart `Box<dyn Iterator<Item = ...> + 'i>` does.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::{iterators::Pair, Parser};
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    /// Returns the texts `pair` contributes, e.g. the values of an argument list.
+    fn texts<'i>(&self, _pair: &Pair<'i, Rule>) -> Box<dyn Iterator<Item = &'i str> + 'i> {
+        Box::new(std::iter::empty())
+    }
+}
+
+#[pest_parser(grammar = "examples/grammar.pest", interface = "ParserInterface")]
+pub struct LanguageParser;
+
+impl ParserInterface for Command {
+    fn texts<'i>(&self, pair: &Pair<'i, Rule>) -> Box<dyn Iterator<Item = &'i str> + 'i> {
+        Box::new(
+            pair.clone()
+                .into_inner()
+                .filter(|pair| pair.as_rule() == RuleKind::Identifier)
+                .map(|pair| pair.as_str()),
+        )
+    }
+}
+
+impl ParserInterface for Arguments {
+    fn texts<'i>(&self, pair: &Pair<'i, Rule>) -> Box<dyn Iterator<Item = &'i str> + 'i> {
+        Box::new(pair.clone().into_inner().map(|pair| pair.as_str()))
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Statement, Argument, Strings, Number, Identifier
+);
+
+fn main() -> Result<()> {
+    let input = String::from("@print(\"hello\", 42); @exit(0);");
+    let pairs = LanguageParser::parse(Rule::Script(Script), &input)?;
+    let texts: Vec<&str> = pairs
+        .flatten()
+        .flat_map(|pair| pair.as_rule().texts(&pair))
+        .collect();
+    assert_eq!(texts, ["print", "\"hello\"", "42", "exit", "0"]);
+    println!("{texts:?}");
+    Ok(())
+}
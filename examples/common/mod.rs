@@ -0,0 +1,43 @@
+//! The parser over `examples/grammar.pest` shared by the examples and the tests (`#[path]`-included there).
+//!
+//! Expects `enum_dispatch::enum_dispatch` in scope, like any `#[pest_parser]` without `enum_dispatch_path`.
+#![allow(unused_macros)]
+
+/// implements `$interface` for rule structs, with the default bodies of its methods: every rule of
+/// `examples/grammar.pest` (`EOI` included) unless the rules are listed after a `:`, and the structs of the
+/// module `for` names instead of the ones in scope.
+macro_rules! rule_structs {
+    ($interface:ident $(for $($module:ident)::+)?) => {
+        rule_structs!(
+            $interface $(for $($module)::+)?:
+            EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Strings, Number, Identifier
+        );
+    };
+    ($interface:ident: $($rule:ident),* $(,)?) => {
+        $(impl $interface for $rule {})*
+    };
+    ($interface:ident for $($module:ident)::+: $($rule:ident),* $(,)?) => {
+        const _: () = {
+            use $($module)::+::*;
+            $(impl $interface for $rule {})*
+        };
+    };
+}
+
+/// declares an empty `ParserInterface`, `LanguageParser` over `examples/grammar.pest` dispatching it with the
+/// other arguments of `#[pest_parser]` given, and the impls of `ParserInterface` for every rule struct.
+macro_rules! language_parser {
+    ($($argument:tt)*) => {
+        #[enum_dispatch]
+        pub trait ParserInterface {}
+
+        #[::enum_dispatch_pest_parser::pest_parser(
+            grammar = "examples/grammar.pest",
+            interface = "ParserInterface",
+            $($argument)*
+        )]
+        pub struct LanguageParser;
+
+        rule_structs!(ParserInterface);
+    };
+}
@@ -0,0 +1,91 @@
+//! Handling pairs with their position in the tree through `dispatch_with_context()`.
+
+use anyhow::{ensure, Result};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    /// Whether the rule may appear at `depth`.
+    fn allowed_at(&self, _depth: usize) -> bool {
+        true
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    generate = "name, context"
+)]
+pub struct LanguageParser;
+
+impl ParserInterface for Statement {
+    fn allowed_at(&self, depth: usize) -> bool {
+        depth == 1
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Command, Arguments, Argument, Strings, Number,
+    Identifier
+);
+
+fn main() -> Result<()> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), "@a(1, \"x\"); @b();")?;
+    let mut visited = Vec::new();
+    dispatch_with_context(pairs, |context| {
+        let rule = context.pair.as_rule();
+        ensure!(
+            rule.allowed_at(context.depth),
+            "misplaced `{}`",
+            context.pair.as_str()
+        );
+        visited.push((
+            rule.kind().name(),
+            context.depth,
+            context.parent.map(|parent| parent.name()),
+            context.index_in_parent,
+        ));
+        Ok(())
+    })?;
+    assert_eq!(
+        visited,
+        [
+            ("Script", 0, None, 0),
+            ("Statement", 1, Some("Script"), 0),
+            ("Command", 2, Some("Statement"), 0),
+            ("Identifier", 3, Some("Command"), 0),
+            ("Arguments", 3, Some("Command"), 1),
+            ("Argument", 4, Some("Arguments"), 0),
+            ("Number", 5, Some("Argument"), 0),
+            ("Argument", 4, Some("Arguments"), 1),
+            ("Strings", 5, Some("Argument"), 0),
+            ("Statement", 1, Some("Script"), 1),
+            ("Command", 2, Some("Statement"), 0),
+            ("Identifier", 3, Some("Command"), 0),
+            // the trailing `EOI` of `Script` is skipped.
+        ]
+    );
+
+    // the handler fails on a statement nested one level too deep, stopping the traversal.
+    let statement = LanguageParser::parse(Rule::Statement(Statement), "@c();")?;
+    let mut calls = 0;
+    let error = dispatch_with_context(statement, |context| {
+        calls += 1;
+        ensure!(
+            context.pair.as_rule().allowed_at(context.depth),
+            "misplaced `{}`",
+            context.pair.as_str()
+        );
+        Ok(())
+    })
+    .unwrap_err();
+    assert_eq!(error.to_string(), "misplaced `@c();`");
+    assert_eq!(calls, 1);
+    println!("ok");
+    Ok(())
+}
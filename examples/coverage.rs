@@ -0,0 +1,57 @@
+//! Finding the rules a test corpus never exercises, with the `RuleCoverage` of the `coverage` feature.
+//!
+//! Every line of `tests/fixtures/coverage_corpus.txt` is parsed and walked while a coverage is installed; the
+//! corpus has no string argument, so `Strings` is reported as uncovered. Run with
+//! `cargo run --example coverage --features coverage`.
+
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+use std::sync::Arc;
+use std::thread;
+
+#[macro_use]
+mod common;
+
+language_parser!(generate = "stack");
+
+const CORPUS: &str = include_str!("../tests/fixtures/coverage_corpus.txt");
+
+fn walk(input: &str) -> anyhow::Result<()> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), input)?;
+    walk_with_stack(pairs, |_, _| Ok(()))
+}
+
+fn main() -> anyhow::Result<()> {
+    // nothing is recorded until a coverage is installed.
+    walk("@a(\"unrecorded\");")?;
+
+    let coverage = Arc::new(RuleCoverage::new());
+    assert!(RuleCoverage::install(coverage.clone()).is_none());
+    for line in CORPUS.lines() {
+        walk(line)?;
+    }
+    // a coverage is installed per thread, and shared by the threads it is installed on.
+    thread::scope(|scope| {
+        scope
+            .spawn(|| {
+                RuleCoverage::install(coverage.clone());
+                walk("@spawned();")
+            })
+            .join()
+            .expect("the walk panicked")
+    })?;
+    assert!(Arc::ptr_eq(&RuleCoverage::uninstall().unwrap(), &coverage));
+    walk("@b(\"unrecorded\");")?;
+
+    assert_eq!(coverage.uncovered(), [RuleKind::Strings]);
+    assert_eq!(coverage.count(RuleKind::Script), 5);
+    assert_eq!(coverage.count(RuleKind::Statement), 6);
+    assert_eq!(coverage.count(RuleKind::Number), 4);
+    // `EOI` is skipped by the walkers and `WHITESPACE` is silent, neither is reported as uncovered.
+    let report = coverage.report();
+    assert_eq!(report.len(), RuleKind::all().len());
+    assert!(report.contains(&(RuleKind::EOI, 0)));
+    assert!(report.contains(&(RuleKind::WHITESPACE, 0)));
+    println!("ok");
+    Ok(())
+}
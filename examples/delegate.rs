@@ -0,0 +1,159 @@
+//! Routing the rules to the functions of one handler type with `delegate_to`.
+//!
+//! `Handle` is implemented for every rule struct by calling the function of `Handlers` named after it in snake case,
+//! `Statement` calling `Handlers::statement`; the impls are written by `delegate_rules!`, generated next to the rules
+//! with `generate = "delegate"`. `Describe` delegates the listed rules only, to `Literals`, and is implemented by
+//! hand for the others. A rule without its function fails to compile, see `tests/ui/delegate_missing_handler.rs`.
+
+use anyhow::{bail, Result};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::{pest_dispatch, pest_rules};
+use pest::Parser;
+use std::cell::RefCell;
+
+#[pest_rules(grammar = "examples/grammar.pest", generate = "delegate")]
+pub struct LanguageParser;
+
+#[pest_dispatch(rules = "LanguageParser", delegate_to = "Handlers")]
+pub trait Handle {
+    fn handle(&self, text: &str) -> Result<()>;
+}
+
+#[pest_dispatch(
+    rules = "LanguageParser",
+    delegate_to = "Literals",
+    handler_method = "describe",
+    handler_rules = "Number, Strings"
+)]
+pub trait Describe {
+    fn describe(&self) -> String;
+
+    fn is_literal(&self) -> bool {
+        false
+    }
+}
+
+thread_local! {
+    static HANDLED: RefCell<Vec<String>> = const { RefCell::new(Vec::new()) };
+}
+
+struct Handlers;
+
+impl Handlers {
+    fn record(rule: &str, text: &str) -> Result<()> {
+        HANDLED.with_borrow_mut(|handled| handled.push(format!("{rule} {text}")));
+        Ok(())
+    }
+
+    fn eoi(_text: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn whitespace(_text: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn script(text: &str) -> Result<()> {
+        Self::record("script", text)
+    }
+
+    fn statement(text: &str) -> Result<()> {
+        Self::record("statement", text)
+    }
+
+    fn command(text: &str) -> Result<()> {
+        Self::record("command", text)
+    }
+
+    fn arguments(_text: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn argument(_text: &str) -> Result<()> {
+        Ok(())
+    }
+
+    fn strings(text: &str) -> Result<()> {
+        Self::record("strings", text)
+    }
+
+    fn number(text: &str) -> Result<()> {
+        text.parse::<u8>()?;
+        Self::record("number", text)
+    }
+
+    fn identifier(text: &str) -> Result<()> {
+        if text == "panic" {
+            bail!("`{text}` is reserved");
+        }
+        Self::record("identifier", text)
+    }
+}
+
+/// the functions of `Describe`, which only `Number` and `Strings` delegate.
+struct Literals;
+
+impl Literals {
+    fn number() -> String {
+        String::from("literal number")
+    }
+
+    fn strings() -> String {
+        String::from("literal strings")
+    }
+}
+
+macro_rules! rule_structs {
+    ($($rule:ident),*) => {
+        $(impl Describe for $rule {
+            fn describe(&self) -> String {
+                format!("{:?}", RuleKind::$rule)
+            }
+        })*
+    };
+}
+
+rule_structs!(EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Identifier);
+
+fn main() -> Result<()> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), "@print(\"hi\", 42);")?;
+    for pair in pairs.clone().flatten() {
+        pair.as_rule().handle(pair.as_str())?;
+    }
+    let handled = HANDLED.with_borrow(|handled| handled.clone());
+    assert_eq!(
+        handled,
+        [
+            "script @print(\"hi\", 42);",
+            "statement @print(\"hi\", 42);",
+            "command @print(\"hi\", 42)",
+            "identifier print",
+            "strings \"hi\"",
+            "number 42",
+        ]
+    );
+    assert!(Identifier.handle("panic").is_err());
+    assert!(Number.handle("256").is_err());
+
+    let described = pairs
+        .flatten()
+        .filter(|pair| pair.as_rule() != RuleKind::Argument)
+        .map(|pair| pair.as_rule().describe())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        described,
+        [
+            "Script",
+            "Statement",
+            "Command",
+            "Identifier",
+            "Arguments",
+            "literal strings",
+            "literal number",
+            "EOI"
+        ]
+    );
+
+    println!("ok");
+    Ok(())
+}
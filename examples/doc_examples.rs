@@ -0,0 +1,32 @@
+//! Testing the examples written in the docs of the grammar with `doc_examples`.
+//!
+//! The rules of `documented.pest` note inputs they match (`/// example: "@cmd(1, 2)"`) and inputs they must not
+//! (`/// counterexample: "cmd(1, 2)"`). Each becomes a generated test, e.g. `Command_example_1`, which run with
+//! `cargo test --example doc_examples`; `RULE_DOCS` keeps the marker lines like any other doc line.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(
+    grammar = "examples/documented.pest",
+    interface = "ParserInterface",
+    generate = "docs",
+    doc_examples = "true"
+)]
+pub struct LanguageParser;
+
+rule_structs!(ParserInterface);
+
+fn main() {
+    assert_eq!(
+        doc_of(RuleKind::Number),
+        Some("example: \"42\"\nexample: \"007\"\ncounterexample: \"4 2\"")
+    );
+    println!("ok");
+}
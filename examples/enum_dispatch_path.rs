@@ -0,0 +1,57 @@
+//! Emitting `enum_dispatch` through an aliased crate or a re-export with `enum_dispatch_path`.
+//!
+//! `enum_dispatch` is not in scope under its own name: the crate is renamed with `extern crate`, and the
+//! interface is registered through a re-export of it, both resolve to the same attribute.
+
+extern crate enum_dispatch as dispatch_alias;
+
+use anyhow::Result;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+/// A crate re-exporting `enum_dispatch`, e.g. a workspace prelude.
+mod reexported {
+    pub use dispatch_alias::enum_dispatch;
+}
+
+#[reexported::enum_dispatch]
+pub trait ParserInterface {
+    fn describe(&self) -> &'static str {
+        "other"
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    enum_dispatch_path = "dispatch_alias"
+)]
+pub struct LanguageParser;
+
+impl ParserInterface for Number {
+    fn describe(&self) -> &'static str {
+        "number"
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Strings,
+    Identifier
+);
+
+fn main() -> Result<()> {
+    let pair = LanguageParser::parse(Rule::Argument(Argument), "42")?
+        .next()
+        .unwrap();
+    let described = pair
+        .into_inner()
+        .map(|pair| pair.as_rule().describe())
+        .collect::<Vec<_>>();
+    assert_eq!(described, ["number"]);
+
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,111 @@
+//! Threading an error type of the project through the generated helpers with `error_type`, without `anyhow` or
+//! `Box<dyn Error>`.
+//!
+//! `walk_into()` collects the `ScriptError`s of the fallible `walk_method`, and `RuleKind::parse()` and
+//! `parse_rule()` return a `ScriptError` too, converted from the error of `pest` with the `From` impl the
+//! expansion asserts, see `tests/ui/error_type_not_from_parse_error.rs`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::iterators::Pair;
+use std::fmt;
+
+#[macro_use]
+mod common;
+
+#[derive(Debug)]
+pub enum ScriptError {
+    Syntax(Box<pest::error::Error<Rule>>),
+    UnknownCommand(String),
+    OutOfRange(String),
+}
+
+impl From<pest::error::Error<Rule>> for ScriptError {
+    fn from(error: pest::error::Error<Rule>) -> Self {
+        Self::Syntax(Box::new(error))
+    }
+}
+
+impl fmt::Display for ScriptError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Syntax(error) => write!(f, "syntax error: {error}"),
+            Self::UnknownCommand(name) => write!(f, "unknown command `{name}`"),
+            Self::OutOfRange(number) => write!(f, "`{number}` is out of range"),
+        }
+    }
+}
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn check(&mut self, _pair: &Pair<'_, Rule>) -> Result<(), ScriptError> {
+        Ok(())
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    walk_method = "check",
+    recover = "true",
+    error_type = "crate::ScriptError",
+    generate = "parse, parse_rule"
+)]
+pub struct LanguageParser;
+
+/// checks the commands and numbers of a script.
+struct Checker;
+
+impl ParserInterface for Checker {
+    fn check(&mut self, pair: &Pair<'_, Rule>) -> Result<(), ScriptError> {
+        match pair.as_rule().kind() {
+            RuleKind::Command => {
+                let name = pair.clone().into_inner().next().map(|name| name.as_str());
+                match name {
+                    Some("print" | "exit") => Ok(()),
+                    name => Err(ScriptError::UnknownCommand(
+                        name.unwrap_or_default().to_string(),
+                    )),
+                }
+            }
+            RuleKind::Number => match pair.as_str().parse::<u8>() {
+                Ok(_) => Ok(()),
+                Err(_) => Err(ScriptError::OutOfRange(pair.as_str().to_string())),
+            },
+            _ => Ok(()),
+        }
+    }
+}
+
+rule_structs!(ParserInterface);
+
+/// parses and checks `input`, with `?` on the parse error since both helpers return a `ScriptError`.
+fn check_script(input: &str) -> Result<Vec<String>, ScriptError> {
+    let pairs = RuleKind::Script.parse(input)?;
+    let errors = walk_into(pairs, &mut Checker);
+    Ok(errors
+        .into_iter()
+        .map(|(span, error)| format!("{}: {error}", span.as_str()))
+        .collect())
+}
+
+fn main() -> Result<(), ScriptError> {
+    assert_eq!(check_script("@print(1); @exit(0);")?, Vec::<String>::new());
+    assert_eq!(
+        check_script("@print(256); @jump(1); @exit(300);")?,
+        [
+            "256: `256` is out of range",
+            "@jump(1): unknown command `jump`",
+            "300: `300` is out of range",
+        ]
+    );
+
+    let error = check_script("@print(1)").unwrap_err();
+    assert!(matches!(error, ScriptError::Syntax(_)), "{error}");
+    let error = LanguageParser::parse_rule::<Number>("x").unwrap_err();
+    assert!(error.to_string().starts_with("syntax error: "), "{error}");
+    assert_eq!(LanguageParser::parse_rule::<Number>("42")?.as_str(), "42");
+
+    println!("ok");
+    Ok(())
+}
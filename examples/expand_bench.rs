@@ -0,0 +1,20 @@
+//! Expansion benchmark of `#[pest_parser]` on synthetic grammars.
+//!
+//! The measurement happens while this example is compiled, so force a rebuild to measure again:
+//!
+//! ```sh
+//! touch examples/expand_bench.rs
+//! cargo run --example expand_bench --features expand-bench
+//! ```
+//!
+//! The results are written to `target/expand-bench.json` as well, so they can be tracked over time.
+
+enum_dispatch_pest_parser::expand_bench!(
+    rules = "10, 100, 500, 1000",
+    keywords = "1000, 2000",
+    output = "target/expand-bench.json"
+);
+
+fn main() {
+    print!("{EXPAND_BENCH}");
+}
@@ -0,0 +1,79 @@
+//! Routing the rules without an impl of their own to a catch-all with `fallback`.
+//!
+//! While an interface is adopted rule by rule, the rules listed in `unhandled` forward to `CatchAll`, which is
+//! built from the kind of the rule on every call. `Evaluate` is midway: `Number` has a real impl, the other rules
+//! still fall back. `Describe` falls back for every rule (`unhandled = "*"`).
+
+use anyhow::{bail, Result};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::{pest_dispatch, pest_rules};
+use pest::Parser;
+
+#[pest_rules(grammar = "examples/grammar.pest", generate = "name")]
+pub struct LanguageParser;
+
+#[pest_dispatch(
+    rules = "LanguageParser",
+    fallback = "CatchAll",
+    unhandled = "EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Strings, Identifier"
+)]
+pub trait Evaluate {
+    fn evaluate(&self, text: &str) -> Result<i64>;
+}
+
+#[pest_dispatch(rules = "LanguageParser", fallback = "CatchAll", unhandled = "*")]
+pub trait Describe {
+    fn describe(&self) -> String;
+}
+
+/// Stands in for the rules which are not implemented yet.
+pub struct CatchAll {
+    kind: RuleKind,
+}
+
+impl From<RuleKind> for CatchAll {
+    fn from(kind: RuleKind) -> Self {
+        Self { kind }
+    }
+}
+
+impl Evaluate for CatchAll {
+    fn evaluate(&self, text: &str) -> Result<i64> {
+        bail!(
+            "cannot evaluate `{text}`: `{}` is not implemented yet",
+            self.kind.name()
+        )
+    }
+}
+
+impl Describe for CatchAll {
+    fn describe(&self) -> String {
+        format!("<{}>", self.kind.name())
+    }
+}
+
+impl Evaluate for Number {
+    fn evaluate(&self, text: &str) -> Result<i64> {
+        Ok(text.parse()?)
+    }
+}
+
+fn main() -> Result<()> {
+    let pair = LanguageParser::parse(Rule::Argument(Argument), "42")?
+        .next()
+        .unwrap();
+    let error = pair.as_rule().evaluate(pair.as_str()).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "cannot evaluate `42`: `Argument` is not implemented yet"
+    );
+    assert_eq!(pair.as_rule().describe(), "<Argument>");
+
+    let number = pair.into_inner().next().unwrap();
+    assert_eq!(number.as_rule().evaluate(number.as_str())?, 42);
+    assert_eq!(number.as_rule().describe(), "<Number>");
+    assert_eq!(Identifier.describe(), "<Identifier>");
+
+    println!("ok");
+    Ok(())
+}
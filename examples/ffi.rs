@@ -0,0 +1,45 @@
+//! Numbering the rules for FFI with `generate = "ffi"`.
+//!
+//! `to_u32()` is the position of a rule in definition order, which is the discriminant of its variant. With
+//! `#[repr(u32)]` on `enum Rule`, the discriminant is stored as a `u32` at the start of every `Rule`, so it is
+//! read back here from the memory of each rule and checked against `to_u32()`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    rule_attr = "#[repr(u32)]",
+    generate = "iter, name, ffi"
+)]
+pub struct LanguageParser;
+
+/// the discriminant of `rule`, as laid out by `#[repr(u32)]`.
+fn discriminant(rule: &Rule) -> u32 {
+    // SAFETY: a `#[repr(u32)]` enum starts with its `u32` discriminant, whatever its variant.
+    unsafe { *(rule as *const Rule).cast::<u32>() }
+}
+
+fn main() {
+    for kind in RuleKind::iter() {
+        let rule = Rule::from(kind);
+        assert_eq!(discriminant(&rule), kind.to_u32(), "{kind:?}");
+        assert_eq!(u32::from(rule), kind.to_u32());
+        assert_eq!(u32::from(kind), kind.index() as u32);
+        assert_eq!(RuleKind::from_u32(kind.to_u32()), Some(kind));
+        assert_eq!(Rule::try_from(kind.to_u32()), Ok(rule));
+    }
+
+    let count = Rule::RULE_COUNT as u32;
+    assert_eq!(RuleKind::from_u32(count), None);
+    let error = RuleKind::try_from(count).unwrap_err();
+    assert_eq!(error.input, count.to_string());
+    assert_eq!(error.suggestion, None);
+    assert_eq!(error.to_string(), format!("unknown rule `{count}`"));
+
+    println!("ok");
+}
@@ -0,0 +1,62 @@
+//! The literals a rule can start with, from `Rule::first_literals()`, e.g. to suggest what can come next.
+//!
+//! The FIRST sets are looked up through the referenced rules (`statement` through `assignment` and `call`), the
+//! alternatives (`modifier`) and past an optional prefix (`modifier?`). A rule which can also start with a
+//! character class (`value` with `number`) or match nothing (`program`) is not complete.
+
+use enum_dispatch::enum_dispatch;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface")]
+    pub struct StatementParser;
+
+    grammar {
+        WHITESPACE = _{ " " }
+        program = { SOI ~ statement* ~ EOI }
+        statement = { modifier? ~ (assignment | call) ~ ";" }
+        modifier = { "pub" | "static" }
+        assignment = { "let" ~ ident ~ "=" ~ value }
+        call = { "@" ~ ident ~ "(" ~ (value ~ ("," ~ value)*)? ~ ")" }
+        value = { number | "true" | "false" | "(" ~ value ~ ")" }
+        number = @{ ASCII_DIGIT+ }
+        ident = @{ ASCII_ALPHA+ }
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, program, statement, modifier, assignment, call, value, number,
+    ident
+);
+
+fn first(kind: RuleKind) -> (&'static [&'static str], bool) {
+    let rule = Rule::from(kind);
+    (rule.first_literals(), rule.first_literals_complete())
+}
+
+fn main() {
+    // alternation.
+    assert_eq!(first(RuleKind::modifier), (&["pub", "static"][..], true));
+    // an optional prefix, then the alternatives of nested rules.
+    assert_eq!(
+        first(RuleKind::statement),
+        (&["pub", "static", "let", "@"][..], true)
+    );
+    // nesting through `statement*`, which can match nothing, like `SOI` and `EOI`.
+    assert_eq!(
+        first(RuleKind::program),
+        (&["pub", "static", "let", "@"][..], false)
+    );
+    // `number` starts with a character class, which is left out.
+    assert_eq!(first(RuleKind::value), (&["true", "false", "("][..], false));
+    assert_eq!(first(RuleKind::number), (&[][..], false));
+    assert_eq!(first(RuleKind::WHITESPACE), (&[" "][..], true));
+    assert_eq!(first(RuleKind::EOI), (&[][..], false));
+
+    println!("ok");
+}
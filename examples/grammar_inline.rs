@@ -0,0 +1,50 @@
+//! Giving the grammar inline with `grammar_inline`, next to the parser, instead of in a file.
+//!
+//! The source is parsed and hooked like the one of a file; its doc comments are the docs of the rules.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn value(&self, _text: &str) -> Option<u64> {
+        None
+    }
+}
+
+#[pest_parser(
+    grammar_inline = r#"
+/// A comma separated list of numbers.
+list   = { SOI ~ number ~ ("," ~ number)* ~ EOI }
+number = @{ ASCII_DIGIT+ }
+"#,
+    interface = "ParserInterface",
+    generate = "docs"
+)]
+pub struct ListParser;
+
+impl ParserInterface for number {
+    fn value(&self, text: &str) -> Option<u64> {
+        text.parse().ok()
+    }
+}
+
+impl ParserInterface for list {}
+impl ParserInterface for EOI {}
+
+fn main() -> Result<()> {
+    let pairs = ListParser::parse(Rule::list(list), "1,20,300")?;
+    let sum = pairs
+        .flatten()
+        .filter_map(|pair| pair.as_rule().value(pair.as_str()))
+        .sum::<u64>();
+    assert_eq!(sum, 321);
+    assert_eq!(
+        doc_of(RuleKind::list),
+        Some("A comma separated list of numbers.")
+    );
+    println!("{sum}");
+    Ok(())
+}
@@ -0,0 +1,74 @@
+//! Handling several rules with one struct through `group`.
+//!
+//! `DecimalNumber`, `HexNumber` and `BinaryNumber` all carry `Number`, whose single impl handles the three of
+//! them, while `RuleKind` still tells them apart. `assert_impls` checks that every struct `Rule` carries, the group
+//! included, has an impl of the interface.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+use std::cell::RefCell;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    /// Records the value of a number literal.
+    fn record(&self, _text: &str, _seen: &RefCell<Vec<u64>>) -> Result<()> {
+        Ok(())
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/group.pest",
+    interface = "ParserInterface",
+    assert_impls = "true",
+    group(Number = "DecimalNumber, HexNumber, BinaryNumber")
+)]
+pub struct NumberParser;
+
+impl ParserInterface for Number {
+    fn record(&self, text: &str, seen: &RefCell<Vec<u64>>) -> Result<()> {
+        let value = match text.get(..2) {
+            Some("0x") => u64::from_str_radix(&text[2..], 16)?,
+            Some("0b") => u64::from_str_radix(&text[2..], 2)?,
+            _ => text.parse()?,
+        };
+        seen.borrow_mut().push(value);
+        Ok(())
+    }
+}
+
+impl ParserInterface for Numbers {}
+impl ParserInterface for EOI {}
+impl ParserInterface for WHITESPACE {}
+
+fn main() -> Result<()> {
+    let seen = RefCell::new(Vec::new());
+    let pairs = NumberParser::parse(Rule::Numbers(Numbers), "42 0x2a 0b101010")?;
+    let mut kinds = Vec::new();
+    for pair in pairs.flatten() {
+        pair.as_rule().record(pair.as_str(), &seen)?;
+        kinds.push(pair.as_rule().kind());
+    }
+    assert_eq!(seen.into_inner(), [42, 42, 42]);
+    assert_eq!(
+        kinds,
+        [
+            RuleKind::Numbers,
+            RuleKind::DecimalNumber,
+            RuleKind::HexNumber,
+            RuleKind::BinaryNumber,
+            RuleKind::EOI,
+        ]
+    );
+
+    assert_eq!(Rule::HexNumber(Number), RuleKind::HexNumber);
+    assert_ne!(Rule::HexNumber(Number), Rule::BinaryNumber(Number));
+    assert_eq!(
+        RuleKind::from_name("BinaryNumber"),
+        Some(RuleKind::BinaryNumber)
+    );
+    assert_eq!(RuleKind::BinaryNumber.name(), "BinaryNumber");
+    println!("ok");
+    Ok(())
+}
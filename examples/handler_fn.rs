@@ -0,0 +1,105 @@
+//! Routing the rules to one function with `handler_fn`.
+//!
+//! Instead of one impl per rule struct, `#[pest_dispatch]` implements the interface by calling a function with the
+//! kind of the rule, e.g. to keep a `match` over the kinds while moving to the interface. `Handle` routes every
+//! rule, `Describe` only the listed ones and is implemented by hand for the others.
+
+use anyhow::{bail, Result};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::{pest_dispatch, pest_rules};
+use pest::Parser;
+use std::cell::RefCell;
+
+#[pest_rules(grammar = "examples/grammar.pest")]
+pub struct LanguageParser;
+
+#[pest_dispatch(rules = "LanguageParser", handler_fn = "crate::handle_rule")]
+pub trait Handle {
+    fn handle(&self, text: &str) -> Result<()>;
+}
+
+#[pest_dispatch(
+    rules = "LanguageParser",
+    handler_fn = "describe_rule",
+    handler_method = "describe",
+    handler_rules = "Number, Strings"
+)]
+pub trait Describe {
+    fn describe(&self) -> String;
+
+    fn is_literal(&self) -> bool {
+        false
+    }
+}
+
+thread_local! {
+    static HANDLED: RefCell<Vec<(RuleKind, String)>> = const { RefCell::new(Vec::new()) };
+}
+
+fn handle_rule(kind: RuleKind, text: &str) -> Result<()> {
+    if kind == RuleKind::Identifier && text == "panic" {
+        bail!("`{text}` is reserved");
+    }
+    HANDLED.with_borrow_mut(|handled| handled.push((kind, text.to_string())));
+    Ok(())
+}
+
+fn describe_rule(kind: RuleKind) -> String {
+    format!("literal {kind:?}")
+}
+
+macro_rules! rule_structs {
+    ($($rule:ident),*) => {
+        $(impl Describe for $rule {
+            fn describe(&self) -> String {
+                format!("{:?}", RuleKind::$rule)
+            }
+        })*
+    };
+}
+
+rule_structs!(EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Identifier);
+
+fn main() -> Result<()> {
+    let pair = LanguageParser::parse(Rule::Command(Command), "@print(\"hi\", 42)")?
+        .next()
+        .unwrap();
+    for pair in pair.clone().into_inner().flatten() {
+        pair.as_rule().handle(pair.as_str())?;
+    }
+    let handled = HANDLED.with_borrow(|handled| handled.clone());
+    let kinds = handled.iter().map(|(kind, _)| *kind).collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            RuleKind::Identifier,
+            RuleKind::Arguments,
+            RuleKind::Argument,
+            RuleKind::Strings,
+            RuleKind::Argument,
+            RuleKind::Number
+        ]
+    );
+    assert_eq!(handled[0].1, "print");
+    assert!(Identifier.handle("panic").is_err());
+
+    let described = pair
+        .into_inner()
+        .flatten()
+        .map(|pair| pair.as_rule().describe())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        described,
+        [
+            "Identifier",
+            "Arguments",
+            "Argument",
+            "literal Strings",
+            "Argument",
+            "literal Number"
+        ]
+    );
+
+    println!("ok");
+    Ok(())
+}
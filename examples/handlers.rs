@@ -0,0 +1,73 @@
+//! Implementing the interface from inherent methods with `#[pest_handler]`.
+//!
+//! A block on a rule struct handles that rule with its only method. A block on any other type handles the
+//! rules named by `#[rule(...)]` on its methods, which take the rule struct as their first argument.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::{pest_handler, pest_parser};
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn describe(&self, text: &str) -> Result<String> {
+        Ok(format!("<{text}>"))
+    }
+}
+
+#[pest_parser(grammar = "examples/grammar.pest", interface = "ParserInterface")]
+pub struct LanguageParser;
+
+#[pest_handler(rule = "Command", interface = "ParserInterface", method = "describe")]
+impl Command {
+    fn handle(&self, text: &str) -> Result<String> {
+        Ok(format!("command {}", &text[1..text.find('(').unwrap()]))
+    }
+}
+
+struct Literals;
+
+#[pest_handler(interface = "ParserInterface", method = "describe")]
+impl Literals {
+    #[rule(Number)]
+    fn number(_rule: &Number, text: &str) -> Result<String> {
+        Ok(format!("number {}", text.parse::<u64>()?))
+    }
+
+    #[rule(Strings, Identifier)]
+    fn text(rule: &impl RuleStruct, text: &str) -> Result<String> {
+        Ok(format!("{rule:?} {}", Self::unquote(text)))
+    }
+
+    /// not a handler, only used by them.
+    fn unquote(text: &str) -> &str {
+        text.trim_matches('"')
+    }
+}
+
+rule_structs!(ParserInterface: EOI, WHITESPACE, Script, Statement, Arguments, Argument);
+
+fn main() -> Result<()> {
+    let pairs = LanguageParser::parse(Rule::Command(Command), "@print(\"hello\", 42)")?;
+    let described = pairs
+        .flatten()
+        .map(|pair| pair.as_rule().describe(pair.as_str()))
+        .collect::<Result<Vec<_>>>()?;
+    assert_eq!(
+        described,
+        [
+            "command print",
+            "Identifier print",
+            "<\"hello\", 42>",
+            "<\"hello\">",
+            "Strings hello",
+            "<42>",
+            "number 42",
+        ]
+    );
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,60 @@
+//! Logging the duration of every parse with `hooked_parser`.
+//!
+//! `pest::Parser` is implemented by the macro on top of `pest`'s parser, calling the `ParseHooks` of the
+//! parser around each parse, so no call site has to be wrapped.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::{
+    error::Error,
+    iterators::{Pair, Pairs},
+    Parser,
+};
+use std::time::Instant;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn parse_rule(&self, _pair: &Pair<'_, Rule>) {}
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    hooked_parser = "true"
+)]
+pub struct LanguageParser;
+
+impl ParseHooks for LanguageParser {
+    type State = Instant;
+
+    fn pre_parse(_rule: Rule, _input: &str) -> Instant {
+        Instant::now()
+    }
+
+    fn post_parse<'i>(
+        start: Instant,
+        rule: Rule,
+        input: &'i str,
+        result: &Result<Pairs<'i, Rule>, Error<Rule>>,
+    ) {
+        let outcome = if result.is_ok() { "ok" } else { "failed" };
+        println!(
+            "parsed {} bytes as `{}` in {:?}: {outcome}",
+            input.len(),
+            rule.kind().name(),
+            start.elapsed()
+        );
+    }
+}
+
+rule_structs!(ParserInterface);
+
+fn main() -> Result<()> {
+    LanguageParser::parse(Rule::Script(Script), "@print(\"hello\", 42);")?;
+    assert!(LanguageParser::parse(Rule::Number(Number), "forty-two").is_err());
+    Ok(())
+}
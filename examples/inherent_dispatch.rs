@@ -0,0 +1,67 @@
+//! Calling the interface on `Rule` without importing it, with `inherent_dispatch`.
+//!
+//! The interface lives in a module of its own, and `main` never imports it: `#[pest_dispatch]` copies its methods
+//! into an inherent impl of `Rule`, which forward to the dispatched call and are completed like any method of
+//! `Rule`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_rules;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+#[pest_rules(grammar = "examples/grammar.pest")]
+pub struct LanguageParser;
+
+mod describe {
+    use super::*;
+    use enum_dispatch_pest_parser::pest_dispatch;
+    use std::fmt::Display;
+
+    #[pest_dispatch(rules = "super::LanguageParser", inherent_dispatch = "true")]
+    pub trait Describe {
+        /// Describes the rule.
+        fn describe(&self) -> &'static str {
+            "other"
+        }
+
+        /// Describes the rule after `prefix`.
+        fn label<P: Display>(&self, prefix: P) -> String {
+            format!("{prefix}: {}", self.describe())
+        }
+    }
+
+    impl Describe for Number {
+        fn describe(&self) -> &'static str {
+            "number"
+        }
+    }
+
+    impl Describe for Strings {
+        fn describe(&self) -> &'static str {
+            "string"
+        }
+    }
+
+    rule_structs!(
+        Describe: EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Identifier
+    );
+}
+
+fn main() -> anyhow::Result<()> {
+    let described = LanguageParser::parse(Rule::Arguments(Arguments), "42, \"hi\", x")?
+        .flatten()
+        .map(|pair| pair.as_rule().describe())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        described,
+        ["other", "other", "number", "other", "string", "other", "other"]
+    );
+
+    assert_eq!(Rule::Number(Number).label(1), "1: number");
+    assert_eq!(Rule::Identifier(Identifier).label("x"), "x: other");
+
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,63 @@
+//! The JSON grammar of `pest_grammars` through `#[pest_parser]`.
+//!
+//! `examples/json.pest` is vendored from `pest_grammars` unchanged. Every pair of a sample document is
+//! dispatched to a trivial counting interface, and the counts are checked against the document. The
+//! grammar has a rule named `inner`, which `enum_dispatch` cannot handle, so `struct_style = "enum"` is used.
+
+use anyhow::Result;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+use std::collections::BTreeMap;
+
+pub trait Counter {
+    fn count(&self, counts: &mut BTreeMap<RuleStruct, usize>);
+}
+
+#[pest_parser(
+    grammar = "examples/json.pest",
+    interface = "Counter",
+    struct_style = "enum"
+)]
+pub struct JsonParser;
+
+impl Counter for RuleStruct {
+    fn count(&self, counts: &mut BTreeMap<RuleStruct, usize>) {
+        *counts.entry(*self).or_default() += 1;
+    }
+}
+
+const DOCUMENT: &str = r#"{
+    "name": "pest",
+    "tags": ["parser", "peg", "\u00e9\n"],
+    "stars": -4.5e3,
+    "nested": { "empty": {}, "list": [] },
+    "ok": true,
+    "none": null
+}"#;
+
+fn main() -> Result<()> {
+    let pairs = JsonParser::parse(Rule::json(RuleStruct::json), DOCUMENT)?;
+    let mut counts = BTreeMap::new();
+    for pair in pairs.flatten() {
+        pair.as_rule().count(&mut counts);
+    }
+    println!("{counts:?}");
+
+    // `string`, `number` and their inner rules are atomic, so only the outermost pairs are produced.
+    let expected = [
+        (RuleStruct::EOI, 1),
+        (RuleStruct::json, 1),
+        (RuleStruct::object, 3),
+        (RuleStruct::pair, 8),
+        (RuleStruct::array, 2),
+        (RuleStruct::value, 12),
+        (RuleStruct::string, 12),
+        (RuleStruct::number, 1),
+        (RuleStruct::bool, 1),
+        (RuleStruct::null, 1),
+    ];
+    assert_eq!(counts, BTreeMap::from(expected));
+
+    assert!(JsonParser::parse(Rule::json(RuleStruct::json), r#"{"unterminated": "#).is_err());
+    Ok(())
+}
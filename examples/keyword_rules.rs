@@ -0,0 +1,57 @@
+//! Rules named like Rust keywords.
+//!
+//! `pest` emits every rule as a raw identifier (`r#statement`). The rule structs and the variants of `Rule` and
+//! `RuleKind` are bare (`statement`), but for the rules named after a keyword, which keep the prefix (`r#match`).
+
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn describe(&self) -> &'static str {
+        "other"
+    }
+}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface")]
+    pub struct KeywordParser;
+
+    grammar {
+        WHITESPACE = _{ " " }
+        statement = { SOI ~ match ~ ";" ~ EOI }
+        match = { "match" ~ ident }
+        ident = @{ ASCII_ALPHA+ }
+    }
+}
+
+impl ParserInterface for statement {
+    fn describe(&self) -> &'static str {
+        "statement"
+    }
+}
+
+impl ParserInterface for r#match {
+    fn describe(&self) -> &'static str {
+        "match"
+    }
+}
+
+impl ParserInterface for ident {}
+impl ParserInterface for WHITESPACE {}
+impl ParserInterface for EOI {}
+
+fn main() -> anyhow::Result<()> {
+    assert_eq!(RuleKind::statement.name(), "statement");
+    assert_eq!(RuleKind::r#match.name(), "match");
+    assert_eq!(RuleKind::from_name("match"), Some(RuleKind::r#match));
+
+    let described = KeywordParser::parse(Rule::statement(statement), "match x;")?
+        .flatten()
+        .map(|pair| pair.as_rule().describe())
+        .collect::<Vec<_>>();
+    assert_eq!(described, ["statement", "match", "other", "other"]);
+
+    println!("ok");
+    Ok(())
+}
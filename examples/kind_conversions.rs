@@ -0,0 +1,78 @@
+//! Converting between `Rule` and `RuleKind` with `From`, in both directions.
+//!
+//! `Rule::from(kind)` builds the payload of the variant (the unit struct, the value of `RuleStruct` or the default of
+//! an overriding type), `RuleKind::from(rule)` is `rule.kind()`. Every kind is round-tripped through both parsers,
+//! one per struct style, checked against `kind()` and `index()`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait Describe {
+    fn describe(&self) -> &'static str {
+        "rule"
+    }
+}
+
+/// the payload of `Strings` in `StructsParser`, built with `Default`.
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Quoted;
+
+impl Describe for Quoted {
+    fn describe(&self) -> &'static str {
+        "quoted"
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "Describe",
+    namespaced = "true",
+    override(Strings = "crate::Quoted")
+)]
+pub struct StructsParser;
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "Describe",
+    namespaced = "true",
+    struct_style = "enum"
+)]
+pub struct EnumParser;
+
+rule_structs!(
+    Describe for structs_parser: EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument,
+    Number, Identifier
+);
+
+impl Describe for enum_parser::RuleStruct {}
+
+fn main() {
+    use structs_parser::{Rule, RuleKind};
+    for (index, kind) in RuleKind::ALL.into_iter().enumerate() {
+        let rule = Rule::from(kind);
+        assert_eq!(RuleKind::from(rule), kind);
+        assert_eq!(rule.kind(), kind);
+        assert_eq!(Rule::from(RuleKind::from(rule)), rule);
+        assert_eq!(RuleKind::from(rule).index(), index);
+    }
+    let strings: RuleKind = Rule::from(RuleKind::Strings).into();
+    assert_eq!(strings, RuleKind::Strings);
+    assert_eq!(Rule::from(strings).describe(), "quoted");
+
+    for (index, kind) in enum_parser::RuleKind::ALL.into_iter().enumerate() {
+        let rule = enum_parser::Rule::from(kind);
+        assert_eq!(enum_parser::RuleKind::from(rule), kind);
+        assert_eq!(rule.kind(), kind);
+        assert_eq!(
+            enum_parser::Rule::from(enum_parser::RuleKind::from(rule)),
+            rule
+        );
+        assert_eq!(enum_parser::RuleKind::from(rule).index(), index);
+    }
+
+    println!("ok");
+}
@@ -0,0 +1,47 @@
+//! Parsing with the rule of a `RuleKind` through `RuleKind::parse()`.
+//!
+//! The kind may come from anywhere at runtime, e.g. a rule name looked up with `RuleKind::from_name()`.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn describe(&self) -> &'static str {
+        "rule"
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    generate = "name, parse"
+)]
+pub struct LanguageParser;
+
+rule_structs!(ParserInterface);
+
+fn main() -> Result<()> {
+    let pair = RuleKind::Command
+        .parse("@print(\"hello\", 42)")?
+        .next()
+        .unwrap();
+    assert_eq!(pair.as_rule(), RuleKind::Command);
+    assert_eq!(pair.as_rule().describe(), "rule");
+    let children = pair
+        .into_inner()
+        .map(|pair| pair.as_rule().kind())
+        .collect::<Vec<_>>();
+    assert_eq!(children, [RuleKind::Identifier, RuleKind::Arguments]);
+
+    let number = RuleKind::from_name("Number").unwrap();
+    assert_eq!(number.parse("42")?.as_str(), "42");
+    let error = number.parse("forty-two").unwrap_err();
+    assert_eq!(error.line_col, pest::error::LineColLocation::Pos((1, 1)));
+    println!("ok");
+    Ok(())
+}
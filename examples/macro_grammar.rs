@@ -0,0 +1,78 @@
+//! A grammar generated by a macro, given to `pest_parser_tokens!`.
+//!
+//! `keyword_language!` builds the grammar from a list of keywords. The attribute arguments have to be
+//! string literals, so a macro cannot expand into `grammar = "..."`, but it can expand into the tokens of
+//! the grammar.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use pest::{iterators::Pair, Parser};
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    /// Describes a token of the input.
+    fn describe(&self, pair: &Pair<'_, Rule>) -> String {
+        format!("`{}`", pair.as_str())
+    }
+}
+
+macro_rules! keyword_language {
+    ($parser:ident: $($keyword:literal),*) => {
+        enum_dispatch_pest_parser::pest_parser_tokens! {
+            #[pest_parser(interface = "ParserInterface", generate = "iter, name, docs")]
+            pub struct $parser;
+
+            grammar {
+                WHITESPACE = _{ " " }
+                // line comments are dropped, doc comments are kept.
+                /// A line of keywords and words.
+                line = { SOI ~ (keyword | word)* ~ EOI }
+                /// One of the keywords of the language.
+                keyword = @{ ($($keyword)|*) ~ !('a'..'z') }
+                word = @{ ('a'..'z')+ }
+            }
+        }
+    };
+}
+
+keyword_language!(KeywordParser: "let", "fn", "if");
+
+impl ParserInterface for keyword {
+    fn describe(&self, pair: &Pair<'_, Rule>) -> String {
+        format!("keyword `{}`", pair.as_str())
+    }
+}
+
+impl ParserInterface for EOI {}
+impl ParserInterface for WHITESPACE {}
+impl ParserInterface for line {}
+impl ParserInterface for word {}
+
+fn main() -> Result<()> {
+    let pairs = KeywordParser::parse(Rule::line(line), "let iffy if fn x")?
+        .next()
+        .unwrap();
+    let described = pairs
+        .into_inner()
+        .filter(|pair| pair.as_rule() != Rule::EOI(EOI))
+        .map(|pair| pair.as_rule().describe(&pair))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        described,
+        [
+            "keyword `let`",
+            "`iffy`",
+            "keyword `if`",
+            "keyword `fn`",
+            "`x`"
+        ]
+    );
+
+    assert_eq!(
+        doc_of(RuleKind::from_name("keyword").unwrap()),
+        Some("One of the keywords of the language.")
+    );
+    assert_eq!(doc_of(RuleKind::from_name("word").unwrap()), None);
+    println!("ok");
+    Ok(())
+}
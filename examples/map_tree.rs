@@ -0,0 +1,110 @@
+//! Collecting what a function returns for each pair with `map_pairs()` and `map_tree()`.
+//!
+//! `map_tree()` turns an arithmetic expression into a tree of `Node`s, which is folded into an `Ast`; the shape of
+//! both is checked, as well as the errors of the function and the pairs `map_pairs()` returns with and without
+//! `EOI`.
+
+use anyhow::{bail, Result};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_rules;
+use pest::iterators::Pair;
+use pest::Parser;
+
+#[pest_rules(grammar = "examples/expr.pest", generate = "map")]
+pub struct CalcParser;
+
+#[derive(Debug, PartialEq)]
+enum Node {
+    Sum,
+    Product,
+    Op(char),
+    Number(i64),
+}
+
+#[derive(Debug, PartialEq)]
+enum Ast {
+    Number(i64),
+    Binary(Box<Ast>, char, Box<Ast>),
+}
+
+fn node(kind: RuleKind, pair: Pair<'_, Rule>) -> Result<Node> {
+    Ok(match kind {
+        RuleKind::Expr => Node::Sum,
+        RuleKind::Term => Node::Product,
+        RuleKind::AddOp | RuleKind::MulOp => Node::Op(pair.as_str().chars().next().unwrap()),
+        RuleKind::Number => Node::Number(pair.as_str().parse()?),
+        kind => bail!("unexpected {kind:?}"),
+    })
+}
+
+/// folds the operands and operators of a sum or product from the left.
+fn ast(tree: Tree<Node>) -> Ast {
+    match tree.value {
+        Node::Number(value) => Ast::Number(value),
+        Node::Sum | Node::Product => {
+            let mut children = tree.children.into_iter();
+            let mut lhs = ast(children.next().unwrap());
+            while let (Some(op), Some(rhs)) = (children.next(), children.next()) {
+                let Node::Op(op) = op.value else {
+                    unreachable!("operands and operators alternate")
+                };
+                lhs = Ast::Binary(Box::new(lhs), op, Box::new(ast(rhs)));
+            }
+            lhs
+        }
+        Node::Op(_) => unreachable!("operators are folded with their operands"),
+    }
+}
+
+fn parse(input: &str) -> Result<Ast> {
+    let calc = CalcParser::parse(Rule::Calc(Calc), input)?.next().unwrap();
+    let mut trees = map_tree(calc.into_inner(), node)?;
+    assert_eq!(trees.len(), 1, "`EOI` is skipped");
+    Ok(ast(trees.remove(0)))
+}
+
+fn main() -> Result<()> {
+    let calc = CalcParser::parse(Rule::Calc(Calc), "1 + 2 * 3")?
+        .next()
+        .unwrap();
+    let trees = map_tree(calc.clone().into_inner(), node)?;
+    let sum = &trees[0];
+    assert_eq!(sum.value, Node::Sum);
+    let values = sum
+        .children
+        .iter()
+        .map(|tree| &tree.value)
+        .collect::<Vec<_>>();
+    assert_eq!(values, [&Node::Product, &Node::Op('+'), &Node::Product]);
+    assert_eq!(sum.children[0].children.len(), 1);
+    assert_eq!(sum.children[2].children.len(), 3);
+
+    let number = |value| Box::new(Ast::Number(value));
+    assert_eq!(
+        parse("1 + 2 * 3")?,
+        Ast::Binary(
+            number(1),
+            '+',
+            Box::new(Ast::Binary(number(2), '*', number(3)))
+        )
+    );
+    assert_eq!(
+        parse("(1 - 2) - 3")?,
+        Ast::Binary(
+            Box::new(Ast::Binary(number(1), '-', number(2))),
+            '-',
+            number(3)
+        )
+    );
+
+    let error = parse("99999999999999999999").unwrap_err();
+    assert_eq!(error.to_string(), "number too large to fit in target type");
+
+    let top = map_pairs(calc.clone().into_inner(), |kind, _| Ok::<_, ()>(kind)).unwrap();
+    assert_eq!(top, [RuleKind::Expr]);
+    let top = map_pairs_with(calc.into_inner(), true, |kind, _| Ok::<_, ()>(kind)).unwrap();
+    assert_eq!(top, [RuleKind::Expr, RuleKind::EOI]);
+
+    println!("ok");
+    Ok(())
+}
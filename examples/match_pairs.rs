@@ -0,0 +1,114 @@
+//! Destructuring the children of pairs with the generated `match_pairs!`.
+//!
+//! The handlers live in their own module: `match_pairs!` only needs to be imported, and the children which
+//! are not the expected rules are reported as errors instead of panicking.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::{iterators::Pair, Parser};
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn parse_rule(&self, _pair: &Pair<'_, Rule>) {}
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    generate = "match_pairs"
+)]
+pub struct LanguageParser;
+
+rule_structs!(ParserInterface);
+
+mod handlers {
+    use crate::{match_pairs, Rule};
+    use anyhow::Result;
+    use pest::iterators::Pair;
+
+    /// Returns the name of a command and its arguments.
+    pub fn command(pair: &Pair<'_, Rule>) -> Result<(String, Vec<String>)> {
+        match_pairs!(pair => [Identifier as name, Arguments? as arguments]);
+        let arguments = match arguments {
+            Some(arguments) => self::arguments(&arguments)?,
+            None => Vec::new(),
+        };
+        Ok((name.as_str().to_string(), arguments))
+    }
+
+    pub fn arguments(pair: &Pair<'_, Rule>) -> Result<Vec<String>> {
+        match_pairs!(pair => [Argument* as arguments]);
+        Ok(arguments
+            .iter()
+            .map(|argument| argument.as_str().to_string())
+            .collect())
+    }
+
+    /// Expects the children of a command in the wrong order.
+    pub fn swapped(pair: &Pair<'_, Rule>) -> Result<()> {
+        match_pairs!(pair => [Arguments? as _arguments, Number as _name]);
+        Ok(())
+    }
+
+    /// Expects one child more than a command has.
+    pub fn too_many(pair: &Pair<'_, Rule>) -> Result<()> {
+        match_pairs!(pair => [Identifier as _name, Arguments as _arguments]);
+        Ok(())
+    }
+
+    /// Expects a single argument.
+    pub fn single(pair: &Pair<'_, Rule>) -> Result<()> {
+        match_pairs!(pair => [Argument as _argument]);
+        Ok(())
+    }
+}
+
+fn parse(rule: Rule, input: &str) -> Result<Pair<'_, Rule>> {
+    Ok(LanguageParser::parse(rule, input)?.next().unwrap())
+}
+
+fn main() -> Result<()> {
+    let command = Rule::Command(Command);
+    let arguments = Rule::Arguments(Arguments);
+
+    // exact match, and a `*` collecting the rest.
+    let (name, args) = handlers::command(&parse(command, "@print(1, \"a\")")?)?;
+    assert_eq!(
+        (name.as_str(), args),
+        ("print", vec!["1".to_string(), "\"a\"".to_string()])
+    );
+
+    // optional child absent.
+    let (name, args) = handlers::command(&parse(command, "@exit()")?)?;
+    assert_eq!((name.as_str(), args.len()), ("exit", 0));
+
+    // wrong order: the optional `Arguments` is skipped, then `Identifier` is found instead of `Number`.
+    let error = handlers::swapped(&parse(command, "@exit(0)")?).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected `Number`, found `Identifier` in `Command` at 1:2"
+    );
+
+    // too few children.
+    let error = handlers::too_many(&parse(command, "@exit()")?).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "expected `Arguments`, found the end of `Command` at 1:8"
+    );
+    let error = error.downcast::<MatchPairsError>()?;
+    assert_eq!(error.expected, Some(RuleKind::Arguments));
+    assert_eq!(error.found, None);
+
+    // a child left over.
+    let error = handlers::single(&parse(arguments, "1, 2")?).unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        "unexpected `Argument` after the last expected child of `Arguments` at 1:4"
+    );
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,72 @@
+//! Bounding the depth of `parse_tree()` and `map_tree()` with `max_depth`.
+//!
+//! Both recurse once per level of the parse tree. With `max_depth`, a pair nested deeper is reported as an error
+//! instead of being descended into: `parse_tree()` fails with a `pest` error at the pair, `map_tree()` with a
+//! `DepthError` converted into the error of the mapping function.
+
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", generate = "tree, map", max_depth = "8")]
+    pub struct NestedParser;
+
+    grammar {
+        document = { SOI ~ group ~ EOI }
+        group = { "(" ~ group? ~ ")" }
+    }
+}
+
+impl ParserInterface for document {}
+impl ParserInterface for group {}
+impl ParserInterface for EOI {}
+
+/// `depth` groups nested in each other, e.g. `((()))` for 3.
+fn nested(depth: usize) -> String {
+    "(".repeat(depth) + &")".repeat(depth)
+}
+
+fn main() -> anyhow::Result<()> {
+    // `document` is at depth 1, so 7 groups fit.
+    let tree = NestedParser::parse_tree(Rule::document(document), &nested(7))?;
+    let mut node = &tree[0];
+    let mut depth = 1;
+    while let [child] = node.children.as_slice() {
+        node = child;
+        depth += 1;
+    }
+    assert_eq!(depth, 8);
+
+    let error = NestedParser::parse_tree(Rule::document(document), &nested(8)).unwrap_err();
+    assert!(
+        error
+            .to_string()
+            .contains("`group` at 1:8 is nested deeper than the limit of 8 levels"),
+        "{error}"
+    );
+
+    // a pathological input is rejected at the limit, whatever its depth.
+    let input = nested(500);
+    let pairs = NestedParser::parse(Rule::document(document), &input)?;
+    let error = map_tree(pairs, |kind, _| anyhow::Ok(kind)).unwrap_err();
+    let error = error.downcast::<DepthError>()?;
+    assert_eq!(
+        error,
+        DepthError {
+            rule: RuleKind::group,
+            offset: 7,
+            line_col: (1, 8),
+            max_depth: 8,
+        }
+    );
+
+    let pairs = NestedParser::parse(Rule::document(document), "(())")?;
+    let trees = map_tree(pairs, |kind, _| anyhow::Ok(kind))?;
+    assert_eq!(trees[0].children[0].children[0].value, RuleKind::group);
+
+    println!("ok");
+    Ok(())
+}
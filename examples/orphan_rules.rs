@@ -0,0 +1,52 @@
+//! Rules which no other rule references still get a struct and a variant of `enum Rule`.
+//!
+//! `pest` generates a variant for every rule of the grammar, reachable from the start rule or not (silent
+//! ones included), so an orphan rule is dispatched like any other and can be parsed as a start rule itself.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[enum_dispatch]
+pub trait Describe {
+    fn describe(&self) -> &'static str;
+}
+
+#[pest_parser(grammar = "examples/orphan.pest", interface = "Describe")]
+pub struct OrphanParser;
+
+macro_rules! describe {
+    ($($rule:ident => $text:literal),*) => {
+        $(impl Describe for $rule {
+            fn describe(&self) -> &'static str {
+                $text
+            }
+        })*
+    };
+}
+
+describe!(
+    EOI => "end of input",
+    WHITESPACE => "whitespace",
+    list => "list",
+    number => "number",
+    word => "orphan",
+    _blank => "silent orphan"
+);
+
+fn main() -> Result<()> {
+    assert!(RuleKind::all().contains(&RuleKind::word));
+    assert!(RuleKind::all().contains(&RuleKind::_blank));
+    assert_eq!(Rule::from(RuleKind::word).describe(), "orphan");
+    assert_eq!(Rule::_blank(_blank).describe(), "silent orphan");
+
+    let pairs = OrphanParser::parse(Rule::word(word), "orphan")?;
+    let rules = pairs.map(|pair| pair.as_rule()).collect::<Vec<_>>();
+    assert_eq!(rules, [Rule::word(word)]);
+    assert_eq!(rules[0].describe(), "orphan");
+
+    let pairs = OrphanParser::parse(Rule::list(list), "1, 2")?;
+    assert!(pairs.flatten().all(|pair| pair.as_rule() != RuleKind::word));
+    Ok(())
+}
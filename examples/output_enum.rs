@@ -0,0 +1,67 @@
+//! Rule structs returning outputs of their own types with `output_enum`.
+//!
+//! `Evaluate` has an associated `Output`, which `enum_dispatch` cannot dispatch. With `output_enum`,
+//! `#[pest_dispatch]` leaves it unlinked, and `rule_output!` (generated by `generate = "output_enum"`) writes
+//! `RuleOutput` and `Rule::evaluate()`, wrapping the output of each rule struct in its variant.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::{pest_dispatch, pest_rules};
+use pest::{iterators::Pair, Parser};
+
+#[pest_rules(grammar = "examples/grammar.pest", generate = "output_enum")]
+pub struct LanguageParser;
+
+#[pest_dispatch(rules = "LanguageParser", output_enum = "RuleOutput")]
+pub trait Evaluate {
+    type Output;
+
+    fn evaluate(&self, pair: &Pair<'_, Rule>) -> Self::Output;
+}
+
+impl Evaluate for Number {
+    type Output = u64;
+
+    fn evaluate(&self, pair: &Pair<'_, Rule>) -> u64 {
+        pair.as_str().parse().unwrap()
+    }
+}
+
+impl Evaluate for Strings {
+    type Output = String;
+
+    fn evaluate(&self, pair: &Pair<'_, Rule>) -> String {
+        pair.as_str().trim_matches('"').to_string()
+    }
+}
+
+macro_rules! no_output {
+    ($($rule:ident),*) => {
+        $(impl Evaluate for $rule {
+            type Output = ();
+
+            fn evaluate(&self, _pair: &Pair<'_, Rule>) {}
+        })*
+    };
+}
+
+no_output!(EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Identifier);
+
+fn main() -> anyhow::Result<()> {
+    let pair = LanguageParser::parse(Rule::Arguments(Arguments), "42, \"hi\"")?
+        .next()
+        .unwrap();
+    let mut numbers = Vec::new();
+    let mut strings = Vec::new();
+    for pair in pair.into_inner().flatten() {
+        match pair.as_rule().evaluate(&pair) {
+            RuleOutput::Number(number) => numbers.push(number),
+            RuleOutput::Strings(string) => strings.push(string),
+            output => assert_eq!(output.kind(), RuleKind::Argument),
+        }
+    }
+    assert_eq!(numbers, [42]);
+    assert_eq!(strings, ["hi"]);
+
+    println!("ok");
+    Ok(())
+}
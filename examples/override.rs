@@ -0,0 +1,86 @@
+//! A rule carrying a type of the user instead of a generated struct, with `override`.
+//!
+//! `Strings` is `my_types::Strings`, built with `Default::default()` wherever a `Rule::Strings` is: it shares a
+//! counter with every other `Strings`, observed after the dispatch. `pest` requires `Rule: Copy`, so the type
+//! must be `Copy` as well, which the generated `override_of_Strings` checks.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::{iterators::Pair, Parser};
+
+#[macro_use]
+mod common;
+
+mod my_types {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    static STRINGS_SEEN: AtomicUsize = AtomicUsize::new(0);
+
+    /// The struct of the rule `Strings`, counting the strings it is dispatched for.
+    #[derive(Clone, Copy, Debug)]
+    pub struct Strings {
+        seen: &'static AtomicUsize,
+    }
+
+    impl Default for Strings {
+        fn default() -> Self {
+            Self {
+                seen: &STRINGS_SEEN,
+            }
+        }
+    }
+
+    impl Strings {
+        pub fn seen(&self) -> usize {
+            self.seen.load(Ordering::Relaxed)
+        }
+
+        pub fn count(&self) {
+            self.seen.fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn visit(&self, _pair: &Pair<'_, Rule>) {}
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    override(Strings = "crate::my_types::Strings")
+)]
+pub struct LanguageParser;
+
+impl ParserInterface for my_types::Strings {
+    fn visit(&self, pair: &Pair<'_, Rule>) {
+        assert!(pair.as_str().starts_with('"'));
+        self.count();
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Number,
+    Identifier
+);
+
+fn main() -> anyhow::Result<()> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), r#"@a("x", 1, "y"); @b("z");"#)?;
+    for pair in pairs.flatten() {
+        pair.as_rule().visit(&pair);
+    }
+    let strings = my_types::Strings::default();
+    assert_eq!(strings.seen(), 3);
+
+    // the tables of rules hold the overridden payload as well.
+    let Rule::Strings(from_kind) = Rule::from(RuleKind::Strings) else {
+        unreachable!()
+    };
+    assert_eq!(from_kind.seen(), 3);
+    assert!(Rule::user_rules().contains(&Rule::Strings(strings)));
+    assert_eq!(Rule::user_rules().len(), Rule::USER_RULE_COUNT);
+
+    println!("ok");
+    Ok(())
+}
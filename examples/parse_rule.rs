@@ -0,0 +1,42 @@
+//! Choosing the start rule by type with `ParserExt::parse_rule()`.
+//!
+//! Generic code over rule structs (`R: RuleMarker`) can parse with the rule of `R` without a `Rule` value.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn describe(&self) -> &'static str {
+        "rule"
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    generate = "parse_rule"
+)]
+pub struct LanguageParser;
+
+rule_structs!(ParserInterface);
+
+/// parses `input` as a whole `R`, returning the kind of the root pair.
+fn root_kind<R: RuleMarker>(input: &str) -> Result<RuleKind> {
+    let pair = LanguageParser::parse_rule::<R>(input)?.next().unwrap();
+    assert_eq!(pair.as_str(), input);
+    Ok(pair.as_rule().kind())
+}
+
+fn main() -> Result<()> {
+    assert_eq!(root_kind::<Statement>("@print(42);")?, RuleKind::Statement);
+    assert_eq!(root_kind::<Number>("42")?, RuleKind::Number);
+    assert_eq!(Number::rule(), RuleKind::Number);
+    assert!(LanguageParser::parse_rule::<Number>("forty-two").is_err());
+    println!("ok");
+    Ok(())
+}
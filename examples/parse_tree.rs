@@ -0,0 +1,44 @@
+//! Printing the whole parse tree with the `Display` of `ParseNode`.
+//!
+//! Every node is printed on its own line, indented by its depth, with its text unless the tree was built
+//! without text (`parse_tree_with(.., false)`) or printed with `{:#}`. The width (`{:4}`) sets the indentation.
+
+use enum_dispatch::enum_dispatch;
+
+#[macro_use]
+mod common;
+
+language_parser!(generate = "tree");
+
+fn main() -> anyhow::Result<()> {
+    let tree = LanguageParser::parse_tree(Rule::Script(Script), "@a(1);")?;
+    assert_eq!(
+        tree[0].to_string(),
+        r#"Script "@a(1);"
+  Statement "@a(1);"
+    Command "@a(1)"
+      Identifier "a"
+      Arguments "1"
+        Argument "1"
+          Number "1"
+"#
+    );
+    assert_eq!(
+        format!("{:4}", tree[0].children[0].children[0].children[1]),
+        "Arguments \"1\"\n    Argument \"1\"\n        Number \"1\"\n"
+    );
+    assert_eq!(
+        format!("{:#}", tree[0].children[0].children[0].children[1]),
+        "Arguments\n  Argument\n    Number\n"
+    );
+
+    let rules_only = LanguageParser::parse_tree_with(Rule::Script(Script), "@a(1);", false)?;
+    assert_eq!(
+        rules_only[0].to_string(),
+        format!("{:#}", tree[0]),
+        "a tree without text prints like `{{:#}}`"
+    );
+
+    print!("{}", tree[0]);
+    Ok(())
+}
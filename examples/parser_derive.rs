@@ -0,0 +1,56 @@
+//! Deriving the parser with a fork of `pest` (here the stand-in `parser_fork`), with `parser_derive`.
+//!
+//! The derive is applied to a hidden parser struct, which the annotated one aliases, and its rules are dispatched
+//! like the ones of `reuse_parser`: the pairs carry the fieldless `Rule` of the fork, converted into the dispatch
+//! `Rule` with `From`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn is_literal(&self) -> bool {
+        false
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    parser_derive = "parser_fork::Parser"
+)]
+pub struct LanguageParser;
+
+impl ParserInterface for Strings {
+    fn is_literal(&self) -> bool {
+        true
+    }
+}
+
+impl ParserInterface for Number {
+    fn is_literal(&self) -> bool {
+        true
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Identifier
+);
+
+fn main() -> anyhow::Result<()> {
+    assert_eq!(LanguageParser::FORK, "parser_fork");
+
+    let literals = LanguageParser::parse(Rule::Arguments(Arguments).into(), "1, \"two\", three")?
+        .flatten()
+        .filter(|pair| Rule::from(pair.as_rule()).is_literal())
+        .map(|pair| pair.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(literals, ["1", "\"two\""]);
+
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,80 @@
+//! Naming the rule structs apart from the rules with `rename_strategy`.
+//!
+//! The rules of `tokens.pest` follow the naming of the grammar (`tok_number`, `add_op`), the structs the naming of
+//! Rust: `strip_prefix:tok_` then `pascal_case` turns them into `Number` and `AddOp`. `Rule` and `RuleKind` keep
+//! the rule names, and the handlers of `delegate_to` follow the structs (`Handlers::number`, `Handlers::add_op`).
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::{pest_dispatch, pest_rules};
+use pest::Parser;
+
+#[pest_rules(
+    grammar = "examples/tokens.pest",
+    rename_strategy = "strip_prefix:tok_, pascal_case",
+    generate = "name, delegate"
+)]
+pub struct TokenParser;
+
+#[pest_dispatch(rules = "TokenParser", delegate_to = "Handlers")]
+pub trait Evaluate {
+    fn evaluate(&self, text: &str) -> Option<i64>;
+}
+
+struct Handlers;
+
+impl Handlers {
+    fn eoi(_text: &str) -> Option<i64> {
+        None
+    }
+
+    fn whitespace(_text: &str) -> Option<i64> {
+        None
+    }
+
+    fn sum(_text: &str) -> Option<i64> {
+        None
+    }
+
+    fn term(_text: &str) -> Option<i64> {
+        None
+    }
+
+    fn add_op(text: &str) -> Option<i64> {
+        Some(if text == "-" { -1 } else { 1 })
+    }
+
+    fn number(text: &str) -> Option<i64> {
+        text.parse().ok()
+    }
+
+    fn ident(text: &str) -> Option<i64> {
+        Some(text.len() as i64)
+    }
+}
+
+fn main() -> Result<()> {
+    // the variants keep the names of the rules, and carry the renamed structs.
+    let rule = Rule::tok_number(Number);
+    assert_eq!(rule, RuleKind::tok_number);
+    assert_eq!(Rule::from(RuleKind::add_op), Rule::add_op(AddOp));
+    assert_eq!(RuleKind::tok_number.name(), "tok_number");
+
+    let pairs = TokenParser::parse(Rule::tok_sum(Sum), "12 + abc - 4")?;
+    let mut total = 0;
+    let mut sign = 1;
+    for pair in pairs.flatten() {
+        match pair.as_rule().kind() {
+            RuleKind::add_op => sign = pair.as_rule().evaluate(pair.as_str()).unwrap_or(1),
+            RuleKind::tok_number | RuleKind::tok_ident => {
+                total += sign * pair.as_rule().evaluate(pair.as_str()).unwrap_or(0);
+            }
+            _ => {}
+        }
+    }
+    assert_eq!(total, 12 + 3 - 4);
+    assert_eq!(Ident.evaluate("xy"), Some(2));
+
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,56 @@
+//! Per-rule data in a `RuleMap<T>`.
+//!
+//! `RuleMap` (generated by `generate = "rule_map"`) keeps a value for every rule, `EOI` included, in an array
+//! indexed by `RuleKind::index()`. Here it counts the pairs of each rule of a parse, and is checked to be built,
+//! mutated and iterated in definition order.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    generate = "rule_map"
+)]
+pub struct LanguageParser;
+
+fn main() -> anyhow::Result<()> {
+    // construction: a clone of the default, or a value computed from each kind.
+    let zeros = RuleMap::new(0_usize);
+    assert!(zeros.iter().all(|(_, count)| *count == 0));
+    let names = RuleMap::from_fn(|kind| format!("{kind:?}"));
+    assert_eq!(names.get(Rule::Statement(Statement)), "Statement");
+    assert_eq!(names[RuleKind::Number], "Number");
+
+    // mutation: counting the pairs of every rule.
+    let mut counts = zeros.clone();
+    let pairs = LanguageParser::parse(Rule::Script(Script), "@print(1, 2);")?;
+    for pair in pairs.flatten() {
+        *counts.get_mut(pair.as_rule()) += 1;
+    }
+    assert_eq!(*counts.get(Rule::Number(Number)), 2);
+    assert_eq!(counts[RuleKind::Command], 1);
+    counts[RuleKind::Command] += 1;
+    assert_eq!(counts[RuleKind::Command], 2);
+    assert_ne!(counts, zeros);
+
+    // iteration: every rule, `EOI` included, in definition order.
+    let kinds = (&names)
+        .into_iter()
+        .map(|(kind, _)| kind)
+        .collect::<Vec<_>>();
+    assert_eq!(kinds, RuleKind::all());
+    assert_eq!(kinds.len(), Rule::RULE_COUNT);
+    assert_eq!(kinds[0], RuleKind::EOI);
+    assert_eq!(counts[RuleKind::EOI], 1);
+    for (kind, name) in &names {
+        assert_eq!(*name, format!("{kind:?}"));
+    }
+
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,40 @@
+//! Maps keyed by `RuleName`, looked up by the names read at runtime.
+//!
+//! `RuleName` borrows as a `str`, so a `HashMap<RuleName, V>` built from the rules answers a `&str` key, e.g. a
+//! rule name from a configuration file, without allocating a `String` for it.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use std::collections::{BTreeSet, HashMap};
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(grammar = "examples/grammar.pest", interface = "ParserInterface")]
+pub struct LanguageParser;
+
+fn main() {
+    let limits = RuleName::ALL
+        .iter()
+        .map(|name| (*name, name.len()))
+        .collect::<HashMap<_, _>>();
+    // e.g. read from a configuration file.
+    let configured = String::from("Statement");
+    assert_eq!(limits.get(configured.as_str()), Some(&9));
+    assert_eq!(limits.get("Statment"), None);
+    assert_eq!(limits[RuleName::from(RuleKind::EOI).as_str()], 3);
+
+    let name = RuleName::from(RuleKind::Identifier);
+    assert_eq!(name.to_string(), "Identifier");
+    assert_eq!(&*name, RuleKind::Identifier.name());
+    assert!(name.starts_with("Ident"));
+
+    // in definition order, and ordered like the strings they hold.
+    assert_eq!(RuleName::ALL.len(), Rule::RULE_COUNT);
+    assert!(RuleKind::iter().all(|kind| RuleName::ALL[kind.index()] == RuleName::from(kind)));
+    let sorted = RuleName::ALL.iter().copied().collect::<BTreeSet<_>>();
+    let first = sorted.iter().map(|name| name.as_str()).next();
+    assert_eq!(first, Some("Argument"));
+
+    println!("ok");
+}
@@ -0,0 +1,65 @@
+//! Generic code over every rule struct, bounded by the sealed `RuleStruct` trait.
+//!
+//! `RuleStruct` carries the derives of the rule structs, and combined with the interface it bounds helpers
+//! which work for any rule without naming one.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use std::collections::BTreeMap;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn weight(&self) -> usize {
+        1
+    }
+}
+
+#[pest_parser(grammar = "examples/grammar.pest", interface = "ParserInterface")]
+pub struct LanguageParser;
+
+impl ParserInterface for Statement {
+    fn weight(&self) -> usize {
+        10
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Command, Arguments, Argument, Strings, Number,
+    Identifier
+);
+
+/// Sums the weights of `structs` by struct, which are ordered like any other `Ord` type.
+fn weights<T: RuleStruct + ParserInterface>(structs: &[T]) -> BTreeMap<T, usize> {
+    let mut weights = BTreeMap::new();
+    for rule in structs {
+        *weights.entry(*rule).or_default() += rule.weight();
+    }
+    weights
+}
+
+/// Describes the default value of any rule struct.
+fn describe<T: RuleStruct>() -> String {
+    format!("{:?}", T::default())
+}
+
+fn main() -> Result<()> {
+    assert_eq!(
+        weights(&[Statement, Statement]),
+        BTreeMap::from([(Statement, 20)])
+    );
+    assert_eq!(
+        weights(&[Number, Number, Number]),
+        BTreeMap::from([(Number, 3)])
+    );
+    assert_eq!(weights::<Strings>(&[]), BTreeMap::new());
+    assert_eq!(describe::<Identifier>(), "Identifier");
+    // the rule structs are zero-sized, `Default` builds the only value they have.
+    assert_eq!(std::mem::size_of::<Statement>(), 0);
+    assert_eq!(std::mem::size_of::<EOI>(), 0);
+    println!("ok");
+    Ok(())
+}
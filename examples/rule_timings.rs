@@ -0,0 +1,71 @@
+//! Finding the rule whose handler is slow, with the `RuleTimings` of the `timing` feature.
+//!
+//! The accumulator of `walk_into()` sleeps on every `Number`, which tops the report of the timings installed
+//! during the walk. Run with `cargo run --example rule_timings --features timing`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::{iterators::Pair, Parser};
+use std::sync::Arc;
+use std::thread;
+use std::time::Duration;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn handle(&mut self, _pair: &Pair<'_, Rule>) {}
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    walk_method = "handle"
+)]
+pub struct LanguageParser;
+
+struct SlowNumbers;
+
+impl ParserInterface for SlowNumbers {
+    fn handle(&mut self, pair: &Pair<'_, Rule>) {
+        if pair.as_rule() == RuleKind::Number {
+            thread::sleep(Duration::from_millis(5));
+        }
+    }
+}
+
+rule_structs!(ParserInterface);
+
+const INPUT: &str = "@print(\"hello\", 42); @add(1, 2, x);";
+
+fn main() -> anyhow::Result<()> {
+    let timings = Arc::new(RuleTimings::new());
+    RuleTimings::install(timings.clone());
+    walk_into(
+        LanguageParser::parse(Rule::Script(Script), INPUT)?,
+        &mut SlowNumbers,
+    );
+    RuleTimings::uninstall();
+    // nothing is recorded once uninstalled.
+    walk_into(
+        LanguageParser::parse(Rule::Script(Script), INPUT)?,
+        &mut SlowNumbers,
+    );
+
+    let report = timings.report_sorted();
+    assert_eq!(report[0].0, RuleKind::Number);
+    assert_eq!(report[0].2, 3);
+    assert!(report[0].1 >= Duration::from_millis(15));
+    assert_eq!(timings.calls(RuleKind::Statement), 2);
+    // the rules never called are left out of the report.
+    assert!(report.iter().all(|(kind, _, _)| *kind != RuleKind::EOI));
+
+    let table = timings.to_string();
+    let mut lines = table.lines();
+    assert!(lines.next().unwrap().starts_with("rule"));
+    assert!(lines.next().unwrap().starts_with("Number"));
+    print!("{table}");
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,61 @@
+//! An interface which only the rule structs implement, with `sealed`.
+//!
+//! `ParserInterface` takes the generated `sealed::Sealed` as supertrait, which only `Rule` and the rule structs
+//! implement: a function taking `impl ParserInterface` can rely on getting a rule struct, since another crate can
+//! neither implement the interface nor `Sealed` for its own types.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface: sealed::Sealed {
+    fn is_literal(&self) -> bool {
+        false
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    sealed = "true"
+)]
+pub struct LanguageParser;
+
+impl ParserInterface for Strings {
+    fn is_literal(&self) -> bool {
+        true
+    }
+}
+
+impl ParserInterface for Number {
+    fn is_literal(&self) -> bool {
+        true
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Identifier
+);
+
+/// only ever called with a rule struct or a `Rule`.
+fn literal(rule: impl ParserInterface) -> bool {
+    rule.is_literal()
+}
+
+fn main() -> anyhow::Result<()> {
+    let literals = LanguageParser::parse(Rule::Arguments(Arguments), "1, \"two\", three")?
+        .flatten()
+        .filter(|pair| literal(pair.as_rule()))
+        .map(|pair| pair.as_str())
+        .collect::<Vec<_>>();
+    assert_eq!(literals, ["1", "\"two\""]);
+    assert!(literal(Number));
+    assert!(!literal(Identifier));
+
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,79 @@
+//! Checking where a pair is with the `RuleStack` of `walk_with_stack()`, e.g. whether it is inside a `Command`.
+
+use anyhow::{ensure, Result};
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+language_parser!(generate = "name, stack");
+
+fn main() -> Result<()> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), "@a(1, x); @b();")?;
+    let mut visited = Vec::new();
+    walk_with_stack(pairs, |stack, pair| {
+        ensure!(stack.top() == Some(pair.as_rule().kind()), "stale stack");
+        let path = stack
+            .path()
+            .iter()
+            .map(|kind| kind.name())
+            .collect::<Vec<_>>();
+        visited.push((
+            pair.as_str().to_string(),
+            path.join("/"),
+            stack.depth(),
+            stack.contains(RuleKind::Arguments),
+        ));
+        Ok(())
+    })?;
+    let expected = [
+        ("@a(1, x); @b();", "Script", 0, false),
+        ("@a(1, x);", "Script/Statement", 1, false),
+        ("@a(1, x)", "Script/Statement/Command", 2, false),
+        ("a", "Script/Statement/Command/Identifier", 3, false),
+        ("1, x", "Script/Statement/Command/Arguments", 3, true),
+        ("1", "Script/Statement/Command/Arguments/Argument", 4, true),
+        (
+            "1",
+            "Script/Statement/Command/Arguments/Argument/Number",
+            5,
+            true,
+        ),
+        // the sibling of the first argument, after `Number` was popped.
+        ("x", "Script/Statement/Command/Arguments/Argument", 4, true),
+        (
+            "x",
+            "Script/Statement/Command/Arguments/Argument/Identifier",
+            5,
+            true,
+        ),
+        // the next statement, no longer inside `Arguments`.
+        ("@b();", "Script/Statement", 1, false),
+        ("@b()", "Script/Statement/Command", 2, false),
+        ("b", "Script/Statement/Command/Identifier", 3, false),
+        // the trailing `EOI` of `Script` is skipped.
+    ];
+    let visited = visited
+        .iter()
+        .map(|(text, path, depth, in_arguments)| {
+            (text.as_str(), path.as_str(), *depth, *in_arguments)
+        })
+        .collect::<Vec<_>>();
+    assert_eq!(visited, expected);
+
+    // stops at the first error, here the first identifier used as an argument.
+    let pairs = LanguageParser::parse(Rule::Script(Script), "@a(b);")?;
+    let error = walk_with_stack(pairs, |stack, pair| {
+        ensure!(
+            !(stack.contains(RuleKind::Arguments) && pair.as_rule() == RuleKind::Identifier),
+            "`{}` is used as an argument",
+            pair.as_str()
+        );
+        Ok(())
+    })
+    .unwrap_err();
+    assert_eq!(error.to_string(), "`b` is used as an argument");
+    println!("ok");
+    Ok(())
+}
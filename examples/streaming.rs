@@ -0,0 +1,71 @@
+//! Pulling the statements of a script one at a time with `dispatch_next()`.
+//!
+//! The loop advances the pairs itself, so it can stop at the first failing statement without visiting
+//! the rest of the script.
+
+use anyhow::{bail, Result};
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::{iterators::Pair, Parser};
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    /// Runs a statement, returning the name of its command.
+    fn run<'i>(&self, pair: Pair<'i, Rule>) -> Result<&'i str> {
+        bail!("`{}` is not a statement", pair.as_str())
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    generate = "dispatch_next"
+)]
+pub struct LanguageParser;
+
+impl ParserInterface for Statement {
+    fn run<'i>(&self, pair: Pair<'i, Rule>) -> Result<&'i str> {
+        let command = pair.into_inner().next().unwrap();
+        let name = command.into_inner().next().unwrap().as_str();
+        if name == "fail" {
+            bail!("`@fail` failed");
+        }
+        Ok(name)
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Command, Arguments, Argument, Strings, Number,
+    Identifier
+);
+
+/// Runs the statements of `input` until one fails, returning the commands run so far and the error.
+fn run_script(input: &str) -> Result<(Vec<&str>, Option<anyhow::Error>)> {
+    let script = LanguageParser::parse(Rule::Script(Script), input)?
+        .next()
+        .unwrap();
+    let mut statements = script.into_inner();
+    let mut commands = Vec::new();
+    while let Some(result) = dispatch_next(&mut statements, &|rule, pair| rule.run(pair)) {
+        match result {
+            Ok(command) => commands.push(command),
+            Err(error) => return Ok((commands, Some(error))),
+        }
+    }
+    Ok((commands, None))
+}
+
+fn main() -> Result<()> {
+    let (commands, error) = run_script("@print(\"hello\"); @sleep(1); @exit(0);")?;
+    assert_eq!(commands, ["print", "sleep", "exit"]);
+    assert!(error.is_none());
+
+    let (commands, error) = run_script("@print(1); @fail(); @exit(0);")?;
+    assert_eq!(commands, ["print"]);
+    assert_eq!(error.unwrap().to_string(), "`@fail` failed");
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,76 @@
+//! Emitting the rule structs in a module of their own with `struct_module`.
+//!
+//! `LanguageParser` is declared in a submodule, so its structs are named by their path from the crate root
+//! (`crate::lang::rules`); `ScriptParser` is namespaced, and only names the module, which is emitted next to the
+//! other generated items (`crate::script_parser::rules`).
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn is_statement(&self) -> bool {
+        false
+    }
+}
+
+mod lang {
+    use super::*;
+
+    #[pest_parser(
+        grammar = "examples/grammar.pest",
+        interface = "ParserInterface",
+        struct_module = "crate::lang::rules"
+    )]
+    pub struct LanguageParser;
+
+    impl ParserInterface for rules::Statement {
+        fn is_statement(&self) -> bool {
+            true
+        }
+    }
+
+    rule_structs!(
+        ParserInterface for rules: EOI, WHITESPACE, Script, Command, Arguments, Argument, Strings,
+        Number, Identifier
+    );
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    namespaced = "true",
+    struct_module = "rules"
+)]
+pub struct ScriptParser;
+
+rule_structs!(ParserInterface for script_parser::rules);
+
+fn main() -> Result<()> {
+    let input = "@a(1);@b(\"2\");";
+    let pairs = lang::LanguageParser::parse(lang::Rule::Script(lang::rules::Script), input)?;
+    let statements = pairs
+        .flatten()
+        .filter(|pair| pair.as_rule().is_statement())
+        .count();
+    assert_eq!(statements, 2);
+
+    let pairs = ScriptParser::parse(
+        script_parser::Rule::Script(script_parser::rules::Script),
+        input,
+    )?;
+    assert_eq!(
+        pairs
+            .flatten()
+            .filter(|pair| pair.as_rule().is_statement())
+            .count(),
+        0
+    );
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,57 @@
+//! Resynchronizing after an error on the rules of `Rule::sync_points()`.
+//!
+//! The parser of `heuristic` keeps the rules found in the grammar, the items of its lists. The parser of the
+//! crate root overrides them with `sync_points`, to resynchronize on whole statements only.
+
+use enum_dispatch::enum_dispatch;
+
+mod heuristic {
+    use enum_dispatch::enum_dispatch;
+
+    enum_dispatch_pest_parser::rule_structs!(grammar = "examples/grammar.pest");
+
+    #[enum_dispatch_pest_parser::pest_rules(
+        grammar = "examples/grammar.pest",
+        struct_path = "crate::heuristic"
+    )]
+    pub struct LanguageParser;
+}
+
+#[enum_dispatch_pest_parser::pest_rules(
+    grammar = "examples/grammar.pest",
+    sync_points = "Script, Statement"
+)]
+pub struct LanguageParser;
+
+/// the rules of `path` (from the root to a failed pair) to resynchronize on, innermost first.
+fn resync_candidates(path: &[RuleKind]) -> Vec<RuleKind> {
+    path.iter()
+        .rev()
+        .filter(|kind| Rule::sync_points().contains(kind))
+        .copied()
+        .collect()
+}
+
+fn main() {
+    assert_eq!(
+        heuristic::Rule::sync_points(),
+        [
+            heuristic::RuleKind::Statement,
+            heuristic::RuleKind::Argument
+        ]
+    );
+    assert_eq!(Rule::sync_points(), [RuleKind::Script, RuleKind::Statement]);
+
+    let path = [
+        RuleKind::Script,
+        RuleKind::Statement,
+        RuleKind::Command,
+        RuleKind::Arguments,
+        RuleKind::Argument,
+    ];
+    assert_eq!(
+        resync_candidates(&path),
+        [RuleKind::Statement, RuleKind::Script]
+    );
+    println!("ok");
+}
@@ -0,0 +1,90 @@
+//! Telling the leaf rules from the composite ones with `TerminalRule`, e.g. to highlight the tokens of an input.
+//!
+//! `ident` and `number` are atomic, so terminal even though `ident` references `letter`. `string` is
+//! compound-atomic: its pair holds the one of `content`, which is terminal. `keyword` only references the silent
+//! `word`, which references nothing with pairs, and `operator` only references a rule under a predicate, so both
+//! are terminal. `call` references the silent `arguments`, which has pairs of `value`s, so it is not.
+
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface")]
+    pub struct CallParser;
+
+    grammar {
+        WHITESPACE = _{ " " }
+        program = { SOI ~ call* ~ EOI }
+        call = { keyword ~ "(" ~ arguments? ~ ")" }
+        arguments = _{ value ~ ("," ~ value)* }
+        value = { number | string | ident }
+        keyword = { word ~ "!" }
+        word = _{ "print" | "log" }
+        operator = { !number ~ ("+" | "-") }
+        ident = @{ letter ~ (letter | ASCII_DIGIT)* }
+        letter = { ASCII_ALPHA | "_" }
+        number = @{ ASCII_DIGIT+ }
+        string = ${ "\"" ~ content ~ "\"" }
+        content = @{ (!"\"" ~ ANY)* }
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, program, call, arguments, value, keyword, word, operator,
+    ident, letter, number, string, content
+);
+
+fn main() -> anyhow::Result<()> {
+    let terminals = RuleKind::ALL
+        .iter()
+        .filter_map(RuleKind::as_terminal)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        terminals,
+        [
+            TerminalRule::EOI,
+            TerminalRule::keyword,
+            TerminalRule::operator,
+            TerminalRule::ident,
+            TerminalRule::letter,
+            TerminalRule::number,
+            TerminalRule::content
+        ]
+    );
+    for composite in [
+        RuleKind::program,
+        RuleKind::call,
+        RuleKind::value,
+        RuleKind::string,
+    ] {
+        assert_eq!(TerminalRule::try_from(composite), Err(composite));
+    }
+    // silent rules have no pairs, so they are neither.
+    assert_eq!(RuleKind::arguments.as_terminal(), None);
+    assert_eq!(RuleKind::from(TerminalRule::number), RuleKind::number);
+    assert_eq!(format!("{:?}", TerminalRule::ident), "ident");
+
+    // the tokens of a call, e.g. to highlight them.
+    let tokens = CallParser::parse(Rule::call(call), "print!(x1, 42, \"hi\")")?
+        .flatten()
+        .filter_map(|pair| Some((pair.as_rule().kind().as_terminal()?, pair.as_str())))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        [
+            (TerminalRule::keyword, "print!"),
+            (TerminalRule::ident, "x1"),
+            (TerminalRule::number, "42"),
+            (TerminalRule::content, "hi")
+        ]
+    );
+
+    println!("ok");
+    Ok(())
+}
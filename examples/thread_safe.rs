@@ -0,0 +1,84 @@
+//! Parsing and dispatching from several threads, with `thread_safe` asserting `Send + Sync` at compile time.
+//!
+//! `pest`'s `Pairs` are not `Send` (they share their tokens through an `Rc`), so every thread parses its own input,
+//! and sends back what it dispatched: `Rule`s and owned `ParseNode`s, which are.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+use std::sync::mpsc;
+use std::thread;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn is_literal(&self) -> bool {
+        false
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "ParserInterface",
+    generate = "iter, name, tree",
+    thread_safe = "true"
+)]
+pub struct LanguageParser;
+
+impl ParserInterface for Strings {
+    fn is_literal(&self) -> bool {
+        true
+    }
+}
+
+impl ParserInterface for Number {
+    fn is_literal(&self) -> bool {
+        true
+    }
+}
+
+rule_structs!(
+    ParserInterface: EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Identifier
+);
+
+fn main() {
+    let inputs = [r#"@a(1, "x");"#, "@b(2);", "@c(d);"];
+    let (sender, receiver) = mpsc::channel::<Rule>();
+
+    let trees = thread::scope(|scope| {
+        let workers = inputs
+            .iter()
+            .map(|input| {
+                let sender = sender.clone();
+                scope.spawn(move || {
+                    let pairs = LanguageParser::parse(Rule::Script(Script), input).unwrap();
+                    for pair in pairs.flatten() {
+                        let rule = pair.as_rule();
+                        if rule.is_literal() {
+                            sender.send(rule).unwrap();
+                        }
+                    }
+                    LanguageParser::parse_tree_with(Rule::Script(Script), input, false).unwrap()
+                })
+            })
+            .collect::<Vec<_>>();
+        workers
+            .into_iter()
+            .map(|worker| worker.join().unwrap())
+            .collect::<Vec<_>>()
+    });
+    drop(sender);
+
+    let mut literals = receiver
+        .iter()
+        .map(|rule| rule.kind().name())
+        .collect::<Vec<_>>();
+    literals.sort();
+    assert_eq!(literals, ["Number", "Number", "Strings"]);
+    assert_eq!(trees.len(), 3);
+    assert!(trees.iter().all(|tree| tree[0].rule == RuleKind::Script));
+
+    println!("ok");
+}
@@ -0,0 +1,86 @@
+//! The TOML grammar of `pest_grammars` through `#[pest_parser]`.
+//!
+//! `examples/toml.pest` is vendored from `pest_grammars` unchanged. Every pair of a sample document is
+//! dispatched to a trivial counting interface, and the counts are checked against the document. The
+//! grammar has a rule named `inner`, which `enum_dispatch` cannot handle, so `struct_style = "enum"` is used.
+
+use anyhow::Result;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+use std::collections::BTreeMap;
+
+pub trait Counter {
+    fn count(&self, counts: &mut BTreeMap<RuleStruct, usize>);
+}
+
+#[pest_parser(
+    grammar = "examples/toml.pest",
+    interface = "Counter",
+    struct_style = "enum"
+)]
+pub struct TomlParser;
+
+impl Counter for RuleStruct {
+    fn count(&self, counts: &mut BTreeMap<RuleStruct, usize>) {
+        *counts.entry(*self).or_default() += 1;
+    }
+}
+
+const DOCUMENT: &str = r#"# comments and newlines are implicit whitespace
+title = "TOML éxample"
+
+[owner]
+name = 'Tom'
+dob = 1979-05-27T07:32:00Z
+
+[database.limits]
+ports = [8000, 8001, 8002,]
+inline = { max = 5_000, ratio = 0.5 }
+enabled = true
+
+[[products]]
+notes = """
+multi line"""
+"#;
+
+fn main() -> Result<()> {
+    let pairs = TomlParser::parse(Rule::toml(RuleStruct::toml), DOCUMENT)?;
+    let mut counts = BTreeMap::new();
+    for pair in pairs.flatten() {
+        pair.as_rule().count(&mut counts);
+    }
+    println!("{counts:?}");
+
+    // `key` is atomic, so its `identifier` is not produced. the date-time rules are compound-atomic and keep
+    // their inner pairs, and integers match `float` since it comes first in `value` (with an optional fraction).
+    let expected = [
+        (RuleStruct::EOI, 1),
+        (RuleStruct::toml, 1),
+        (RuleStruct::table, 2),
+        (RuleStruct::array_table, 1),
+        (RuleStruct::pair, 9),
+        (RuleStruct::key, 13),
+        (RuleStruct::inline_table, 1),
+        (RuleStruct::array, 1),
+        (RuleStruct::multi_line_string, 1),
+        (RuleStruct::string, 1),
+        (RuleStruct::literal, 1),
+        (RuleStruct::date_time, 1),
+        (RuleStruct::partial_time, 1),
+        (RuleStruct::full_date, 1),
+        (RuleStruct::full_time, 1),
+        (RuleStruct::date_fullyear, 1),
+        (RuleStruct::date_month, 1),
+        (RuleStruct::date_mday, 1),
+        (RuleStruct::time_hour, 1),
+        (RuleStruct::time_minute, 1),
+        (RuleStruct::time_second, 1),
+        (RuleStruct::time_offset, 1),
+        (RuleStruct::float, 5),
+        (RuleStruct::boolean, 1),
+    ];
+    assert_eq!(counts, BTreeMap::from(expected));
+
+    assert!(TomlParser::parse(Rule::toml(RuleStruct::toml), "[unterminated").is_err());
+    Ok(())
+}
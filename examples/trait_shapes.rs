@@ -0,0 +1,85 @@
+//! The shapes of interface traits `#[pest_dispatch]` supports, see "Interface Shapes" in the README.
+//!
+//! `Analyze` has default methods (`priority` is only overridden by `Number`), a method with a `where` clause, a
+//! generic method and a method returning `Self`. `NAME` would have been an associated const, which cannot be
+//! dispatched: `#[pest_dispatch]` rejects it and suggests the `name` method instead.
+
+use anyhow::Result;
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::{pest_dispatch, pest_rules};
+use pest::Parser;
+use std::fmt::Display;
+
+#[pest_rules(grammar = "examples/grammar.pest")]
+pub struct LanguageParser;
+
+#[pest_dispatch(rules = "LanguageParser")]
+pub trait Analyze {
+    /// instead of `const NAME: &'static str`.
+    fn name(&self) -> &'static str;
+
+    fn priority(&self) -> u8 {
+        0
+    }
+
+    fn label<T>(&self, value: T) -> String
+    where
+        T: Display,
+    {
+        format!("{}({value})", self.name())
+    }
+
+    fn count<I: IntoIterator>(&self, items: I) -> usize {
+        items.into_iter().count()
+    }
+
+    fn copied(&self) -> Self
+    where
+        Self: Copy,
+    {
+        *self
+    }
+}
+
+macro_rules! analyzed_rules {
+    ($($rule:ident),*) => {
+        $(
+            impl Analyze for $rule {
+                fn name(&self) -> &'static str {
+                    stringify!($rule)
+                }
+            }
+        )*
+    };
+}
+
+analyzed_rules!(
+    EOI, WHITESPACE, Script, Statement, Command, Arguments, Argument, Strings, Identifier
+);
+
+impl Analyze for Number {
+    fn name(&self) -> &'static str {
+        "Number"
+    }
+
+    fn priority(&self) -> u8 {
+        2
+    }
+}
+
+fn main() -> Result<()> {
+    let argument = LanguageParser::parse(Rule::Argument(Argument), "42")?
+        .next()
+        .unwrap();
+    let number = argument.clone().into_inner().next().unwrap();
+    let (argument, number) = (argument.as_rule(), number.as_rule());
+
+    assert_eq!(argument.priority(), 0);
+    assert_eq!(number.priority(), 2);
+    assert_eq!(number.label(42), "Number(42)");
+    assert_eq!(argument.count(["a", "b"]), 2);
+    assert_eq!(number.copied(), number);
+
+    println!("ok");
+    Ok(())
+}
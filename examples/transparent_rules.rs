@@ -0,0 +1,164 @@
+//! Splicing wrapper rules out of the walks with `transparent_rules`.
+//!
+//! `Arguments` and `Argument` only wrap the values of a command. `TransparentParser` declares them transparent,
+//! `PlainParser` does not, and the same nested input is walked with both: the walkers of the former never hand
+//! the wrappers over, and visit their children in their place, under the parent and among the siblings the
+//! wrappers had. Both parsers are namespaced, being generated from the same grammar.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::iterators::Pair;
+use pest::{Parser, RuleType};
+use std::ops::ControlFlow;
+
+#[macro_use]
+mod common;
+
+#[enum_dispatch]
+pub trait Visit {
+    fn visit<R: RuleType>(&mut self, _pair: &Pair<'_, R>) {}
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "Visit",
+    walk_method = "visit",
+    namespaced = "true",
+    generate = "name, walk_until, context, stack"
+)]
+pub struct PlainParser;
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "Visit",
+    walk_method = "visit",
+    namespaced = "true",
+    generate = "name, walk_until, context, stack, dispatch_next",
+    transparent_rules = "Arguments, Argument"
+)]
+pub struct TransparentParser;
+
+rule_structs!(Visit for plain_parser);
+rule_structs!(Visit for transparent_parser);
+
+/// Records the rule names of the visited pairs.
+#[derive(Default)]
+struct Names(Vec<String>);
+
+impl Visit for Names {
+    fn visit<R: RuleType>(&mut self, pair: &Pair<'_, R>) {
+        // `Rule` is debugged with its payload, e.g. `Number(Number)`.
+        let rule = format!("{:?}", pair.as_rule());
+        self.0.push(rule.split('(').next().unwrap().to_string());
+    }
+}
+
+const INPUT: &str = "@print(\"hi\", 42); @exit();";
+
+fn main() -> anyhow::Result<()> {
+    // `walk_into()`: the wrappers are spliced out, the rest of the sequence is the same.
+    let mut plain = Names::default();
+    plain_parser::walk_into(
+        PlainParser::parse(plain_parser::Rule::Script(plain_parser::Script), INPUT)?,
+        &mut plain,
+    );
+    let mut transparent = Names::default();
+    transparent_parser::walk_into(
+        TransparentParser::parse(
+            transparent_parser::Rule::Script(transparent_parser::Script),
+            INPUT,
+        )?,
+        &mut transparent,
+    );
+    let spliced = plain
+        .0
+        .iter()
+        .filter(|name| !name.starts_with("Argument"))
+        .cloned()
+        .collect::<Vec<_>>();
+    assert_eq!(transparent.0, spliced);
+    assert_eq!(
+        transparent.0,
+        [
+            "Script",
+            "Statement",
+            "Command",
+            "Identifier",
+            "Strings",
+            "Number",
+            "Statement",
+            "Command",
+            "Identifier"
+        ]
+    );
+
+    // `dispatch_with_context()`: the values are children of `Command`, after its `Identifier`.
+    let mut contexts = Vec::new();
+    transparent_parser::dispatch_with_context(
+        TransparentParser::parse(
+            transparent_parser::Rule::Statement(transparent_parser::Statement),
+            "@print(\"hi\", 42);",
+        )?,
+        |context| {
+            contexts.push((
+                context.pair.as_rule().kind().name(),
+                context.depth,
+                context.parent.map(|parent| parent.name()),
+                context.index_in_parent,
+            ));
+            Ok::<_, ()>(())
+        },
+    )
+    .unwrap();
+    assert_eq!(
+        contexts,
+        [
+            ("Statement", 0, None, 0),
+            ("Command", 1, Some("Statement"), 0),
+            ("Identifier", 2, Some("Command"), 0),
+            ("Strings", 2, Some("Command"), 1),
+            ("Number", 2, Some("Command"), 2),
+        ]
+    );
+
+    // `walk_with_stack()`: the wrappers are not on the path either.
+    let mut paths = Vec::new();
+    transparent_parser::walk_with_stack(
+        TransparentParser::parse(
+            transparent_parser::Rule::Command(transparent_parser::Command),
+            "@print(42)",
+        )?,
+        |stack, _| {
+            let path = stack.path().iter().map(|kind| kind.name());
+            paths.push(path.collect::<Vec<_>>().join("/"));
+            Ok::<_, ()>(())
+        },
+    )
+    .unwrap();
+    assert_eq!(paths, ["Command", "Command/Identifier", "Command/Number"]);
+
+    // `walk_until()` never stops at a wrapper.
+    let pairs = TransparentParser::parse(
+        transparent_parser::Rule::Arguments(transparent_parser::Arguments),
+        "1, x",
+    )?;
+    let first = transparent_parser::walk_until(pairs, |pair| {
+        ControlFlow::Break(pair.as_rule().kind().name())
+    });
+    assert_eq!(first, Some("Number"));
+    assert!(transparent_parser::RuleKind::Argument.is_transparent());
+    assert!(!transparent_parser::RuleKind::Number.is_transparent());
+
+    // `dispatch_next()` hands over the pairs it is given, wrappers included.
+    let mut pairs = TransparentParser::parse(
+        transparent_parser::Rule::Arguments(transparent_parser::Arguments),
+        "1, x",
+    )?;
+    let visitor = |rule: transparent_parser::Rule, _| Ok::<_, ()>(rule.kind().name());
+    assert_eq!(
+        transparent_parser::dispatch_next(&mut pairs, &visitor),
+        Some(Ok("Arguments"))
+    );
+    println!("ok");
+    Ok(())
+}
@@ -0,0 +1,44 @@
+//! Looking rules up by name with `FromStr`, and the `UnknownRuleError` of a name which is not one of the grammar.
+//!
+//! The error suggests the closest rule name when it is a likely typo, i.e. a few edits away from the input.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(grammar = "examples/grammar.pest", interface = "ParserInterface")]
+pub struct LanguageParser;
+
+fn main() {
+    assert_eq!("Statement".parse::<RuleKind>(), Ok(RuleKind::Statement));
+    assert_eq!("EOI".parse::<Rule>(), Ok(Rule::EOI(EOI)));
+
+    // a near miss suggests the closest name.
+    let error = "Statment".parse::<RuleKind>().unwrap_err();
+    assert_eq!(error.input, "Statment");
+    assert_eq!(error.suggestion, Some("Statement"));
+    assert_eq!(
+        error.to_string(),
+        "unknown rule `Statment`; did you mean `Statement`?"
+    );
+    assert_eq!(
+        "argument".parse::<Rule>().unwrap_err().suggestion,
+        Some("Argument")
+    );
+
+    // a far miss suggests nothing.
+    let error = "Declaration".parse::<RuleKind>().unwrap_err();
+    assert_eq!(error.suggestion, None);
+    assert_eq!(error.to_string(), "unknown rule `Declaration`");
+    assert_eq!(UnknownRuleError::new("").suggestion, None);
+
+    let error: Box<dyn std::error::Error> = Box::new(UnknownRuleError::new("Numbr"));
+    assert_eq!(
+        error.to_string(),
+        "unknown rule `Numbr`; did you mean `Number`?"
+    );
+
+    println!("ok");
+}
@@ -0,0 +1,114 @@
+#!/usr/bin/env bash
+# Measures incremental rebuilds of a crate using `#[pest_parser]` with a synthetic grammar:
+# touching a handler module only, adding a single rule to the grammar, and adding a method to the
+# interface.
+#
+#   scripts/incremental_rebuild.sh [RULES]   (default: 100 rules)
+#
+# Set SPLIT=1 to use `#[pest_rules]` and `#[pest_dispatch]` instead of `#[pest_parser]`.
+#
+# Set TIMINGS=1 to additionally write cargo's `--timings` report of every rebuild to the crate's
+# `target/cargo-timings/`.
+set -euo pipefail
+
+RULES=${1:-100}
+ROOT=$(cd "$(dirname "$0")/.." && pwd)
+CRATE=$(mktemp -d)
+trap 'rm -rf "$CRATE"' EXIT
+mkdir -p "$CRATE/src"
+
+cat >"$CRATE/Cargo.toml" <<TOML
+[package]
+name = "incremental_rebuild"
+version = "0.1.0"
+edition = "2021"
+
+[dependencies]
+enum_dispatch = "^0.3"
+enum_dispatch_pest_parser = { path = "$ROOT" }
+pest = "^2.5"
+TOML
+
+# writes the grammar, the parser and one handler impl per rule; `extra` adds one more rule, `method` one
+# more (defaulted) interface method.
+generate() {
+    local extra=${1:-} method=${2:-}
+    local choices="" rules="EOI WHITESPACE top"
+    {
+        echo 'WHITESPACE = _{ " " }'
+        for ((i = 0; i < RULES; i++)); do
+            choices+="r$i | "
+            rules+=" r$i"
+            echo "r$i = { \"k$i\" ~ (\"(\" ~ r$(((i + 1) % RULES))? ~ \")\")? }"
+        done
+        if [[ -n $extra ]]; then
+            choices+="extra | "
+            rules+=" extra"
+            echo 'extra = { "x" }'
+        fi
+        echo "top = { SOI ~ (${choices% | })* ~ EOI }"
+    } >"$CRATE/src/grammar.pest"
+
+    local trait_attr parser_attr extra_method=""
+    if [[ -n $method ]]; then
+        extra_method='fn extra(&self) -> usize { 1 }'
+    fi
+    if [[ -n ${SPLIT:-} ]]; then
+        trait_attr='#[enum_dispatch_pest_parser::pest_dispatch(rules = "GrammarParser")]'
+        parser_attr='#[enum_dispatch_pest_parser::pest_rules(grammar = "src/grammar.pest")]'
+    else
+        trait_attr='#[enum_dispatch]'
+        parser_attr='#[enum_dispatch_pest_parser::pest_parser(grammar = "src/grammar.pest", interface = "Interface")]'
+    fi
+    cat >"$CRATE/src/main.rs" <<RUST
+#![allow(non_camel_case_types)]
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+
+mod handlers;
+
+$trait_attr
+pub trait Interface {
+    fn weight(&self) -> usize;
+    $extra_method
+}
+
+$parser_attr
+pub struct GrammarParser;
+
+fn main() {
+    let pairs = GrammarParser::parse(Rule::top(top), "k1(k2)").unwrap();
+    println!("{}", pairs.flatten().map(|pair| pair.as_rule().weight()).sum::<usize>());
+}
+RUST
+
+    {
+        echo 'use crate::*;'
+        for rule in $rules; do
+            echo "impl Interface for $rule { fn weight(&self) -> usize { (0..${#rule}).filter(|x| x % 3 == 0).count() } }"
+        done
+    } >"$CRATE/src/handlers.rs"
+}
+
+build() {
+    local label=$1 start end
+    start=$(date +%s%N)
+    cargo build --quiet --manifest-path "$CRATE/Cargo.toml" ${TIMINGS:+--timings}
+    end=$(date +%s%N)
+    printf '%-18s %6d ms\n' "$label" $(((end - start) / 1000000))
+}
+
+generate
+build "initial"
+for _ in 1 2; do
+    touch "$CRATE/src/handlers.rs"
+    build "touch handlers"
+    generate extra
+    build "add a rule"
+    generate
+    build "remove the rule"
+    generate "" method
+    build "add a method"
+    generate
+    build "remove the method"
+done
@@ -0,0 +1,203 @@
+//! The shapes of `pest_generator`'s output known to the hooking, selected with `pest_version`.
+
+use proc_macro2::{Delimiter, TokenStream, TokenTree};
+use syn::ItemEnum;
+
+/// What tells the output of one minor version of `pest_generator` from the others.
+///
+/// The extraction and the hooking are shared by all versions, since they only rely on the variants of
+/// `enum Rule` and on `Rule :: r#rule` paths, which have the same shape in all of them. The landmarks are the
+/// parts which differ, looked up to check that the output is the one `pest_version` declares.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) struct Landmarks {
+    /// `#[doc = "End-of-input"]` on the `EOI` variant of `enum Rule`, `None` for a grammar without `EOI`, which
+    /// has no such variant.
+    eoi_doc: Option<bool>,
+    /// `impl Rule { pub fn all_rules() ... }`, listing the rules but `EOI`.
+    all_rules: bool,
+}
+
+impl Landmarks {
+    /// looks the landmarks up in `pest`'s output (hooked or not) and its extracted `enum Rule`.
+    pub fn find(rule_enum: &ItemEnum, code: &TokenStream) -> Self {
+        let eoi_doc = rule_enum
+            .variants
+            .iter()
+            .find(|variant| variant.ident == "EOI")
+            .map(|eoi| eoi.attrs.iter().any(|attr| attr.path().is_ident("doc")));
+        // only the top-level items are looked at, a function body is a single group.
+        let tokens = code.clone().into_iter().collect::<Vec<_>>();
+        let all_rules = tokens.windows(3).any(|window| match window {
+            [TokenTree::Ident(item), TokenTree::Ident(name), TokenTree::Group(body)]
+                if item == "impl" && name == "Rule" && body.delimiter() == Delimiter::Brace =>
+            {
+                body.stream()
+                    .into_iter()
+                    .any(|token| matches!(token, TokenTree::Ident(ident) if ident == "all_rules"))
+            }
+            _ => false,
+        });
+        Self { eoi_doc, all_rules }
+    }
+
+    /// whether these landmarks, found in an output, are the `expected` ones of a version.
+    fn matches(&self, expected: &Self) -> bool {
+        self.all_rules == expected.all_rules
+            && self
+                .eoi_doc
+                .is_none_or(|eoi_doc| Some(eoi_doc) == expected.eoi_doc)
+    }
+
+    /// describes the landmark the output of `adapter` has and this output does not, or the other way around.
+    fn difference(&self, adapter: &Adapter) -> String {
+        let expected = adapter.landmarks;
+        let (missing, landmark) = if self.all_rules != expected.all_rules {
+            (expected.all_rules, "`Rule::all_rules()`")
+        } else {
+            (
+                expected.eoi_doc == Some(true),
+                "a doc comment on `Rule::EOI`",
+            )
+        };
+        if missing {
+            format!("it has no {landmark}")
+        } else {
+            format!("it has {landmark}")
+        }
+    }
+}
+
+/// One minor version of `pest` whose output is known to work with the hooking.
+pub(crate) struct Adapter {
+    /// the version given to `pest_version`, e.g. `"2.7"`.
+    pub version: &'static str,
+    landmarks: Landmarks,
+}
+
+/// The supported versions, oldest first.
+pub(crate) const ADAPTERS: &[Adapter] = &[
+    Adapter {
+        version: "2.5",
+        landmarks: Landmarks {
+            eoi_doc: Some(false),
+            all_rules: false,
+        },
+    },
+    Adapter {
+        version: "2.7",
+        landmarks: Landmarks {
+            eoi_doc: Some(true),
+            all_rules: true,
+        },
+    },
+];
+
+/// returns the adapter of `version`, panicking on an unsupported one.
+pub(crate) fn adapter(version: &str) -> &'static Adapter {
+    ADAPTERS
+        .iter()
+        .find(|adapter| adapter.version == version)
+        .unwrap_or_else(|| {
+            panic!(
+                "unsupported `pest_version` `{version}`, expected one of {:?}",
+                ADAPTERS
+                    .iter()
+                    .map(|adapter| adapter.version)
+                    .collect::<Vec<_>>()
+            )
+        })
+}
+
+impl Adapter {
+    /// checks that the output with the `found` landmarks is the one of this version.
+    pub fn check(&self, found: Landmarks) -> Result<(), String> {
+        if found.matches(&self.landmarks) {
+            return Ok(());
+        }
+        let detected = ADAPTERS
+            .iter()
+            .find(|adapter| found.matches(&adapter.landmarks))
+            .map_or_else(
+                || String::from("an unknown version"),
+                |adapter| format!("pest {}", adapter.version),
+            );
+        Err(format!(
+            "`pest_version = \"{}\"` does not match the output of `pest_generator`, which looks like {detected}: {}. \
+             Update `pest_version`, or the `pest` dependency to {}",
+            self.version,
+            found.difference(self),
+            self.version
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::PestParserArgs;
+    use crate::{check_hooked_codes, hook_raw_codes, pest_rule_enum};
+    use quote::quote;
+    use syn::parse_quote;
+
+    /// the fixtures are the outputs of `pest_generator` for the same grammar, captured with each version.
+    const FIXTURES: &[(&str, &str)] = &[
+        ("2.5", include_str!("../tests/fixtures/pest-2.5.7.rs")),
+        ("2.7", include_str!("../tests/fixtures/pest-2.7.15.rs")),
+    ];
+
+    fn landmarks(fixture: &str) -> (Landmarks, ItemEnum, TokenStream) {
+        let code = fixture.parse::<TokenStream>().unwrap();
+        let rule_enum = pest_rule_enum(code.clone());
+        (Landmarks::find(&rule_enum, &code), rule_enum, code)
+    }
+
+    #[test]
+    fn each_fixture_matches_its_adapter_only() {
+        for (version, fixture) in FIXTURES {
+            let (found, _, _) = landmarks(fixture);
+            for adapter in ADAPTERS {
+                assert_eq!(
+                    adapter.check(found).is_ok(),
+                    adapter.version == *version,
+                    "output of pest {version} checked as pest {}",
+                    adapter.version
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn mismatch_names_the_detected_version() {
+        let (found, _, _) = landmarks(FIXTURES[1].1);
+        let error = adapter("2.5").check(found).unwrap_err();
+        assert!(error.contains("looks like pest 2.7"), "{error}");
+        assert!(error.contains("it has `Rule::all_rules()`"), "{error}");
+    }
+
+    #[test]
+    fn every_fixture_is_hooked() {
+        let args = PestParserArgs::from_arguments(parse_quote! {
+            grammar = "fixture.pest", interface = "Interface"
+        });
+        for (_, fixture) in FIXTURES {
+            let (_, rule_enum, code) = landmarks(fixture);
+            let variants = rule_enum
+                .variants
+                .iter()
+                .map(|variant| variant.ident.to_string())
+                .collect::<Vec<_>>();
+            assert_eq!(
+                variants,
+                ["EOI", "r#WHITESPACE", "r#statement", "r#call", "r#ident"]
+            );
+            let hooked = hook_raw_codes(code, &rule_enum, &args, quote!(Interface));
+            check_hooked_codes(&hooked, &rule_enum);
+        }
+    }
+
+    #[test]
+    #[should_panic(expected = "unsupported `pest_version` `2.6`")]
+    fn unknown_version() {
+        adapter("2.6");
+    }
+}
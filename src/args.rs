@@ -0,0 +1,1392 @@
+//! Parsing of the `#[pest_parser(...)]` attribute arguments.
+
+use crate::adapter::adapter;
+use crate::grammar::GrammarSource;
+use proc_macro2::{Span, TokenStream};
+use quote::quote;
+use std::collections::HashMap;
+use syn::{
+    ext::IdentExt,
+    parenthesized,
+    parse::{ParseStream, Parser},
+    parse_quote, parse_str,
+    punctuated::Punctuated,
+    Attribute, Expr, Ident, ItemEnum, Lit, LitStr, MacroDelimiter, Meta, MetaList, MetaNameValue,
+    Path, Token,
+};
+
+/// Optional helpers selected with `generate = "..."`, and whether they are generated when `generate` is omitted.
+///
+/// - `iter`: `RuleKind::iter()`
+/// - `name`: `RuleKind::name()`, `RuleKind::from_name()`, `RuleName`, `FromStr` and `UnknownRuleError`
+/// - `compat`: `CompatParser`
+/// - `tokens`: `tokens()`, `tokens_with()` and `Token`
+/// - `rewrite`: `rewrite()` and `RewriteError`
+/// - `docs`: `RULE_DOCS` and `doc_of()`
+/// - `walk_until`: `walk_until()`, `walk_until_with()` and `Descend`
+/// - `tree`: `ParseNode` and `parse_tree()`
+/// - `validate`: `validate()`
+/// - `dispatch_next`: `dispatch_next()`
+/// - `match_pairs`: `match_pairs!` and `MatchPairsError`
+/// - `parse`: `RuleKind::parse()`
+/// - `context`: `RuleContext` and `dispatch_with_context()`
+/// - `parse_rule`: `RuleMarker` and `ParserExt::parse_rule()`
+/// - `stack`: `RuleStack` and `walk_with_stack()`
+/// - `assert_parses`: `assert_parses!`
+/// - `map`: `map_pairs()`, `map_tree()` and `Tree`
+/// - `output_enum`: `rule_output!`, for `#[pest_dispatch(output_enum = "...")]`
+/// - `all_variants`: `all_variants!`
+/// - `rule_map`: `RuleMap<T>`
+/// - `delegate`: `delegate_rules!`, for `#[pest_dispatch(delegate_to = "...")]`
+/// - `ffi`: `RuleKind::to_u32()`, `RuleKind::from_u32()`, `From<RuleKind>` and `TryFrom<u32>`
+pub(crate) const HELPERS: &[(&str, bool)] = &[
+    ("iter", true),
+    ("name", true),
+    ("compat", false),
+    ("tokens", false),
+    ("rewrite", false),
+    ("docs", false),
+    ("walk_until", false),
+    ("tree", false),
+    ("validate", false),
+    ("dispatch_next", false),
+    ("match_pairs", false),
+    ("parse", false),
+    ("context", false),
+    ("parse_rule", false),
+    ("stack", false),
+    ("assert_parses", false),
+    ("map", false),
+    ("output_enum", false),
+    ("all_variants", false),
+    ("rule_map", false),
+    ("delegate", false),
+    ("ffi", false),
+];
+
+/// Helpers embedding the names or docs of the rules, which are not available with `strings = "minimal"`.
+const NAMED_HELPERS: &[&str] = &["name", "docs"];
+
+/// Names of all arguments accepted by `#[pest_parser]`.
+const ARGUMENTS: &[&str] = &[
+    "grammar",
+    "grammar_inline",
+    "interface",
+    "kind_derives",
+    "rule_attr",
+    "generate",
+    "walk_method",
+    "recover",
+    "struct_style",
+    "struct_path",
+    "struct_module",
+    "reuse_parser",
+    "hooked_parser",
+    "strings",
+    "group",
+    "enum_dispatch_path",
+    "sync_points",
+    "pest_version",
+    "namespaced",
+    "module",
+    "thread_safe",
+    "max_depth",
+    "sealed",
+    "parser_derive",
+    "assert_impls",
+    "transparent_rules",
+    "rename_strategy",
+    "error_type",
+    "doc_examples",
+];
+
+/// How the dispatch payloads carried by the variants of `enum Rule` are generated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum StructStyle {
+    /// one unit struct per rule, e.g. `Rule::r#Script(crate::r#Script)`.
+    Structs,
+    /// a single fieldless `enum RuleStruct`, e.g. `Rule::r#Script(crate::RuleStruct::r#Script)`.
+    Enum,
+}
+
+/// A step of `rename_strategy`, turning the name of a rule into the name of its struct.
+#[derive(Clone, Debug, Eq, PartialEq)]
+pub(crate) enum RenameStep {
+    /// `strip_prefix:tok_`, removing `tok_` from the names starting with it.
+    StripPrefix(String),
+    /// `strip_suffix:_rule`, removing `_rule` from the names ending with it.
+    StripSuffix(String),
+    /// `pascal_case`, capitalizing the words separated by underscores and joining them, e.g. `add_op` to `AddOp`.
+    PascalCase,
+}
+
+impl RenameStep {
+    fn apply(&self, name: &str) -> String {
+        match self {
+            RenameStep::StripPrefix(prefix) => name
+                .strip_prefix(prefix.as_str())
+                .unwrap_or(name)
+                .to_string(),
+            RenameStep::StripSuffix(suffix) => name
+                .strip_suffix(suffix.as_str())
+                .unwrap_or(name)
+                .to_string(),
+            RenameStep::PascalCase => name
+                .split('_')
+                .map(|word| {
+                    let mut chars = word.chars();
+                    chars
+                        .next()
+                        .map(|first| first.to_uppercase().chain(chars).collect::<String>())
+                        .unwrap_or_default()
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Which string tables about the rules are generated.
+#[derive(Clone, Copy, Debug, Eq, PartialEq)]
+pub(crate) enum Strings {
+    /// rule names and docs are available at runtime, through the helpers which need them.
+    Full,
+    /// no rule name or doc is embedded, the APIs based on them are replaced by stubs failing to compile.
+    Minimal,
+}
+
+/// All arguments accepted by `#[pest_parser]`.
+pub(crate) struct PestParserArgs {
+    /// the grammar file given to `grammar`, the source given to `grammar_inline`, or the grammar given as tokens to
+    /// `pest_parser_tokens!`.
+    pub grammar: GrammarSource,
+    /// the `enum_dispatch` trait which is dispatched by `enum Rule`, `None` with `#[pest_rules]`.
+    pub interface: Option<String>,
+    /// extra derives appended to the derive list of `enum RuleKind`.
+    pub kind_derives: Vec<Path>,
+    /// extra attributes placed on `enum Rule`, one per `rule_attr` argument.
+    pub rule_attrs: Vec<Attribute>,
+    /// enabled optional helpers, see [`HELPERS`].
+    pub generate: Vec<String>,
+    /// the `&mut self` interface method called on the accumulator by `walk_into()`.
+    pub walk_method: Option<Ident>,
+    /// whether `walk_method` is fallible and `walk_into()` collects its errors, see `recover`.
+    pub recover: bool,
+    /// the error of `walk_into()` with `recover`, `RuleKind::parse()` and `parse_rule()` instead of their own, see
+    /// `error_type`.
+    pub error_type: Option<Path>,
+    /// how the dispatch payloads are generated.
+    pub struct_style: StructStyle,
+    /// the module the rule structs are emitted in by `rule_structs!`, instead of next to the parser.
+    pub struct_path: Option<Path>,
+    /// the module the rule structs are emitted in next to the generated items, a name (e.g. `rules`) or its path
+    /// from the crate root (e.g. `crate::lang::rules`), see `struct_module`.
+    pub struct_module: Option<Path>,
+    /// an existing `pest` parser whose rules are dispatched, instead of generating a parser.
+    pub reuse_parser: Option<Path>,
+    /// the derive (e.g. of a fork of `pest`) generating the parser, which is then dispatched like `reuse_parser`.
+    pub parser_derive: Option<Path>,
+    /// whether `pest::Parser` is implemented by hand, calling the `ParseHooks` of the parser around `pest`'s.
+    pub hooked_parser: bool,
+    /// which string tables about the rules are generated.
+    pub strings: Strings,
+    /// the rule structs shared by several rules, each with its rules (unraw, e.g. `Script`), see `group`.
+    pub groups: Vec<(String, Vec<String>)>,
+    /// the rules (unraw, e.g. `Strings`) carrying a type of the user instead of a generated struct, see `override`.
+    pub overrides: Vec<(String, Path)>,
+    /// the crate (or module) `enum_dispatch` is taken from, `None` for the plain `#[enum_dispatch]`.
+    pub enum_dispatch_path: Option<Path>,
+    /// the rules (unraw) returned by `Rule::sync_points()`, `None` for the ones found by its heuristic.
+    #[cfg_attr(not(feature = "pest_meta"), allow(dead_code))]
+    pub sync_points: Option<Vec<String>>,
+    /// the rules (unraw) whose pairs the walkers descend into without handing them to the user.
+    pub transparent_rules: Vec<String>,
+    /// the value of `transparent_rules`, which its unknown rules are reported on.
+    pub transparent_rules_span: Span,
+    /// the steps turning the name of every rule into the name of its struct, in order, see `rename_strategy`.
+    pub rename_strategy: Vec<RenameStep>,
+    /// the minor version of `pest` whose output is expected, e.g. `"2.7"`, `None` to accept any known shape.
+    pub pest_version: Option<String>,
+    /// whether everything but the parser struct is emitted in a module of its own, see `module`.
+    pub namespaced: bool,
+    /// the module of a namespaced parser (e.g. `crate::json_parser`), `None` until the parser is named, see
+    /// [`PestParserArgs::namespace`].
+    pub module: Option<Path>,
+    /// whether `Rule`, `RuleKind` and the parser are asserted to be `Send + Sync` at compile time.
+    pub thread_safe: bool,
+    /// whether `Rule` and the rule structs implement a `sealed::Sealed`, which the interface takes as supertrait.
+    pub sealed: bool,
+    /// whether every payload of `Rule` is asserted to implement the interface, see `assert_impls`.
+    pub assert_impls: bool,
+    /// the deepest nesting of pairs `parse_tree()` and `map_tree()` descend into, `None` for no limit.
+    pub max_depth: Option<usize>,
+    /// whether the `example:` and `counterexample:` lines of the rule docs are turned into tests, see `doc_examples`.
+    pub doc_examples: bool,
+}
+
+impl PestParserArgs {
+    pub fn from_arguments(args: Punctuated<Meta, Token![,]>) -> Self {
+        Self::with_grammar(args, None)
+    }
+
+    /// same as [`PestParserArgs::from_arguments`], for `pest_parser_tokens!` which gives the grammar as `tokens`.
+    pub fn from_tokens(args: Punctuated<Meta, Token![,]>, tokens: GrammarSource) -> Self {
+        Self::with_grammar(args, Some(tokens))
+    }
+
+    fn with_grammar(args: Punctuated<Meta, Token![,]>, tokens: Option<GrammarSource>) -> Self {
+        let mut grammar = None;
+        let mut grammar_inline = None;
+        let mut interface = None;
+        let mut kind_derives = Vec::new();
+        let mut rule_attrs = Vec::new();
+        let mut generate = None;
+        let mut walk_method = None;
+        let mut recover = None;
+        let mut error_type = None;
+        let mut struct_style = None;
+        let mut struct_path = None;
+        let mut struct_module = None;
+        let mut reuse_parser = None;
+        let mut parser_derive = None;
+        let mut hooked_parser = None;
+        let mut strings = None;
+        let mut groups = Vec::new();
+        let mut overrides = Vec::new();
+        let mut enum_dispatch_path = None;
+        let mut sync_points = None;
+        let mut transparent_rules = None;
+        let mut rename_strategy = None;
+        let mut pest_version = None;
+        let mut namespaced = None;
+        let mut module = None;
+        let mut thread_safe = None;
+        let mut max_depth = None;
+        let mut sealed = None;
+        let mut assert_impls = None;
+        let mut doc_examples = None;
+
+        for arg in args {
+            let arg = match arg {
+                Meta::NameValue(arg) => arg,
+                Meta::List(list) if list.path.is_ident("group") => {
+                    groups.extend(parse_groups(&list.tokens));
+                    continue;
+                }
+                Meta::List(list) if list.path.is_ident("override") => {
+                    overrides.extend(parse_overrides(&list.tokens));
+                    continue;
+                }
+                _ => panic!(
+                    "arguments must be `key = \"value\"`, `group(Struct = \"rules\")` or `override(Rule = \"Type\")`"
+                ),
+            };
+            let (key, value) = get_argument_literal(arg);
+            if key == "grammar_inline" {
+                assert!(
+                    grammar_inline.is_none(),
+                    "argument `grammar_inline` is given more than once"
+                );
+                grammar_inline = Some((value.value(), value.span()));
+                continue;
+            }
+            if key == "transparent_rules" {
+                assert!(
+                    transparent_rules.is_none(),
+                    "argument `transparent_rules` is given more than once"
+                );
+                transparent_rules = Some((value.value(), value.span()));
+                continue;
+            }
+            let value = value.value();
+            let slot = match key.as_str() {
+                "grammar" => &mut grammar,
+                "interface" => &mut interface,
+                "kind_derives" => {
+                    kind_derives = Punctuated::<Path, Token![,]>::parse_terminated
+                        .parse_str(&value)
+                        .unwrap_or_else(|err| {
+                            panic!("`kind_derives` must be a comma separated list of paths: {err}")
+                        })
+                        .into_iter()
+                        .collect();
+                    continue;
+                }
+                "rule_attr" => {
+                    rule_attrs.push(parse_rule_attr(&value));
+                    continue;
+                }
+                "generate" => &mut generate,
+                "walk_method" => &mut walk_method,
+                "recover" => &mut recover,
+                "error_type" => &mut error_type,
+                "struct_style" => &mut struct_style,
+                "struct_path" => &mut struct_path,
+                "struct_module" => &mut struct_module,
+                "reuse_parser" => &mut reuse_parser,
+                "parser_derive" => &mut parser_derive,
+                "hooked_parser" => &mut hooked_parser,
+                "strings" => &mut strings,
+                "enum_dispatch_path" => &mut enum_dispatch_path,
+                "sync_points" => &mut sync_points,
+                "rename_strategy" => &mut rename_strategy,
+                "pest_version" => &mut pest_version,
+                "namespaced" => &mut namespaced,
+                "module" => &mut module,
+                "thread_safe" => &mut thread_safe,
+                "max_depth" => &mut max_depth,
+                "sealed" => &mut sealed,
+                "assert_impls" => &mut assert_impls,
+                "doc_examples" => &mut doc_examples,
+                "inherent_dispatch" => panic!(
+                    "`inherent_dispatch` copies the methods of the interface, which `#[pest_parser]` only knows by \
+                     name; split the parser with `#[pest_rules]` and give it to `#[pest_dispatch]` on the trait"
+                ),
+                "group" => panic!("`group` is given as `group(Struct = \"RuleA, RuleB\")`"),
+                "override" => panic!("`override` is given as `override(Rule = \"crate::Type\")`"),
+                _ => panic!("unknown argument `{key}`, expected one of {ARGUMENTS:?}"),
+            };
+            assert!(slot.is_none(), "argument `{key}` is given more than once");
+            *slot = Some(value);
+        }
+
+        // `parser_derive` generates the parser outside of this crate too, so both have the same restrictions.
+        let reused = match (&reuse_parser, &parser_derive) {
+            (Some(_), Some(_)) => {
+                panic!("`reuse_parser` and `parser_derive` both give the parser, give one or the other")
+            }
+            (Some(_), None) => Some("reuse_parser"),
+            (None, Some(_)) => Some("parser_derive"),
+            (None, None) => None,
+        };
+
+        let strings = match strings.as_deref() {
+            None | Some("full") => Strings::Full,
+            Some("minimal") => Strings::Minimal,
+            Some(strings) => panic!("unknown `strings` `{strings}`, expected `full` or `minimal`"),
+        };
+
+        let generate: Vec<String> = match generate {
+            Some(generate) => split_list(&generate)
+                .inspect(|helper| {
+                    assert!(
+                        HELPERS.iter().any(|(known, _)| known == helper),
+                        "unknown helper `{helper}` in `generate`, expected some of {:?}",
+                        HELPERS.iter().map(|(known, _)| known).collect::<Vec<_>>()
+                    )
+                })
+                .collect(),
+            None => HELPERS
+                .iter()
+                .filter(|(_, default)| *default)
+                .filter(|(helper, _)| strings == Strings::Full || !NAMED_HELPERS.contains(helper))
+                .map(|(helper, _)| helper.to_string())
+                .collect(),
+        };
+        if strings == Strings::Minimal {
+            for helper in NAMED_HELPERS {
+                assert!(
+                    !generate.iter().any(|enabled| enabled == helper),
+                    "helper `{helper}` is disabled by `strings = \"minimal\"`, which omits the rule names and docs"
+                );
+            }
+        }
+
+        let recover = parse_flag("recover", recover);
+        assert!(
+            !recover || walk_method.is_some(),
+            "`recover` applies to `walk_into()`, which requires `walk_method`"
+        );
+        assert!(
+            error_type.is_none()
+                || recover
+                || generate.iter().any(|helper| helper == "parse" || helper == "parse_rule"),
+            "`error_type` is the error of `walk_into()` with `recover`, `RuleKind::parse()` and `parse_rule()`, none of \
+             which is generated"
+        );
+
+        let struct_style = match struct_style.as_deref() {
+            None | Some("structs") => StructStyle::Structs,
+            Some("enum") => StructStyle::Enum,
+            Some(style) => {
+                panic!("unknown `struct_style` `{style}`, expected `structs` or `enum`")
+            }
+        };
+        assert!(
+            struct_path.is_none() || struct_style == StructStyle::Structs,
+            "`struct_path` requires `struct_style = \"structs\"`"
+        );
+        if struct_module.is_some() {
+            assert!(
+                struct_style == StructStyle::Structs,
+                "`struct_module` requires `struct_style = \"structs\"`, the enum style has no struct per rule"
+            );
+            assert!(
+                struct_path.is_none(),
+                "`struct_module` is not available with `struct_path`, whose structs are emitted by `rule_structs!`"
+            );
+        }
+        if rename_strategy.is_some() {
+            assert!(
+                struct_style == StructStyle::Structs,
+                "`rename_strategy` requires `struct_style = \"structs\"`, the enum style has no struct per rule"
+            );
+            assert!(
+                struct_path.is_none(),
+                "`rename_strategy` is not available with `struct_path`, whose structs are emitted by `rule_structs!`"
+            );
+        }
+        if !groups.is_empty() {
+            assert!(
+                struct_style == StructStyle::Structs,
+                "`group` requires `struct_style = \"structs\"`, the enum style implements the interface once anyway"
+            );
+            assert!(
+                struct_path.is_none(),
+                "`group` is not available with `struct_path`, whose structs are emitted by `rule_structs!`"
+            );
+            if let Some(reused) = reused {
+                panic!("`group` is not available with `{reused}`");
+            }
+        }
+        if !overrides.is_empty() {
+            assert!(
+                struct_style == StructStyle::Structs,
+                "`override` requires `struct_style = \"structs\"`, the enum style has no type per rule"
+            );
+            assert!(
+                groups.is_empty(),
+                "`override` is not available with `group`, whose payloads are kept in a `static`"
+            );
+        }
+        for (index, (rule, _)) in overrides.iter().enumerate() {
+            assert!(
+                !overrides[..index].iter().any(|(other, _)| other == rule),
+                "rule `{rule}` is overridden more than once"
+            );
+        }
+        let mut grouped = HashMap::new();
+        for (group, rules) in &groups {
+            for rule in rules {
+                if let Some(other) = grouped.insert(rule, group) {
+                    panic!("rule `{rule}` is in both `group({other})` and `group({group})`");
+                }
+            }
+        }
+
+        assert!(
+            struct_style == StructStyle::Structs || !generate.iter().any(|helper| helper == "parse_rule"),
+            "helper `parse_rule` requires `struct_style = \"structs\"`, the enum style has no type per rule"
+        );
+        assert!(
+            struct_style == StructStyle::Structs || !generate.iter().any(|helper| helper == "output_enum"),
+            "helper `output_enum` requires `struct_style = \"structs\"`, the enum style has one output type for all rules"
+        );
+        assert!(
+            struct_style == StructStyle::Structs || !generate.iter().any(|helper| helper == "delegate"),
+            "helper `delegate` requires `struct_style = \"structs\"`, the enum style has one struct for all rules"
+        );
+
+        // these helpers walk `Pairs<Rule>`, but the pairs of a reused parser carry its own `Rule`.
+        if let Some(reused) = reused {
+            for helper in [
+                "compat",
+                "tokens",
+                "rewrite",
+                "walk_until",
+                "tree",
+                "validate",
+                "dispatch_next",
+                "match_pairs",
+                "parse",
+                "context",
+                "parse_rule",
+                "stack",
+                "assert_parses",
+                "map",
+                "output_enum",
+                "delegate",
+            ] {
+                assert!(
+                    !generate.iter().any(|enabled| enabled == helper),
+                    "helper `{helper}` is not available with `{reused}`"
+                );
+            }
+            assert!(
+                walk_method.is_none(),
+                "`walk_method` is not available with `{reused}`"
+            );
+        }
+        assert!(
+            sync_points.is_none() || cfg!(feature = "pest_meta"),
+            "`sync_points` overrides `Rule::sync_points()`, which requires the `pest_meta` feature"
+        );
+        if let Some(version) = &pest_version {
+            adapter(version);
+            if let Some(reused) = reused {
+                panic!("`pest_version` is not available with `{reused}`, the parser is not generated by `pest_generator` here");
+            }
+        }
+        let hooked_parser = parse_flag("hooked_parser", hooked_parser);
+        if let (true, Some(reused)) = (hooked_parser, reused) {
+            panic!("`hooked_parser` is not available with `{reused}`, the parser is not generated here");
+        }
+        let doc_examples = parse_flag("doc_examples", doc_examples);
+        if let (true, Some(reused)) = (doc_examples, reused) {
+            panic!("`doc_examples` is not available with `{reused}`, whose pairs carry the `Rule` of its parser");
+        }
+
+        let max_depth = max_depth.map(|depth| {
+            depth
+                .parse::<usize>()
+                .ok()
+                .filter(|depth| *depth > 0)
+                .unwrap_or_else(|| panic!("`max_depth` must be a positive number, found `{depth}`"))
+        });
+        assert!(
+            max_depth.is_none() || generate.iter().any(|helper| helper == "tree" || helper == "map"),
+            "`max_depth` bounds `parse_tree()` and `map_tree()`, enable `tree` or `map` in `generate`"
+        );
+
+        // a given `module` implies `namespaced`, it only overrides the module named after the parser.
+        let module = module.map(|module| parse_module(&module));
+        let namespaced = parse_flag("namespaced", namespaced) || module.is_some();
+
+        let grammar = match (grammar, grammar_inline, tokens) {
+            (Some(_), Some(_), _) => {
+                panic!("`grammar` and `grammar_inline` are both given, give either the grammar file or its source")
+            }
+            (Some(_), None, Some(_)) | (None, Some(_), Some(_)) => {
+                panic!("`pest_parser_tokens!` takes the grammar in its `grammar {{ ... }}` block, not as argument")
+            }
+            (Some(grammar), None, None) => GrammarSource::File(grammar),
+            (None, Some((source, span)), None) => GrammarSource::Inline(source, span),
+            (None, None, Some(tokens)) => tokens,
+            (None, None, None) => panic!("missing argument `grammar` (or `grammar_inline`)"),
+        };
+
+        Self {
+            grammar,
+            interface,
+            kind_derives,
+            rule_attrs,
+            generate,
+            walk_method: walk_method.map(parse_walk_method),
+            recover,
+            error_type: error_type.map(parse_error_type),
+            struct_style,
+            struct_path: struct_path.map(|path| {
+                parse_str(&path)
+                    .unwrap_or_else(|err| panic!("`struct_path` must be a module path: {err}"))
+            }),
+            struct_module: struct_module.map(|module| parse_struct_module(&module)),
+            reuse_parser: reuse_parser.map(|parser| {
+                parse_str(&parser).unwrap_or_else(|err| {
+                    panic!("`reuse_parser` must be a path to a parser: {err}")
+                })
+            }),
+            parser_derive: parser_derive.map(|derive| {
+                parse_str(&derive).unwrap_or_else(|err| {
+                    panic!("`parser_derive` must be a path to a derive macro: {err}")
+                })
+            }),
+            hooked_parser,
+            strings,
+            groups,
+            overrides,
+            enum_dispatch_path: enum_dispatch_path.map(parse_enum_dispatch_path),
+            sync_points: sync_points.map(|rules| split_list(&rules).collect()),
+            transparent_rules: transparent_rules
+                .as_ref()
+                .map(|(rules, _)| split_list(rules).collect())
+                .unwrap_or_default(),
+            transparent_rules_span: transparent_rules
+                .map_or_else(Span::call_site, |(_, span)| span),
+            rename_strategy: rename_strategy
+                .map(|strategy| split_list(&strategy).map(parse_rename_step).collect())
+                .unwrap_or_default(),
+            pest_version,
+            namespaced,
+            module,
+            thread_safe: parse_flag("thread_safe", thread_safe),
+            sealed: parse_flag("sealed", sealed),
+            assert_impls: parse_flag("assert_impls", assert_impls),
+            doc_examples,
+            max_depth,
+        }
+    }
+
+    /// names the module of a namespaced parser after `parser` (`JsonParser` is emitted in `crate::json_parser`),
+    /// unless `module` is given. Called once the parser struct is known, before anything is generated.
+    pub fn namespace(&mut self, parser: &Ident) {
+        if self.namespaced && self.module.is_none() {
+            let module = Ident::new(&snake_case(&parser.unraw().to_string()), parser.span());
+            self.module = Some(parse_quote!(crate::#module));
+        }
+    }
+
+    /// returns the name of the module of a namespaced parser, emitted next to the parser struct.
+    pub fn module_ident(&self) -> Option<&Ident> {
+        self.module.as_ref().map(|module| {
+            &module
+                .segments
+                .last()
+                .expect("`module` must not be empty")
+                .ident
+        })
+    }
+
+    /// returns the interface as a path, so it can be referred to from generated code.
+    pub fn interface_path(&self) -> Path {
+        let interface = self
+            .interface
+            .as_ref()
+            .expect("missing argument `interface`");
+        parse_str(interface)
+            .unwrap_or_else(|err| panic!("`interface` must be a path to a trait: {err}"))
+    }
+
+    /// returns the `enum_dispatch` attribute of `enum Rule`: linked to the interface, or only registering
+    /// the enum with `#[pest_rules]`, leaving the link to `#[pest_dispatch]` on the interface.
+    pub fn enum_dispatch_attr(&self) -> TokenStream {
+        let interface = self.interface.as_ref().map(|_| self.interface_path());
+        self.enum_dispatch_attr_with(quote!(#interface))
+    }
+
+    /// same as [`PestParserArgs::enum_dispatch_attr`], but naming `interface` instead, e.g. a placeholder.
+    pub fn enum_dispatch_attr_with(&self, interface: TokenStream) -> TokenStream {
+        let enum_dispatch = enum_dispatch_attr_path(self.enum_dispatch_path.as_ref());
+        match &self.interface {
+            Some(_) => quote!(#[#enum_dispatch(#interface)]),
+            None => quote!(#[#enum_dispatch]),
+        }
+    }
+
+    /// sums up everything the hooking of `pest`'s output depends on besides the grammar and the interface itself:
+    /// the payloads (`struct_style`, `struct_path`, `struct_module`, `group`, `override`, `module`, `rename_strategy`),
+    /// `rule_attr`, `strings`, `enum_dispatch_path` and whether there is an interface at all. See `hooked_parser_cached`.
+    pub fn hook_key(&self) -> String {
+        let rule_attrs = &self.rule_attrs;
+        let enum_dispatch = enum_dispatch_attr_path(self.enum_dispatch_path.as_ref());
+        let overrides = self
+            .overrides
+            .iter()
+            .map(|(rule, ty)| format!("{rule}={}", quote!(#ty)))
+            .collect::<Vec<_>>();
+        format!(
+            "{:?} {} {:?} {:?} {:?} {:?} {} {} {}",
+            self.struct_style,
+            self.struct_root(),
+            self.groups,
+            overrides,
+            self.rename_strategy,
+            self.strings,
+            self.interface.is_some(),
+            quote!(#(#rule_attrs)*),
+            enum_dispatch,
+        )
+    }
+
+    /// checks the arguments for `#[pest_rules]`, which leaves everything about the interface to `#[pest_dispatch]`.
+    pub fn assert_rules_only(&self) {
+        assert!(
+            self.interface.is_none(),
+            "`interface` is given to `#[pest_dispatch]` instead of `#[pest_rules]`"
+        );
+        assert!(
+            self.walk_method.is_none(),
+            "`walk_method` is given to `#[pest_dispatch]` instead of `#[pest_rules]`"
+        );
+        assert!(
+            self.groups.is_empty(),
+            "`group` is not available with `#[pest_rules]`, `#[pest_dispatch]` cannot dispatch grouped rules"
+        );
+    }
+
+    /// checks the rules named by `group`, `override` and `transparent_rules` against the variants of `rule_enum`,
+    /// once the grammar is known. An unknown rule in `transparent_rules` is reported on its value.
+    pub fn check_rule_names(&self, rule_enum: &ItemEnum) -> syn::Result<()> {
+        let known = |rule: &str| {
+            rule_enum
+                .variants
+                .iter()
+                .any(|variant| variant.ident.unraw() == rule)
+        };
+        for (group, rules) in &self.groups {
+            for rule in rules {
+                assert!(known(rule), "unknown rule `{rule}` in `group({group})`");
+            }
+        }
+        for (rule, _) in &self.overrides {
+            assert!(known(rule), "unknown rule `{rule}` in `override`");
+        }
+        match self.transparent_rules.iter().find(|rule| !known(rule)) {
+            Some(rule) => Err(syn::Error::new(
+                self.transparent_rules_span,
+                format!("unknown rule `{rule}` in `transparent_rules`"),
+            )),
+            None => Ok(()),
+        }
+    }
+
+    /// returns the payload types of the variants `rules` of `enum Rule`, without duplicates (a group, `RuleStruct`
+    /// or an overriding type may be carried by several variants), in the order of their first variant.
+    pub fn payload_paths(&self, rules: &[Ident]) -> Vec<Path> {
+        let mut payloads = Vec::new();
+        for rule in rules {
+            let payload = self.payload_type(&rule.to_string());
+            if !payloads.contains(&payload) {
+                payloads.push(payload);
+            }
+        }
+        payloads
+            .iter()
+            .map(|payload| parse_str(payload).expect("illegal payload type"))
+            .collect()
+    }
+
+    /// returns the payload type of the variant `rule` of `enum Rule`, as code.
+    ///
+    /// `rule` is spliced in textually, as stringified by `pest` (e.g. `r#Script`).
+    pub fn payload_type(&self, rule: &str) -> String {
+        if let Some(ty) = self.override_of(rule) {
+            return quote!(#ty).to_string();
+        }
+        match self.struct_style {
+            StructStyle::Structs => format!("{}::{}", self.struct_root(), self.rule_struct(rule)),
+            StructStyle::Enum => format!("{}::RuleStruct", self.module_root()),
+        }
+    }
+
+    /// returns the expression constructing the payload of the variant `rule` of `enum Rule`, as code.
+    ///
+    /// `rule` is spliced in textually, as stringified by `pest` (e.g. `r#Script`).
+    pub fn payload_value(&self, rule: &str) -> String {
+        if let Some(ty) = self.override_of(rule) {
+            return format!("<{} as ::core::default::Default>::default()", quote!(#ty));
+        }
+        match self.struct_style {
+            StructStyle::Structs => {
+                format!("{}::{} {{}}", self.struct_root(), self.rule_struct(rule))
+            }
+            StructStyle::Enum => format!("{}::RuleStruct::{rule}", self.module_root()),
+        }
+    }
+
+    /// returns the struct carried by the variant `rule`, i.e. its group or the struct of the rule itself, as code.
+    fn rule_struct(&self, rule: &str) -> String {
+        let name = rule.strip_prefix("r#").unwrap_or(rule);
+        match self.group_of(name) {
+            Some(group) => group.to_string(),
+            None => self.struct_name(rule),
+        }
+    }
+
+    /// returns the name of the struct of the rule `rule` (e.g. `r#tok_ident`), as code: the rule itself, or the
+    /// name `rename_strategy` turns it into (e.g. `Ident`), with a raw prefix for a keyword.
+    pub fn struct_name(&self, rule: &str) -> String {
+        if self.rename_strategy.is_empty() {
+            return rule.to_string();
+        }
+        let rule = rule.strip_prefix("r#").unwrap_or(rule);
+        let name = self
+            .rename_strategy
+            .iter()
+            .fold(rule.to_string(), |name, step| step.apply(&name));
+        let identifier = name
+            .chars()
+            .next()
+            .is_some_and(|first| first.is_alphabetic() || first == '_')
+            && name.chars().all(|c| c.is_alphanumeric() || c == '_')
+            && name != "_";
+        assert!(
+            identifier,
+            "`rename_strategy` turns the rule `{rule}` into `{name}`, which is not an identifier"
+        );
+        // `gen` is reserved from the 2024 edition on, `syn` only rejects the keywords of the 2021 edition.
+        if name == "gen" || parse_str::<Ident>(&name).is_err() {
+            format!("r#{name}")
+        } else {
+            name
+        }
+    }
+
+    /// returns the type of the rule struct (or group struct) named `name`, as code.
+    pub fn struct_type(&self, name: &Ident) -> String {
+        format!("{}::{name}", self.struct_root())
+    }
+
+    /// returns the type of the user carried by the variant `rule` (e.g. `r#Strings`), if it is overridden.
+    pub fn override_of(&self, rule: &str) -> Option<&Path> {
+        let name = rule.strip_prefix("r#").unwrap_or(rule);
+        self.overrides
+            .iter()
+            .find(|(overridden, _)| overridden == name)
+            .map(|(_, ty)| ty)
+    }
+
+    /// returns the name of the group struct of the rule `name` (unraw), if it is grouped.
+    pub fn group_of(&self, name: &str) -> Option<&str> {
+        self.groups
+            .iter()
+            .find(|(_, rules)| rules.iter().any(|rule| rule == name))
+            .map(|(group, _)| group.as_str())
+    }
+
+    /// returns the module of the rule structs, as code.
+    fn struct_root(&self) -> String {
+        match (&self.struct_path, &self.struct_module) {
+            (Some(path), _) => quote!(#path).to_string().replace(' ', ""),
+            // a bare name is the one of a module next to the generated items.
+            (None, Some(module)) if module.segments.len() == 1 => {
+                format!("{}::{}", self.module_root(), quote!(#module))
+            }
+            (None, Some(module)) => quote!(#module).to_string().replace(' ', ""),
+            (None, None) => self.module_root(),
+        }
+    }
+
+    /// returns the name of the module the rule structs are emitted in with `struct_module`.
+    pub fn struct_module_ident(&self) -> Option<&Ident> {
+        self.struct_module.as_ref().map(|module| {
+            &module
+                .segments
+                .last()
+                .expect("`struct_module` must not be empty")
+                .ident
+        })
+    }
+
+    /// returns the module of the generated items, as code: the one of a namespaced parser, or the crate root.
+    fn module_root(&self) -> String {
+        match &self.module {
+            Some(module) => quote!(#module).to_string().replace(' ', ""),
+            None => String::from("crate"),
+        }
+    }
+
+    /// same as [`PestParserArgs::payload_value`], but for a known rule and as tokens.
+    pub fn payload(&self, rule: &Ident) -> TokenStream {
+        self.payload_value(&rule.to_string())
+            .parse()
+            .expect("illegal payload expression")
+    }
+
+    /// returns whether the optional `helper` should be generated.
+    pub fn generates(&self, helper: &str) -> bool {
+        self.generate.iter().any(|enabled| enabled == helper)
+    }
+}
+
+/// Names of all arguments accepted by `#[pest_dispatch]`.
+const DISPATCH_ARGUMENTS: &[&str] = &[
+    "rules",
+    "walk_method",
+    "recover",
+    "error_type",
+    "enum_dispatch_path",
+    "handler_fn",
+    "delegate_to",
+    "handler_method",
+    "handler_rules",
+    "fallback",
+    "unhandled",
+    "output_enum",
+    "namespaced",
+    "inherent_dispatch",
+];
+
+/// All arguments accepted by `#[pest_dispatch]`.
+pub(crate) struct DispatchArgs {
+    /// the parser generated by `#[pest_rules]`, whose `enum Rule` dispatches the annotated interface.
+    pub rules: Path,
+    /// same as [`PestParserArgs::walk_method`].
+    pub walk_method: Option<Ident>,
+    /// same as [`PestParserArgs::recover`].
+    pub recover: bool,
+    /// the error collected by `walk_into()` with `recover` instead of a boxed one, see `error_type`.
+    pub error_type: Option<Path>,
+    /// same as [`PestParserArgs::enum_dispatch_path`].
+    pub enum_dispatch_path: Option<Path>,
+    /// the function every rule struct forwards `handler_method` to, with the kind of its rule.
+    pub handler_fn: Option<Path>,
+    /// the type whose functions, named after the rule structs, the rule structs forward `handler_method` to.
+    pub delegate_to: Option<Path>,
+    /// the method of the interface forwarded to `handler_fn` or `delegate_to`, `None` for the only method of the
+    /// interface.
+    pub handler_method: Option<Ident>,
+    /// the rule structs forwarding to `handler_fn` or `delegate_to`, `None` for all of them.
+    pub handler_rules: Option<Vec<Path>>,
+    /// the type implementing the interface for the `unhandled` rule structs, built from their kind.
+    pub fallback: Option<Path>,
+    /// the rule structs forwarding to `fallback`, `None` for all of them (`"*"`).
+    pub unhandled: Option<Vec<Path>>,
+    /// the enum of the outputs of the interface, dispatched by `rule_output!` instead of `enum_dispatch`.
+    pub output_enum: Option<Ident>,
+    /// whether `rules` is a namespaced parser, whose `enum Rule` is registered with `enum_dispatch` as `<Parser>Rule`.
+    pub namespaced: bool,
+    /// whether the methods of the interface are also inherent methods of `Rule`, found without importing it.
+    pub inherent_dispatch: bool,
+}
+
+impl DispatchArgs {
+    pub fn from_arguments(args: Punctuated<MetaNameValue, Token![,]>) -> Self {
+        let mut rules = None;
+        let mut walk_method = None;
+        let mut recover = None;
+        let mut error_type = None;
+        let mut enum_dispatch_path = None;
+        let mut handler_fn = None;
+        let mut delegate_to = None;
+        let mut handler_method = None;
+        let mut handler_rules = None;
+        let mut fallback = None;
+        let mut unhandled = None;
+        let mut output_enum = None;
+        let mut namespaced = None;
+        let mut inherent_dispatch = None;
+
+        for arg in args {
+            let (key, value) = get_pest_parser_argument(arg);
+            let slot = match key.as_str() {
+                "rules" => &mut rules,
+                "walk_method" => &mut walk_method,
+                "recover" => &mut recover,
+                "error_type" => &mut error_type,
+                "enum_dispatch_path" => &mut enum_dispatch_path,
+                "handler_fn" => &mut handler_fn,
+                "delegate_to" => &mut delegate_to,
+                "handler_method" => &mut handler_method,
+                "handler_rules" => &mut handler_rules,
+                "fallback" => &mut fallback,
+                "unhandled" => &mut unhandled,
+                "output_enum" => &mut output_enum,
+                "namespaced" => &mut namespaced,
+                "inherent_dispatch" => &mut inherent_dispatch,
+                "interface" => panic!("`#[pest_dispatch]` dispatches the trait it is placed on, `interface` is not needed"),
+                _ => panic!("unknown argument `{key}`, expected one of {DISPATCH_ARGUMENTS:?}"),
+            };
+            assert!(slot.is_none(), "argument `{key}` is given more than once");
+            *slot = Some(value);
+        }
+
+        let recover = parse_flag("recover", recover);
+        assert!(
+            !recover || walk_method.is_some(),
+            "`recover` applies to `walk_into()`, which requires `walk_method`"
+        );
+        assert!(
+            error_type.is_none() || recover,
+            "`error_type` is the error of `walk_into()` with `recover`, which is not given"
+        );
+        assert!(
+            handler_fn.is_some() || delegate_to.is_some() || (handler_method.is_none() && handler_rules.is_none()),
+            "`handler_method` and `handler_rules` configure `handler_fn` or `delegate_to`, which is missing"
+        );
+        assert!(
+            handler_fn.is_none() || delegate_to.is_none(),
+            "`handler_fn` and `delegate_to` both forward `handler_method`, give one or the other"
+        );
+        assert_eq!(
+            fallback.is_some(),
+            unhandled.is_some(),
+            "`fallback` and `unhandled` go together: `unhandled` lists the rule structs forwarding to `fallback`, or `\"*\"` for all of them"
+        );
+        assert!(
+            !(handler_rules.is_none() && (handler_fn.is_some() || delegate_to.is_some()) && unhandled.as_deref() == Some("*")),
+            "`handler_fn` (or `delegate_to`) and `fallback` cannot both implement the interface for every rule struct"
+        );
+        assert!(
+            output_enum.is_none()
+                || (handler_fn.is_none() && delegate_to.is_none() && fallback.is_none() && walk_method.is_none()),
+            "`output_enum` dispatches the interface through `rule_output!`, not with `handler_fn`, `delegate_to`, `fallback` or `walk_method`"
+        );
+        let inherent_dispatch = parse_flag("inherent_dispatch", inherent_dispatch);
+        assert!(
+            !inherent_dispatch || output_enum.is_none(),
+            "with `output_enum`, the methods of the interface are already inherent methods of `Rule`"
+        );
+
+        Self {
+            rules: parse_str(&rules.expect("missing argument `rules`"))
+                .unwrap_or_else(|err| panic!("`rules` must be a path to a parser: {err}")),
+            walk_method: walk_method.map(parse_walk_method),
+            recover,
+            error_type: error_type.map(parse_error_type),
+            enum_dispatch_path: enum_dispatch_path.map(parse_enum_dispatch_path),
+            handler_fn: handler_fn.map(|handler| {
+                parse_str(&handler).unwrap_or_else(|err| {
+                    panic!("`handler_fn` must be a path to a function: {err}")
+                })
+            }),
+            delegate_to: delegate_to.map(|delegate_to| {
+                parse_str(&delegate_to)
+                    .unwrap_or_else(|err| panic!("`delegate_to` must be a path to a type: {err}"))
+            }),
+            handler_method: handler_method.map(|method| {
+                parse_str(&method)
+                    .unwrap_or_else(|err| panic!("`handler_method` must be an identifier: {err}"))
+            }),
+            handler_rules: handler_rules.map(|rules| parse_rule_structs("handler_rules", &rules)),
+            fallback: fallback.map(|fallback| {
+                parse_str(&fallback)
+                    .unwrap_or_else(|err| panic!("`fallback` must be a path to a type: {err}"))
+            }),
+            unhandled: unhandled
+                .filter(|unhandled| unhandled.trim() != "*")
+                .map(|unhandled| parse_rule_structs("unhandled", &unhandled)),
+            output_enum: output_enum.map(|output_enum| {
+                parse_str(&output_enum)
+                    .unwrap_or_else(|err| panic!("`output_enum` must be an identifier: {err}"))
+            }),
+            namespaced: parse_flag("namespaced", namespaced),
+            inherent_dispatch,
+        }
+    }
+
+    /// returns the `enum_dispatch` attribute linking the annotated trait to `enum Rule`.
+    pub fn enum_dispatch_attr(&self) -> TokenStream {
+        let enum_dispatch = enum_dispatch_attr_path(self.enum_dispatch_path.as_ref());
+        let rule = if self.namespaced {
+            let parser = &self
+                .rules
+                .segments
+                .last()
+                .expect("`rules` must not be empty")
+                .ident;
+            dispatch_rule_ident(parser)
+        } else {
+            Ident::new("Rule", proc_macro2::Span::call_site())
+        };
+        quote!(#[#enum_dispatch(#rule)])
+    }
+
+    /// returns the path of `enum Rule`, which `pest` emits next to the parser, or in the module of a namespaced
+    /// one, whose `Rule` must be in scope.
+    pub fn rule_path(&self) -> Path {
+        if self.namespaced {
+            return parse_quote!(Rule);
+        }
+        let mut rule = self.rules.clone();
+        rule.segments
+            .last_mut()
+            .expect("`rules` must not be empty")
+            .ident = Ident::new("Rule", proc_macro2::Span::call_site());
+        rule
+    }
+}
+
+/// Names of all arguments accepted by `#[pest_handler]`.
+const HANDLER_ARGUMENTS: &[&str] = &["rule", "interface", "method"];
+
+/// All arguments accepted by `#[pest_handler]`.
+pub(crate) struct HandlerArgs {
+    /// the rule struct handled by the only method of the block, instead of `#[rule(...)]` on the method.
+    pub rule: Option<Path>,
+    /// the interface implemented for the rule structs.
+    pub interface: Path,
+    /// the method of the interface implemented by the handlers.
+    pub method: Ident,
+}
+
+impl HandlerArgs {
+    pub fn from_arguments(args: Punctuated<MetaNameValue, Token![,]>) -> Self {
+        let mut rule = None;
+        let mut interface = None;
+        let mut method = None;
+
+        for arg in args {
+            let (key, value) = get_argument_literal(arg);
+            let slot = match key.as_str() {
+                "rule" => &mut rule,
+                "interface" => &mut interface,
+                "method" => &mut method,
+                _ => panic!("unknown argument `{key}`, expected one of {HANDLER_ARGUMENTS:?}"),
+            };
+            assert!(slot.is_none(), "argument `{key}` is given more than once");
+            *slot = Some(value);
+        }
+
+        Self {
+            rule: rule.map(|rule| {
+                rule.parse()
+                    .unwrap_or_else(|err| panic!("`rule` must be a path to a rule struct: {err}"))
+            }),
+            interface: interface
+                .expect("missing argument `interface`")
+                .parse()
+                .unwrap_or_else(|err| panic!("`interface` must be a path to a trait: {err}")),
+            method: method
+                .expect("missing argument `method`")
+                .parse()
+                .unwrap_or_else(|err| panic!("`method` must be an identifier: {err}")),
+        }
+    }
+}
+
+/// parses the contents of one `group(...)` argument, `Struct = "RuleA, RuleB"` for every group.
+fn parse_groups(tokens: &TokenStream) -> Vec<(String, Vec<String>)> {
+    Punctuated::<MetaNameValue, Token![,]>::parse_terminated
+        .parse2(tokens.clone())
+        .unwrap_or_else(|err| {
+            panic!("`group` must be like `group(Number = \"Decimal, Hex\")`: {err}")
+        })
+        .into_iter()
+        .map(|arg| {
+            let (group, rules) = get_pest_parser_argument(arg);
+            let rules = split_list(&rules)
+                .map(|rule| {
+                    parse_str::<Ident>(&rule)
+                        .unwrap_or_else(|err| {
+                            panic!("`{rule}` in `group({group})` must be a rule name: {err}")
+                        })
+                        .unraw()
+                        .to_string()
+                })
+                .collect::<Vec<_>>();
+            assert!(!rules.is_empty(), "`group({group})` has no rules");
+            (group, rules)
+        })
+        .collect()
+}
+
+/// parses the arguments of `#[pest_parser]`, like `Punctuated::<Meta, Token![,]>::parse_terminated`, but also
+/// accepting `override(...)`, whose name is a keyword `syn` does not take as the path of a `Meta`, and rejecting
+/// `grammar_inline` next to `grammar`.
+pub(crate) fn parse_arguments(input: ParseStream) -> syn::Result<Punctuated<Meta, Token![,]>> {
+    let mut args = Punctuated::new();
+    while !input.is_empty() {
+        let arg = if input.peek(Token![override]) {
+            let keyword = input.parse::<Token![override]>()?;
+            let content;
+            let paren = parenthesized!(content in input);
+            Meta::List(MetaList {
+                path: Ident::new("override", keyword.span).into(),
+                delimiter: MacroDelimiter::Paren(paren),
+                tokens: content.parse()?,
+            })
+        } else {
+            input.parse()?
+        };
+        args.push_value(arg);
+        if input.is_empty() {
+            break;
+        }
+        args.push_punct(input.parse()?);
+    }
+    // reported here rather than by `PestParserArgs`, to point at the literal instead of the whole attribute.
+    let value = |key: &str| {
+        args.iter().find_map(|arg| match arg {
+            Meta::NameValue(arg) if arg.path.is_ident(key) => Some(&arg.value),
+            _ => None,
+        })
+    };
+    if let (Some(_), Some(inline)) = (value("grammar"), value("grammar_inline")) {
+        return Err(syn::Error::new_spanned(
+            inline,
+            "`grammar` and `grammar_inline` are both given, give either the grammar file or its source",
+        ));
+    }
+    Ok(args)
+}
+
+/// parses the contents of one `override(...)` argument, `Rule = "crate::Type"` for every overridden rule.
+fn parse_overrides(tokens: &TokenStream) -> Vec<(String, Path)> {
+    Punctuated::<MetaNameValue, Token![,]>::parse_terminated
+        .parse2(tokens.clone())
+        .unwrap_or_else(|err| {
+            panic!("`override` must be like `override(Strings = \"crate::Strings\")`: {err}")
+        })
+        .into_iter()
+        .map(|arg| {
+            let (rule, ty) = get_pest_parser_argument(arg);
+            let rule = rule.strip_prefix("r#").unwrap_or(&rule).to_string();
+            let ty = parse_str(&ty).unwrap_or_else(|err| {
+                panic!("`{ty}` in `override({rule})` must be a path to a type: {err}")
+            });
+            (rule, ty)
+        })
+        .collect()
+}
+
+/// parses the value of the boolean argument `key`, `false` if it is omitted.
+fn parse_flag(key: &str, value: Option<String>) -> bool {
+    match value.as_deref() {
+        None | Some("false") => false,
+        Some("true") => true,
+        Some(value) => panic!("`{key}` must be `true` or `false`, found `{value}`"),
+    }
+}
+
+/// returns the name `enum Rule` of the namespaced parser `parser` is declared under, so `enum_dispatch`, which
+/// knows the enums by name only, tells it from the `Rule`s of the other parsers of the crate.
+pub(crate) fn dispatch_rule_ident(parser: &Ident) -> Ident {
+    Ident::new(&format!("{}Rule", parser.unraw()), parser.span())
+}
+
+/// parses the value of `module`, an absolute path to the module a namespaced parser is emitted in.
+fn parse_module(module: &str) -> Path {
+    let module: Path = parse_str(module).unwrap_or_else(|err| {
+        panic!("`module` must be a module path like `crate::json_parser`: {err}")
+    });
+    assert!(
+        module.leading_colon.is_none() && module.segments.len() > 1 && module.segments[0].ident == "crate",
+        "`module` must start with `crate::`, the rule structs are referred to by this path from any module"
+    );
+    module
+}
+
+/// parses the value of `struct_module`, the name of the module of the rule structs, or its absolute path when the
+/// parser is not declared at the crate root.
+fn parse_struct_module(module: &str) -> Path {
+    let module: Path = parse_str(module).unwrap_or_else(|err| {
+        panic!("`struct_module` must be a module name like `rules`, or a path like `crate::lang::rules`: {err}")
+    });
+    let named = module.segments.len() == 1
+        && !["crate", "self", "super", "Self"]
+            .iter()
+            .any(|keyword| module.segments[0].ident == keyword);
+    assert!(
+        module.leading_colon.is_none()
+            && (named || (module.segments.len() > 1 && module.segments[0].ident == "crate"))
+            && module.segments.iter().all(|segment| segment.arguments.is_none()),
+        "`struct_module` must be a module name like `rules`, or a path starting with `crate::` like \
+         `crate::lang::rules`, the rule structs are referred to by this path from any module"
+    );
+    module
+}
+
+/// converts the name of a parser to the name of its module, e.g. `JsonParser` to `json_parser`, or the name of a
+/// rule struct to the name of its handler.
+pub(crate) fn snake_case(name: &str) -> String {
+    let mut snake = String::new();
+    let mut chars = name.chars().peekable();
+    let mut previous: Option<char> = None;
+    while let Some(c) = chars.next() {
+        if c.is_uppercase() {
+            // a new word starts after a lowercase letter or a digit, or before the last capital of an acronym.
+            let boundary = previous.is_some_and(|previous| {
+                previous.is_lowercase()
+                    || previous.is_ascii_digit()
+                    || (previous.is_uppercase()
+                        && chars.peek().is_some_and(|next| next.is_lowercase()))
+            });
+            if boundary {
+                snake.push('_');
+            }
+            snake.extend(c.to_lowercase());
+        } else {
+            snake.push(c);
+        }
+        previous = Some(c);
+    }
+    snake
+}
+
+/// parses the value of `error_type`, the path of the error type replacing the errors of the generated helpers.
+fn parse_error_type(ty: String) -> Path {
+    parse_str(&ty).unwrap_or_else(|err| panic!("`error_type` must be a path to a type: {err}"))
+}
+
+/// parses the value of `enum_dispatch_path`, the path of a crate or module exporting the `enum_dispatch` attribute.
+fn parse_enum_dispatch_path(path: String) -> Path {
+    parse_str(&path).unwrap_or_else(|err| {
+        panic!("`enum_dispatch_path` must be a path like `::reexported::enum_dispatch`: {err}")
+    })
+}
+
+/// parses the list of rule structs given to the argument `key`.
+fn parse_rule_structs(key: &str, rules: &str) -> Vec<Path> {
+    split_list(rules)
+        .map(|rule| {
+            parse_str(&rule).unwrap_or_else(|err| {
+                panic!("`{key}` must list rule structs, found `{rule}`: {err}")
+            })
+        })
+        .collect()
+}
+
+/// returns the path of the `enum_dispatch` attribute, within `crate_path` if given.
+fn enum_dispatch_attr_path(crate_path: Option<&Path>) -> TokenStream {
+    match crate_path {
+        Some(path) => quote!(#path::enum_dispatch),
+        None => quote!(enum_dispatch),
+    }
+}
+
+/// parses a step of `rename_strategy`, e.g. `strip_prefix:tok_` or `pascal_case`.
+fn parse_rename_step(step: String) -> RenameStep {
+    let (kind, argument) = match step.split_once(':') {
+        Some((kind, argument)) => (kind.trim(), Some(argument.trim())),
+        None => (step.as_str(), None),
+    };
+    match (kind, argument) {
+        ("strip_prefix", Some(prefix)) if !prefix.is_empty() => RenameStep::StripPrefix(prefix.to_string()),
+        ("strip_suffix", Some(suffix)) if !suffix.is_empty() => RenameStep::StripSuffix(suffix.to_string()),
+        ("pascal_case", None) => RenameStep::PascalCase,
+        ("strip_prefix" | "strip_suffix", _) => {
+            panic!("`rename_strategy` step `{kind}` takes what to strip, e.g. `{kind}:tok_`")
+        }
+        ("pascal_case", Some(_)) => panic!("`rename_strategy` step `pascal_case` takes no argument"),
+        _ => panic!(
+            "unknown `rename_strategy` step `{step}`, expected `strip_prefix:<prefix>`, `strip_suffix:<suffix>` or \
+             `pascal_case`"
+        ),
+    }
+}
+
+fn parse_walk_method(method: String) -> Ident {
+    parse_str(&method).unwrap_or_else(|err| panic!("`walk_method` must be an identifier: {err}"))
+}
+
+/// parses the value of one `rule_attr` argument, which must be exactly one outer attribute.
+///
+/// `#[enum_dispatch]` is rejected since it is already inserted (or deliberately left out) by the macro.
+fn parse_rule_attr(value: &str) -> Attribute {
+    let attrs = Attribute::parse_outer
+        .parse_str(value)
+        .unwrap_or_else(|err| panic!("`rule_attr` must be an attribute like `#[repr(u8)]`: {err}"));
+    let [attr] = <[Attribute; 1]>::try_from(attrs).unwrap_or_else(|attrs| {
+        panic!(
+            "`rule_attr` must be exactly one attribute, found {}; repeat `rule_attr` for more",
+            attrs.len()
+        )
+    });
+    assert!(
+        !attr.path().is_ident("enum_dispatch"),
+        "`#[enum_dispatch]` is placed on `enum Rule` by the macro itself"
+    );
+    attr
+}
+
+/// splits a comma separated argument value into its trimmed, non-empty items.
+fn split_list(value: &str) -> impl Iterator<Item = String> + '_ {
+    value
+        .split(',')
+        .map(str::trim)
+        .filter(|item| !item.is_empty())
+        .map(String::from)
+}
+
+pub(crate) fn get_pest_parser_argument(arg: MetaNameValue) -> (String, String) {
+    let (key, value) = get_argument_literal(arg);
+    (key, value.value())
+}
+
+/// same as [`get_pest_parser_argument`], but keeps the literal, so a value parsed from it points at the argument.
+fn get_argument_literal(arg: MetaNameValue) -> (String, LitStr) {
+    let key = if let Some(ident) = arg.path.get_ident() {
+        ident.to_string()
+    } else {
+        panic!("key of argument must be an identifier");
+    };
+    let value = if let Expr::Lit(lit) = arg.value {
+        if let Lit::Str(lit_str) = lit.lit {
+            lit_str
+        } else {
+            panic!("value of argument must be a string literal");
+        }
+    } else {
+        panic!("value of argument must be a string literal");
+    };
+    (key, value)
+}
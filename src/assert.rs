@@ -0,0 +1,46 @@
+//! Generation of `assert_parses!`, testing a rule of the grammar on an inline input.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+/// Like `match_pairs!`, the generated `assert_parses!` is re-exported with `pub(crate) use`, and only calls an
+/// inherent method of `RuleKind`, so a test module next to the parser (`use super::*;`) has all it needs. The rule
+/// is named as a variant of `RuleKind`, so a misspelled rule is reported by `rustc` at the call.
+///
+/// The method is `#[track_caller]`, so a failure points at the assertion rather than at the generated code.
+pub(crate) fn assert_parses_generator(parser: &Ident) -> TokenStream {
+    quote! {
+        impl RuleKind {
+            /// Parses `input` with this rule, which must match all of it, see `assert_parses!`.
+            #[doc(hidden)]
+            #[track_caller]
+            pub fn assert_parses<'i>(self, name: &str, input: &'i str) -> ::pest::iterators::Pair<'i, Rule> {
+                let pair = match <#parser as ::pest::Parser<Rule>>::parse(self.into(), input) {
+                    Ok(mut pairs) => pairs.next(),
+                    Err(error) => panic!("`{name}` does not parse {input:?}:\n{error}"),
+                };
+                let pair = pair.unwrap_or_else(|| panic!("`{name}` parses {input:?}, but yields no pair"));
+                if pair.as_span().end() != input.len() {
+                    panic!(
+                        "`{name}` only matches {:?} of {input:?}, the rest is left",
+                        pair.as_str()
+                    );
+                }
+                pair
+            }
+        }
+
+        /// Asserts that `input` parses with `rule` as a whole, e.g. `assert_parses!(Statement, "@print(42);")`,
+        /// and returns its pair. Otherwise, panics with the error of `pest` (or with the part of `input` the rule
+        /// matches), for the tests of a grammar.
+        #[allow(unused_macros)]
+        macro_rules! __assert_parses_rules {
+            ($rule:ident, $input:expr $(,)?) => {
+                RuleKind::$rule.assert_parses(::core::stringify!($rule), $input)
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use __assert_parses_rules as assert_parses;
+    }
+}
@@ -0,0 +1,243 @@
+//! The expansion benchmark behind the `expand-bench` feature, see `examples/expand_bench.rs`.
+//!
+//! A proc-macro crate cannot export functions, and the compiler's token API is only available while a
+//! macro is expanded, so the benchmark is a macro itself: `expand_bench!` runs every phase of
+//! `#[pest_parser]` on synthetic grammars while it is expanded and writes the timings as JSON.
+
+use crate::args::{get_pest_parser_argument, PestParserArgs};
+use crate::grammar::{GrammarContext, GrammarSource};
+use crate::{
+    check_hooked_codes, enum_dispatch_tag_generator, hook_raw_codes, pest_parser_generator,
+    pest_rule_enum, rule_structs_generator,
+};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::quote;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::time::{Duration, Instant};
+use syn::{parse::Parser, parse_quote, punctuated::Punctuated, MetaNameValue, Token};
+
+/// Returns a grammar of `rules` rules (plus `WHITESPACE` and the entry rule `main`).
+///
+/// The rules cycle through the shapes found in real grammars: sequences and choices referring to other
+/// rules, repetitions, and atomic and silent rules with literals and ranges.
+pub(crate) fn synthetic_grammar(rules: usize) -> String {
+    let mut grammar = String::from("WHITESPACE = _{ \" \" | \"\\t\" | NEWLINE }\n");
+    let _ = writeln!(grammar, "main = {{ SOI ~ (rule_0 | rule_1)* ~ EOI }}");
+    for index in 0..rules {
+        let next = (index + 1) % rules;
+        let other = (index + 7) % rules;
+        let _ = match index % 5 {
+            0 => writeln!(
+                grammar,
+                "rule_{index} = {{ \"k{index}\" ~ (\"(\" ~ rule_{next}? ~ \")\")? }}"
+            ),
+            1 => writeln!(
+                grammar,
+                "rule_{index} = {{ rule_{next} | rule_{other} | \"v{index}\" }}"
+            ),
+            2 => writeln!(
+                grammar,
+                "rule_{index} = @{{ ASCII_DIGIT+ ~ (\".\" ~ ASCII_DIGIT+)? }}"
+            ),
+            3 => writeln!(
+                grammar,
+                "rule_{index} = _{{ \"[\" ~ (rule_{next} ~ (\",\" ~ rule_{next})*)? ~ \"]\" }}"
+            ),
+            _ => writeln!(
+                grammar,
+                "rule_{index} = ${{ 'a'..'z' ~ ('a'..'z' | \"_\")* }}"
+            ),
+        };
+    }
+    grammar
+}
+
+/// Returns a grammar whose entry rule `main` repeats `keyword`, a choice of `keywords` string literals, like the
+/// keywords (or the reserved words, or the names of the built-in functions) of a language.
+pub(crate) fn keyword_grammar(keywords: usize) -> String {
+    let choice = (0..keywords)
+        .map(|index| format!("\"kw{index}\""))
+        .collect::<Vec<_>>()
+        .join(" | ");
+    format!(
+        "WHITESPACE = _{{ \" \" | \"\\t\" | NEWLINE }}\n\
+         main = {{ SOI ~ keyword* ~ EOI }}\n\
+         keyword = @{{ ({choice}) ~ !ASCII_ALPHANUMERIC }}\n"
+    )
+}
+
+/// Wall time of every phase of one `#[pest_parser]` expansion.
+struct Timings {
+    /// what was measured, e.g. `("rules", 100)` for `synthetic_grammar(100)`.
+    size: (&'static str, usize),
+    derive_parser: Duration,
+    extraction: Duration,
+    structs: Duration,
+    hooking: Duration,
+    check: Duration,
+    /// a whole `#[pest_parser]` expansion of the grammar, nothing cached.
+    expansion: Duration,
+    /// the expansion again with another interface, reusing the cached hooked code.
+    interface_toggle: Duration,
+    /// the expansion again with another `rule_attr`, reusing `pest`'s cached output only.
+    hook_miss: Duration,
+}
+
+impl Timings {
+    fn measure(grammar_file: &str, size: (&'static str, usize)) -> Self {
+        let args = PestParserArgs::from_arguments(parse_quote! {
+            grammar = #grammar_file, interface = "Interface"
+        });
+
+        let start = Instant::now();
+        let raw_codes = GrammarContext::load(&GrammarSource::File(grammar_file.into()))
+            .generate_parser(&Ident::new("BenchParser", Span::call_site()));
+        let derive_parser = start.elapsed();
+
+        let start = Instant::now();
+        let rule_enum = pest_rule_enum(raw_codes.clone());
+        let extraction = start.elapsed();
+
+        let start = Instant::now();
+        let enums = rule_enum
+            .variants
+            .iter()
+            .map(|variant| variant.ident.clone())
+            .collect::<Vec<_>>();
+        drop(rule_structs_generator(&enums));
+        drop(enum_dispatch_tag_generator(&rule_enum, &args));
+        let structs = start.elapsed();
+
+        let start = Instant::now();
+        let interface = args.interface_path();
+        let hooked_codes = hook_raw_codes(raw_codes, &rule_enum, &args, quote!(#interface));
+        let hooking = start.elapsed();
+
+        let start = Instant::now();
+        check_hooked_codes(&hooked_codes, &rule_enum);
+        let check = start.elapsed();
+
+        let vis = parse_quote!(pub);
+        let parser = parse_quote!(BenchParser);
+        let expand = |args| {
+            let args = PestParserArgs::from_arguments(args);
+            let start = Instant::now();
+            drop(pest_parser_generator(&vis, &parser, &args));
+            start.elapsed()
+        };
+        let expansion = expand(parse_quote! { grammar = #grammar_file, interface = "Interface" });
+        let interface_toggle =
+            expand(parse_quote! { grammar = #grammar_file, interface = "OtherInterface" });
+        let hook_miss = expand(parse_quote! {
+            grammar = #grammar_file, interface = "Interface", rule_attr = "#[allow(unused)]"
+        });
+
+        Self {
+            size,
+            derive_parser,
+            extraction,
+            structs,
+            hooking,
+            check,
+            expansion,
+            interface_toggle,
+            hook_miss,
+        }
+    }
+
+    fn to_json(&self) -> String {
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        format!(
+            r#"{{"{}": {}, "derive_parser_ms": {:.3}, "extraction_ms": {:.3}, "structs_ms": {:.3}, "hooking_ms": {:.3}, "check_ms": {:.3}, "expansion_ms": {:.3}, "interface_toggle_ms": {:.3}, "hook_miss_ms": {:.3}}}"#,
+            self.size.0,
+            self.size.1,
+            ms(self.derive_parser),
+            ms(self.extraction),
+            ms(self.structs),
+            ms(self.hooking),
+            ms(self.check),
+            ms(self.expansion),
+            ms(self.interface_toggle),
+            ms(self.hook_miss),
+        )
+    }
+}
+
+/// `expand_bench!(rules = "10, 100", keywords = "1000", output = "target/expand-bench.json")`, expanding to
+/// `pub const EXPAND_BENCH: &str` holding the written JSON.
+pub(crate) fn expand_bench(input: TokenStream) -> TokenStream {
+    let mut rules = None;
+    let mut keywords = None;
+    let mut output = None;
+    let args = Punctuated::<MetaNameValue, Token![,]>::parse_terminated
+        .parse2(input)
+        .unwrap_or_else(|err| panic!("illegal `expand_bench!` arguments: {err}"));
+    for arg in args {
+        match get_pest_parser_argument(arg) {
+            (key, value) if key == "rules" => rules = Some(value),
+            (key, value) if key == "keywords" => keywords = Some(value),
+            (key, value) if key == "output" => output = Some(value),
+            (key, _) => {
+                panic!("unknown argument `{key}`, expected `rules`, `keywords` or `output`")
+            }
+        }
+    }
+    let counts = |key: &str, counts: &str| {
+        counts
+            .split(',')
+            .map(|count| {
+                count
+                    .trim()
+                    .parse::<usize>()
+                    .unwrap_or_else(|err| panic!("`{key}` must be a list of counts: {err}"))
+            })
+            .collect::<Vec<_>>()
+    };
+    let rules = counts("rules", rules.as_deref().unwrap_or("10, 100, 500, 1000"));
+    let keywords = keywords.map_or_else(Vec::new, |keywords| counts("keywords", &keywords));
+
+    let directory = std::env::temp_dir().join("enum_dispatch_pest_parser_bench");
+    std::fs::create_dir_all(&directory).expect("cannot create the benchmark directory");
+    let grammars = rules
+        .into_iter()
+        .map(|count| ("rules", count, synthetic_grammar(count)))
+        .chain(
+            keywords
+                .into_iter()
+                .map(|count| ("keywords", count, keyword_grammar(count))),
+        );
+    let results = grammars
+        .map(|(key, count, grammar)| {
+            let grammar_file = directory.join(format!("grammar_{key}_{count}.pest"));
+            std::fs::write(&grammar_file, grammar).expect("cannot write the synthetic grammar");
+            let grammar_file = grammar_file
+                .to_str()
+                .expect("path of the grammar file must be valid UTF-8");
+            Timings::measure(grammar_file, (key, count)).to_json()
+        })
+        .collect::<Vec<_>>();
+    let profile = if cfg!(debug_assertions) {
+        "debug"
+    } else {
+        "release"
+    };
+    let json = format!(
+        "{{\"profile\": \"{profile}\", \"results\": [\n  {}\n]}}\n",
+        results.join(",\n  ")
+    );
+
+    if let Some(output) = output {
+        let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+        let output = PathBuf::from(root).join(output);
+        if let Some(parent) = output.parent() {
+            std::fs::create_dir_all(parent).expect("cannot create the output directory");
+        }
+        std::fs::write(&output, &json)
+            .unwrap_or_else(|err| panic!("cannot write `{}`: {err}", output.display()));
+    }
+    quote! {
+        /// The timings of every phase of `#[pest_parser]`, measured while this constant was expanded.
+        pub const EXPAND_BENCH: &str = #json;
+    }
+}
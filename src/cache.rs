@@ -0,0 +1,313 @@
+//! Process-global memoization of `pest`'s code generation and of its hooking.
+
+use crate::grammar::GrammarContext;
+use crate::timings::PhaseTimings;
+use crate::{bare_rule_enum, pest_rule_enum};
+use proc_macro2::{Ident, Span, TokenStream};
+use quote::{quote, ToTokens};
+use std::collections::hash_map::DefaultHasher;
+use std::collections::VecDeque;
+use std::hash::{Hash, Hasher};
+use std::sync::{Mutex, PoisonError};
+use syn::{ItemEnum, Path};
+
+/// the number of generated parsers kept, the oldest one is evicted first.
+const CAPACITY: usize = 8;
+
+/// the number of hooked variants kept per generated parser, the least recently used one is evicted first.
+const HOOKED_CAPACITY: usize = 4;
+
+/// the parser name the cached code is generated for, replaced by the real one on every use.
+const PLACEHOLDER: &str = "__EnumDispatchPestParser";
+
+/// the interface the cached hooked code dispatches, replaced by the real one on every use.
+const INTERFACE_PLACEHOLDER: &str = "__EnumDispatchPestInterface";
+
+/// set to disable the cache, e.g. to rule it out when debugging an expansion.
+const NO_CACHE: &str = "ENUM_DISPATCH_PEST_NO_CACHE";
+
+/// grammar path (`None` for tokens) and hash of its content.
+type Key = (Option<String>, u64);
+
+/// What is cached about one grammar, all of it generated for the parser `PLACEHOLDER`.
+struct Entry {
+    key: Key,
+    /// `pest`'s output.
+    code: String,
+    /// `enum Rule` of `code`, extracted on first use.
+    rule_enum: Option<String>,
+    /// `code` hooked for the given hook key (see `PestParserArgs::hook_key`), dispatching
+    /// `INTERFACE_PLACEHOLDER`, most recently used last.
+    hooked: Vec<(String, String)>,
+}
+
+static CACHE: Mutex<VecDeque<Entry>> = Mutex::new(VecDeque::new());
+
+/// Returns `enum Rule` of `pest`'s output for `grammar`, which does not depend on the parser name, so it is
+/// only extracted once per grammar. Its variants are bare where possible, see [`bare_rule_enum`].
+pub(crate) fn rule_enum_cached(grammar: &GrammarContext) -> ItemEnum {
+    if no_cache() {
+        return bare_rule_enum(pest_rule_enum(grammar.generate_parser(&placeholder())));
+    }
+    bare_rule_enum(with_entry(grammar, |entry| {
+        cached_rule_enum(entry, &mut None)
+    }))
+}
+
+/// Returns `enum Rule` of `pest`'s output for `grammar`, and the output hooked by `hook` for the parser `ident`
+/// dispatching `interface`.
+///
+/// The hooking only depends on the grammar and on the arguments summed up by `hook_key`, the interface is a
+/// single path in the inserted `#[enum_dispatch(...)]`. The hooked code is therefore cached per grammar and
+/// hook key, with placeholders for the parser name and the interface, so changing the interface of a parser
+/// (e.g. while iterating on its design with rust-analyzer, whose proc-macro server keeps the cache) neither
+/// generates, extracts nor hooks `pest`'s output again. `hook` gets `pest`'s output, its `enum Rule` and the
+/// interface to insert, with the variants of `enum Rule` as `pest` emits them; the returned one is bare.
+pub(crate) fn hooked_parser_cached(
+    grammar: &GrammarContext,
+    ident: &Ident,
+    hook_key: String,
+    interface: Option<Path>,
+    timings: &mut PhaseTimings,
+    hook: impl FnOnce(TokenStream, &ItemEnum, TokenStream, &mut PhaseTimings) -> TokenStream,
+) -> (ItemEnum, TokenStream) {
+    hooked_parser(
+        grammar,
+        ident,
+        hook_key,
+        interface,
+        timings,
+        !no_cache(),
+        hook,
+    )
+}
+
+/// [`hooked_parser_cached`], bypassing the cache unless `cache` is set.
+fn hooked_parser(
+    grammar: &GrammarContext,
+    ident: &Ident,
+    hook_key: String,
+    interface: Option<Path>,
+    timings: &mut PhaseTimings,
+    cache: bool,
+    hook: impl FnOnce(TokenStream, &ItemEnum, TokenStream, &mut PhaseTimings) -> TokenStream,
+) -> (ItemEnum, TokenStream) {
+    if !cache {
+        let raw_codes = grammar.generate_parser(ident);
+        timings.phase("derive_parser");
+        let rule_enum = pest_rule_enum(raw_codes.clone());
+        timings.phase("extraction");
+        let hooked = hook(raw_codes, &rule_enum, quote!(#interface), timings);
+        return (bare_rule_enum(rule_enum), hooked);
+    }
+
+    let (rule_enum, hooked) = with_entry(grammar, |entry| {
+        timings.phase("derive_parser");
+        // `pest`'s output is only parsed if it is needed for the extraction or the hooking.
+        let mut raw_codes = None;
+        let rule_enum = cached_rule_enum(entry, &mut raw_codes);
+        timings.phase("extraction");
+        let hooked = match entry.hooked.iter().position(|(key, _)| *key == hook_key) {
+            Some(index) => {
+                let hooked = entry.hooked.remove(index);
+                entry.hooked.push(hooked);
+                entry.hooked[entry.hooked.len() - 1].1.clone()
+            }
+            None => {
+                let raw_codes = raw_codes.unwrap_or_else(|| parse_code(&entry.code));
+                let interface = Ident::new(INTERFACE_PLACEHOLDER, Span::call_site());
+                let hooked = hook(raw_codes, &rule_enum, quote!(#interface), timings).to_string();
+                if entry.hooked.len() == HOOKED_CAPACITY {
+                    entry.hooked.remove(0);
+                }
+                entry.hooked.push((hook_key, hooked.clone()));
+                hooked
+            }
+        };
+        (rule_enum, hooked)
+    });
+    let hooked = with_names(&hooked, ident, interface.as_ref());
+    timings.phase("cache");
+    (bare_rule_enum(rule_enum), hooked)
+}
+
+/// returns `enum Rule` of the entry, extracting it from its code (parsed into `raw_codes`) on first use.
+fn cached_rule_enum(entry: &mut Entry, raw_codes: &mut Option<TokenStream>) -> ItemEnum {
+    if let Some(rule_enum) = &entry.rule_enum {
+        return syn::parse_str(rule_enum).expect("cannot parse the cached `enum Rule`.");
+    }
+    let rule_enum = pest_rule_enum(raw_codes.insert(parse_code(&entry.code)).clone());
+    entry.rule_enum = Some(rule_enum.to_token_stream().to_string());
+    rule_enum
+}
+
+/// runs `f` on the cache entry of `grammar`, running `pest`'s code generation first on a miss.
+///
+/// The grammar is always included (`include_grammar`), so the parser is rebuilt when the grammar changes,
+/// and users which only need `enum Rule` (`rule_structs!`) share the same output.
+///
+/// Generating a parser is by far the most expensive part of an expansion, and a crate may declare several
+/// parsers over the same grammar (e.g. with different interfaces). The output is therefore cached per
+/// compiler process, keyed by the grammar path and content, and generated for a placeholder name so it
+/// can be shared between parsers of different names. Correctness does not depend on the cache: a hit
+/// yields the same code as a fresh generation.
+fn with_entry<T>(grammar: &GrammarContext, f: impl FnOnce(&mut Entry) -> T) -> T {
+    let key = key(grammar);
+    let mut cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+    let index = match cache.iter().position(|entry| entry.key == key) {
+        Some(index) => index,
+        None => {
+            let code = grammar.generate_parser(&placeholder()).to_string();
+            if cache.len() == CAPACITY {
+                cache.pop_front();
+            }
+            cache.push_back(Entry {
+                key,
+                code,
+                rule_enum: None,
+                hooked: Vec::new(),
+            });
+            cache.len() - 1
+        }
+    };
+    f(&mut cache[index])
+}
+
+fn key(grammar: &GrammarContext) -> Key {
+    let mut hasher = DefaultHasher::new();
+    grammar.source().hash(&mut hasher);
+    (grammar.path().map(String::from), hasher.finish())
+}
+
+/// replaces the placeholders of cached `code` by the parser `ident` and the `interface`, and parses it.
+fn with_names(code: &str, ident: &Ident, interface: Option<&Path>) -> TokenStream {
+    let mut code = code.replace(PLACEHOLDER, &ident.to_string());
+    if let Some(interface) = interface {
+        code = code.replace(INTERFACE_PLACEHOLDER, &quote!(#interface).to_string());
+    }
+    parse_code(&code)
+}
+
+fn parse_code(code: &str) -> TokenStream {
+    code.parse()
+        .expect("cannot parse the cached code of `pest`.")
+}
+
+fn placeholder() -> Ident {
+    Ident::new(PLACEHOLDER, Span::call_site())
+}
+
+fn no_cache() -> bool {
+    std::env::var_os(NO_CACHE).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::args::PestParserArgs;
+    use crate::grammar::GrammarSource;
+    use syn::parse_quote;
+
+    fn grammar(source: &str) -> GrammarContext {
+        GrammarContext::load(&GrammarSource::Inline(source.into(), Span::call_site()))
+    }
+
+    /// returns the code of the parser `ident` dispatching `interface`, and whether `pest`'s output was hooked
+    /// for it.
+    fn hooked(grammar: &GrammarContext, ident: &str, interface: Path) -> (String, bool) {
+        hooked_with(grammar, ident, "key".into(), interface, true)
+    }
+
+    /// same as `hooked`, with the hook key of the expansion, and bypassing the cache unless `cache` is set.
+    fn hooked_with(
+        grammar: &GrammarContext,
+        ident: &str,
+        hook_key: String,
+        interface: Path,
+        cache: bool,
+    ) -> (String, bool) {
+        let mut hooked = false;
+        let (_, code) = hooked_parser(
+            grammar,
+            &Ident::new(ident, Span::call_site()),
+            hook_key,
+            Some(interface),
+            &mut PhaseTimings::new(),
+            cache,
+            |raw_codes, _, interface, _| {
+                hooked = true;
+                quote!(#interface #raw_codes)
+            },
+        );
+        (code.to_string(), hooked)
+    }
+
+    fn is_cached(grammar: &GrammarContext) -> bool {
+        let cache = CACHE.lock().unwrap_or_else(PoisonError::into_inner);
+        cache.iter().any(|entry| entry.key == key(grammar))
+    }
+
+    #[test]
+    fn shares_the_hooking_between_interfaces() {
+        let grammar = grammar("shared = { \"shared\" }");
+        let (first, hooked_first) = hooked(&grammar, "FirstParser", parse_quote!(FirstInterface));
+        let (second, hooked_second) =
+            hooked(&grammar, "SecondParser", parse_quote!(SecondInterface));
+        assert!(hooked_first && !hooked_second);
+
+        assert!(first.starts_with("FirstInterface") && first.contains("FirstParser"));
+        assert!(second.starts_with("SecondInterface") && second.contains("SecondParser"));
+        assert!(!second.contains("FirstInterface") && !second.contains("FirstParser"));
+        for code in [&first, &second] {
+            assert!(!code.contains(PLACEHOLDER) && !code.contains(INTERFACE_PLACEHOLDER));
+        }
+    }
+
+    #[test]
+    fn hooks_again_for_another_rename_strategy() {
+        let grammar = grammar("tok_renamed = { \"renamed\" }");
+        let hook_key = |rename_strategy: &str| {
+            PestParserArgs::from_arguments(parse_quote! {
+                grammar = "renamed.pest", interface = "Interface", rename_strategy = #rename_strategy
+            })
+            .hook_key()
+        };
+        let (_, hooked_stripped) = hooked_with(
+            &grammar,
+            "RenamedParser",
+            hook_key("strip_prefix:tok_"),
+            parse_quote!(Interface),
+            true,
+        );
+        let (_, hooked_pascal) = hooked_with(
+            &grammar,
+            "RenamedParser",
+            hook_key("pascal_case"),
+            parse_quote!(Interface),
+            true,
+        );
+        assert!(hooked_stripped && hooked_pascal);
+    }
+
+    #[test]
+    fn is_bypassed_without_cache() {
+        let grammar = grammar("bypassed = { \"bypassed\" }");
+        let bypassed = |grammar| {
+            hooked_with(
+                grammar,
+                "FirstParser",
+                "key".into(),
+                parse_quote!(Interface),
+                false,
+            )
+        };
+        let (first, hooked_first) = bypassed(&grammar);
+        let (second, hooked_second) = bypassed(&grammar);
+        assert!(hooked_first && hooked_second);
+        assert_eq!(first, second);
+        assert!(!is_cached(&grammar));
+
+        hooked(&grammar, "FirstParser", parse_quote!(Interface));
+        assert!(is_cached(&grammar));
+    }
+}
@@ -0,0 +1,119 @@
+//! Generation of `map_pairs()` and `map_tree()`, walks collecting what a function returns for each pair.
+
+use crate::coverage::record_coverage;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+/// The walks of `walk_into()` and `walk_until()` keep their results in the accumulator or in the break value;
+/// these return one value per pair instead, e.g. the AST node built for it, and stop at the first error.
+///
+/// `EOI` is skipped like in the other walks, unless asked for with the `_with` variants: its pair is a child of
+/// the start rule, so only the top-level pairs can be `EOI`. The mapping function is taken as `&mut dyn FnMut`
+/// by the recursion, so it is compiled once per value and error type rather than once per closure.
+///
+/// With `max_depth`, `map_tree()` stops at the first pair nested deeper and returns its `DepthError`, converted
+/// into the error type of `f`, which must therefore implement `From<DepthError>`.
+pub(crate) fn collect_generator(vis: &Visibility, max_depth: Option<usize>) -> TokenStream {
+    let record = record_coverage(quote!(pair));
+    let limit = max_depth.map(|max_depth| {
+        let doc = format!(
+            " Fails with a `DepthError` on the first pair nested more than {max_depth} levels deep."
+        );
+        quote!(#[doc = ""] #[doc = #doc])
+    });
+    // the depth is only tracked with a limit to check it against.
+    let (bound, depth, check, next, first) = match max_depth {
+        Some(max_depth) => (
+            quote!(where E: ::core::convert::From<DepthError>),
+            quote!(depth: usize,),
+            quote! {
+                if depth > #max_depth {
+                    return Err(DepthError::new(&pair, #max_depth).into());
+                }
+            },
+            quote!(depth + 1,),
+            quote!(1,),
+        ),
+        None => (quote!(), quote!(), quote!(), quote!(), quote!()),
+    };
+    quote! {
+        /// A value for every pair of a parse tree, as returned by [`map_tree`].
+        #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+        #vis struct Tree<T> {
+            /// the value returned for the pair.
+            pub value: T,
+            /// the trees of its children, in order.
+            pub children: Vec<Tree<T>>,
+        }
+
+        /// Returns what `f` returns for each of `pairs` (skipping `EOI`), without visiting their children, or the
+        /// first error of `f`.
+        #vis fn map_pairs<'i, T, E>(
+            pairs: ::pest::iterators::Pairs<'i, Rule>,
+            f: impl FnMut(RuleKind, ::pest::iterators::Pair<'i, Rule>) -> ::std::result::Result<T, E>,
+        ) -> ::std::result::Result<Vec<T>, E> {
+            map_pairs_with(pairs, false, f)
+        }
+
+        /// Same as [`map_pairs`], but `EOI` is mapped as well if `eoi` is set.
+        #vis fn map_pairs_with<'i, T, E>(
+            pairs: ::pest::iterators::Pairs<'i, Rule>,
+            eoi: bool,
+            mut f: impl FnMut(RuleKind, ::pest::iterators::Pair<'i, Rule>) -> ::std::result::Result<T, E>,
+        ) -> ::std::result::Result<Vec<T>, E> {
+            pairs
+                .filter(|pair| eoi || pair.as_rule() != RuleKind::EOI)
+                .map(|pair| {
+                    #record
+                    f(pair.as_rule().kind(), pair)
+                })
+                .collect()
+        }
+
+        /// Maps `pairs` and all their descendants (skipping `EOI`) depth-first with `f`, called on a pair before
+        /// its children, and returns the values in the shape of the parse tree, or the first error of `f`.
+        #limit
+        #vis fn map_tree<'i, T, E>(
+            pairs: ::pest::iterators::Pairs<'i, Rule>,
+            f: impl FnMut(RuleKind, ::pest::iterators::Pair<'i, Rule>) -> ::std::result::Result<T, E>,
+        ) -> ::std::result::Result<Vec<Tree<T>>, E>
+        #bound
+        {
+            map_tree_with(pairs, false, f)
+        }
+
+        /// Same as [`map_tree`], but `EOI` is mapped as well if `eoi` is set.
+        #vis fn map_tree_with<'i, T, E>(
+            pairs: ::pest::iterators::Pairs<'i, Rule>,
+            eoi: bool,
+            mut f: impl FnMut(RuleKind, ::pest::iterators::Pair<'i, Rule>) -> ::std::result::Result<T, E>,
+        ) -> ::std::result::Result<Vec<Tree<T>>, E>
+        #bound
+        {
+            fn map<'i, T, E>(
+                pairs: ::pest::iterators::Pairs<'i, Rule>,
+                eoi: bool,
+                #depth
+                f: &mut dyn FnMut(RuleKind, ::pest::iterators::Pair<'i, Rule>) -> ::std::result::Result<T, E>,
+            ) -> ::std::result::Result<Vec<Tree<T>>, E>
+            #bound
+            {
+                pairs
+                    .filter(|pair| eoi || pair.as_rule() != RuleKind::EOI)
+                    .map(|pair| {
+                        #check
+                        #record
+                        let children = pair.clone().into_inner();
+                        Ok(Tree {
+                            value: f(pair.as_rule().kind(), pair)?,
+                            children: map(children, eoi, #next f)?,
+                        })
+                    })
+                    .collect()
+            }
+
+            map(pairs, eoi, #first &mut f)
+        }
+    }
+}
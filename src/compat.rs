@@ -0,0 +1,61 @@
+//! Generation of `CompatParser`, a facade for code written against a plain `pest` parser.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Visibility;
+
+/// `pest::Parser<RuleKind>` cannot be implemented on top of the hooked parser, because the returned
+/// `Pairs<'i, R>` are created by `pest` itself and can't change their rule type. What can be done is
+/// accepting a `RuleKind` on the way in and converting errors back to `Error<RuleKind>` on the way out.
+pub(crate) fn compat_parser_generator(vis: &Visibility, parser: &Ident) -> TokenStream {
+    quote! {
+        /// Facade over the generated parser for code which works with the fieldless `RuleKind`.
+        ///
+        /// NOTE: `pest::Parser<RuleKind>` is NOT implemented, since the returned pairs always carry the
+        /// hooked `Rule`. Use [`CompatParser::parse_kind`] and convert failures with
+        /// [`CompatParser::kind_error`] instead.
+        #vis struct CompatParser;
+
+        impl CompatParser {
+            /// Parses `input` with the rule of the given kind.
+            pub fn parse_kind(
+                kind: RuleKind,
+                input: &str,
+            ) -> ::std::result::Result<::pest::iterators::Pairs<'_, Rule>, ::pest::error::Error<Rule>> {
+                <#parser as ::pest::Parser<Rule>>::parse(Rule::from(kind), input)
+            }
+
+            /// Converts an error of the generated parser into an error over `RuleKind`.
+            ///
+            /// `input` must be the input the error was produced from.
+            pub fn kind_error(error: ::pest::error::Error<Rule>, input: &str) -> ::pest::error::Error<RuleKind> {
+                let path = error.path().map(String::from);
+                let variant = match error.variant {
+                    ::pest::error::ErrorVariant::ParsingError { positives, negatives } => {
+                        ::pest::error::ErrorVariant::ParsingError {
+                            positives: positives.iter().map(Rule::kind).collect(),
+                            negatives: negatives.iter().map(Rule::kind).collect(),
+                        }
+                    }
+                    ::pest::error::ErrorVariant::CustomError { message } => {
+                        ::pest::error::ErrorVariant::CustomError { message }
+                    }
+                };
+                let converted = match error.location {
+                    ::pest::error::InputLocation::Pos(pos) => ::pest::error::Error::new_from_pos(
+                        variant,
+                        ::pest::Position::new(input, pos).expect("error does not belong to `input`"),
+                    ),
+                    ::pest::error::InputLocation::Span((start, end)) => ::pest::error::Error::new_from_span(
+                        variant,
+                        ::pest::Span::new(input, start, end).expect("error does not belong to `input`"),
+                    ),
+                };
+                match path {
+                    Some(path) => converted.with_path(&path),
+                    None => converted,
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,90 @@
+//! Generation of `RuleContext` and `dispatch_with_context()`, a traversal telling handlers where a pair is.
+
+use crate::coverage::record_coverage;
+use crate::rule_timings::time_handler;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+/// The traversal keeps the parent and the number of visited siblings next to every level of its stack, so the
+/// context of a pair costs nothing but copying them. `EOI` is neither visited nor counted as a sibling.
+///
+/// Like `walk_until()`, it is a shim over a nested function taking `f` as `&mut dyn FnMut` and keeping the error
+/// aside, so it is compiled once whatever `E` and the closure are.
+pub(crate) fn rule_context_generator(vis: &Visibility) -> TokenStream {
+    let record = record_coverage(quote!(pair));
+    let visit = time_handler(quote!(pair), quote!(f(context)));
+    quote! {
+        /// Where a pair visited by [`dispatch_with_context`] is in the parse tree.
+        #[derive(Clone, Copy, Debug)]
+        #vis struct RuleContext<'i, 'p> {
+            /// the visited pair.
+            pub pair: &'p ::pest::iterators::Pair<'i, Rule>,
+            /// the number of ancestors of the pair within the walked pairs, `0` for the pairs given.
+            pub depth: usize,
+            /// the rule of the parent pair, `None` for the pairs given.
+            pub parent: Option<RuleKind>,
+            /// the position of the pair among its siblings, not counting `EOI`.
+            pub index_in_parent: usize,
+        }
+
+        /// Visits every pair of `pairs` in pre-order (skipping `EOI`) and hands it to `f` with its context,
+        /// stopping at the first error.
+        #vis fn dispatch_with_context<'i, E>(
+            pairs: ::pest::iterators::Pairs<'i, Rule>,
+            mut f: impl FnMut(RuleContext<'i, '_>) -> Result<(), E>,
+        ) -> Result<(), E> {
+            fn walk<'i>(
+                pairs: ::pest::iterators::Pairs<'i, Rule>,
+                f: &mut dyn FnMut(RuleContext<'i, '_>) -> ::core::ops::ControlFlow<()>,
+            ) {
+                // the siblings left to visit, their parent, the number of siblings visited so far, their depth and
+                // whether they are the children of a transparent pair, spliced in its place.
+                let mut stack = vec![(pairs, None, 0, 0, false)];
+                while let Some((pairs, parent, visited, depth, _)) = stack.last_mut() {
+                    let Some(pair) = pairs.next() else {
+                        // the spliced children were counted as siblings of the transparent pair.
+                        if let Some((_, _, visited, _, true)) = stack.pop() {
+                            if let Some(level) = stack.last_mut() {
+                                level.2 = visited;
+                            }
+                        }
+                        continue;
+                    };
+                    if pair.as_rule() == RuleKind::EOI {
+                        continue;
+                    }
+                    #record
+                    let (parent, depth) = (*parent, *depth);
+                    if pair.as_rule().kind().is_transparent() {
+                        let visited = *visited;
+                        stack.push((pair.into_inner(), parent, visited, depth, true));
+                        continue;
+                    }
+                    let context = RuleContext {
+                        pair: &pair,
+                        depth,
+                        parent,
+                        index_in_parent: *visited,
+                    };
+                    *visited += 1;
+                    if #visit.is_break() {
+                        return;
+                    }
+                    let kind = pair.as_rule().kind();
+                    stack.push((pair.into_inner(), Some(kind), 0, depth + 1, false));
+                }
+            }
+
+            let mut error = None;
+            walk(pairs, &mut |context| match f(context) {
+                Ok(()) => ::core::ops::ControlFlow::Continue(()),
+                Err(e) => {
+                    error = Some(e);
+                    ::core::ops::ControlFlow::Break(())
+                }
+            });
+            error.map_or(Ok(()), Err)
+        }
+    }
+}
@@ -0,0 +1,117 @@
+//! Generation of `RuleCoverage`, counting the rules the walkers hand to the user (the `coverage` feature).
+
+use crate::grammar::GrammarContext;
+use pest_meta::ast::RuleType;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{ext::IdentExt, Visibility};
+
+/// The counts are a `RuleMap` of atomics, so one coverage can be shared (through an `Arc`) by the threads running a
+/// test corpus. The walkers do not take it as an argument, their signatures would depend on the feature: a
+/// coverage is installed for the current thread instead, and every walker records the pairs it visits (or maps)
+/// through `RuleKind::__record_coverage()`, a method since `walk_into()` is generated next to the interface, where
+/// only `Rule` and `RuleKind` are in scope. Without the feature, nothing is generated and nothing recorded.
+///
+/// Silent rules have no pairs and `EOI` is skipped by the walkers, so neither is reported as uncovered.
+pub(crate) fn coverage_generator(
+    vis: &Visibility,
+    rules: &[Ident],
+    grammar: &GrammarContext,
+) -> TokenStream {
+    let silent = &grammar
+        .parsed()
+        .rules
+        .iter()
+        .filter(|rule| rule.ty == RuleType::Silent)
+        .map(|rule| rule.name.as_str())
+        .collect::<Vec<_>>();
+    let visited = rules.iter().filter(|rule| {
+        let name = rule.unraw().to_string();
+        name != "EOI" && !silent.contains(&name.as_str())
+    });
+    quote! {
+        /// How many times the walkers visited each rule on the threads this coverage was installed on, e.g. to
+        /// find the rules a test corpus never exercises.
+        #[derive(Debug)]
+        #vis struct RuleCoverage(RuleMap<::std::sync::atomic::AtomicU64>);
+
+        ::std::thread_local! {
+            static __RULE_COVERAGE: ::core::cell::RefCell<
+                ::core::option::Option<::std::sync::Arc<RuleCoverage>>,
+            > = const { ::core::cell::RefCell::new(::core::option::Option::None) };
+        }
+
+        #[allow(dead_code)]
+        impl RuleCoverage {
+            /// the rules the walkers can visit, all of them but `EOI` and the silent rules.
+            const VISITED: &'static [RuleKind] = &[#(RuleKind::#visited),*];
+
+            /// Returns a coverage with no rule visited.
+            pub fn new() -> Self {
+                Self(RuleMap::from_fn(|_| ::std::sync::atomic::AtomicU64::new(0)))
+            }
+
+            /// Counts a visit of `kind`.
+            #[inline]
+            pub fn record(&self, kind: RuleKind) {
+                self.0[kind].fetch_add(1, ::std::sync::atomic::Ordering::Relaxed);
+            }
+
+            /// Returns the number of visits of `kind`.
+            pub fn count(&self, kind: RuleKind) -> u64 {
+                self.0[kind].load(::std::sync::atomic::Ordering::Relaxed)
+            }
+
+            /// Returns the number of visits of every rule, in definition order.
+            pub fn report(&self) -> Vec<(RuleKind, u64)> {
+                RuleKind::all().iter().map(|kind| (*kind, self.count(*kind))).collect()
+            }
+
+            /// Returns the rules never visited, in definition order, but `EOI` and the silent rules.
+            pub fn uncovered(&self) -> Vec<RuleKind> {
+                Self::VISITED
+                    .iter()
+                    .copied()
+                    .filter(|kind| self.count(*kind) == 0)
+                    .collect()
+            }
+
+            /// Makes the walkers of the current thread record into `coverage`, returning the coverage installed
+            /// before, if any.
+            pub fn install(
+                coverage: ::std::sync::Arc<Self>,
+            ) -> ::core::option::Option<::std::sync::Arc<Self>> {
+                __RULE_COVERAGE.with_borrow_mut(|installed| installed.replace(coverage))
+            }
+
+            /// Stops the recording on the current thread, returning the installed coverage, if any.
+            pub fn uninstall() -> ::core::option::Option<::std::sync::Arc<Self>> {
+                __RULE_COVERAGE.with_borrow_mut(::core::option::Option::take)
+            }
+        }
+
+        impl ::core::default::Default for RuleCoverage {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        impl RuleKind {
+            #[doc(hidden)]
+            #[inline]
+            pub fn __record_coverage(self) {
+                __RULE_COVERAGE.with_borrow(|installed| {
+                    if let ::core::option::Option::Some(coverage) = installed {
+                        coverage.record(self);
+                    }
+                });
+            }
+        }
+    }
+}
+
+/// returns the statement recording a visit of the pair `pair` into the installed coverage, nothing without the
+/// `coverage` feature.
+pub(crate) fn record_coverage(pair: TokenStream) -> Option<TokenStream> {
+    cfg!(feature = "coverage").then(|| quote!(#pair.as_rule().kind().__record_coverage();))
+}
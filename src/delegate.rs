@@ -0,0 +1,164 @@
+//! Generation of `delegate_to`, implementing the interface for the rule structs by calling the functions of one
+//! handler type named after the rules.
+
+use crate::args::{snake_case, PestParserArgs};
+use crate::dispatch::{forward_method, rule_struct_impls};
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{
+    bracketed,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    parse_str, ItemTrait, Path, Token, TraitItem, TraitItemFn,
+};
+
+/// `#[pest_dispatch]` does not know the rules, so, like `rule_output!`, the generated `delegate_rules!` bakes in
+/// the rule structs and forwards the handler type, the method and the trait
+/// given by `#[pest_dispatch(delegate_to = "...")]` to the hidden `__delegate_rules!` of this crate.
+///
+/// A handler is named after its rule struct in snake case (`Statement` to `statement`, `AddOp` to `add_op`, `EOI`
+/// to `eoi`), with a raw prefix for a keyword (`r#match`). The rule structs of overridden rules are the types of
+/// the user, which implement the interface themselves, and are left out.
+pub(crate) fn delegate_generator(rules: &[Ident], args: &PestParserArgs) -> TokenStream {
+    let mut structs = Vec::new();
+    for rule in rules {
+        let rule = rule.to_string();
+        if args.override_of(&rule).is_some() {
+            continue;
+        }
+        let payload = args.payload_type(&rule);
+        // the grouped rules share the struct of their group, which is implemented once.
+        if !structs.contains(&payload) {
+            structs.push(payload);
+        }
+    }
+    let structs = structs
+        .iter()
+        .map(|payload| parse_str::<Path>(payload).expect("illegal payload type"));
+    quote! {
+        /// Implements a trait for the rule structs by calling the function of a handler type named after each
+        /// rule, see `#[pest_dispatch(delegate_to = "...")]`.
+        #[allow(unused_macros)]
+        macro_rules! __delegate_rules_structs {
+            ($($input:tt)*) => {
+                ::enum_dispatch_pest_parser::__delegate_rules! { [#(#structs),*] $($input)* }
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use __delegate_rules_structs as delegate_rules;
+    }
+}
+
+/// returns the name of the handler of the rule struct `name`, e.g. `add_op` for `AddOp`.
+fn handler_ident(name: &Ident) -> Ident {
+    let handler = snake_case(&name.unraw().to_string());
+    // `gen` is reserved from the 2024 edition on, `syn` only rejects the keywords of the 2021 edition.
+    if handler == "gen" || parse_str::<Ident>(&handler).is_err() {
+        Ident::new_raw(&handler, name.span())
+    } else {
+        Ident::new(&handler, name.span())
+    }
+}
+
+/// The input of `__delegate_rules!`: the rule structs baked in by the generated `delegate_rules!`, then the handler
+/// type, the forwarded method and the trait given by `#[pest_dispatch]`.
+pub(crate) struct DelegateInput {
+    structs: Vec<Path>,
+    delegate_to: Path,
+    method: Ident,
+    item: ItemTrait,
+}
+
+impl Parse for DelegateInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        bracketed!(content in input);
+        let mut structs = Vec::new();
+        while !content.is_empty() {
+            structs.push(content.parse()?);
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+        let delegate_to = input.parse()?;
+        input.parse::<Token![;]>()?;
+        let method = input.parse()?;
+        input.parse::<Token![;]>()?;
+        Ok(Self {
+            structs,
+            delegate_to,
+            method,
+            item: input.parse()?,
+        })
+    }
+}
+
+/// Implements the interface for every rule struct, its `method` calling the handler of the rule struct on the
+/// handler type with the arguments of the method. A missing handler is reported by `rustc` as a function not
+/// found on the handler type.
+pub(crate) fn delegate_expand(input: DelegateInput) -> TokenStream {
+    let DelegateInput {
+        structs,
+        delegate_to,
+        method,
+        item,
+    } = input;
+    let Some(method) = item.items.iter().find_map(|trait_item| match trait_item {
+        TraitItem::Fn(function) if function.sig.ident == method => Some(function),
+        _ => None,
+    }) else {
+        return quote!(::core::compile_error!(
+            "the method of `delegate_to` is not in the interface"
+        ));
+    };
+    delegate_impls(&item.ident, &structs, &delegate_to, method)
+}
+
+/// Implements `interface` for the rule structs `structs`, `method` calling the handler of each on `delegate_to`.
+pub(crate) fn delegate_impls(
+    interface: &Ident,
+    structs: &[Path],
+    delegate_to: &Path,
+    method: &TraitItemFn,
+) -> TokenStream {
+    let collision = handler_collision(structs, delegate_to)
+        .map(|message| quote!(::core::compile_error!(#message);));
+    let impls = rule_struct_impls(interface, Some(structs), |rule| {
+        let rule = rule.expect("the rule structs are listed");
+        let handler = handler_ident(&rule.segments.last().expect("empty rule struct").ident);
+        forward_method(
+            method,
+            Some(rule),
+            "handler_method",
+            |_, arguments| quote!(<#delegate_to>::#handler(#(#arguments),*)),
+        )
+    });
+    quote!(#collision #impls)
+}
+
+/// Reports two rule structs with the same handler, i.e. named alike in snake case (`value` and `Value`, `AddOp`
+/// and `add_op`), which `pest` and the rule structs tell apart. Both would silently call the same function. The impls
+/// are still generated, so the error is the only one.
+fn handler_collision(structs: &[Path], delegate_to: &Path) -> Option<String> {
+    let names = structs
+        .iter()
+        .map(|rule| &rule.segments.last().expect("empty rule struct").ident)
+        .collect::<Vec<_>>();
+    for (index, name) in names.iter().enumerate() {
+        let handler = handler_ident(name);
+        if let Some(other) = names[..index]
+            .iter()
+            .find(|other| handler_ident(other) == handler)
+        {
+            return Some(format!(
+                "the rule structs `{}` and `{}` both delegate to `{}::{handler}`, their names are the same in snake case; \
+                 rename one of the rules, or leave one out with `handler_rules` and implement it by hand",
+                other.unraw(),
+                name.unraw(),
+                quote!(#delegate_to).to_string().replace(' ', ""),
+            ));
+        }
+    }
+    None
+}
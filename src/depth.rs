@@ -0,0 +1,54 @@
+//! Generation of `DepthError`, the error of a parse tree nested deeper than `max_depth`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+/// `parse_tree()` and `map_tree()` recurse once per level of the parse tree, so a pathological input (e.g. ten
+/// thousand nested brackets) would overflow the stack. With `max_depth`, they stop at the first pair below that
+/// depth and return a `DepthError` naming it, instead of descending further.
+///
+/// The error keeps the position of the pair as an offset rather than a `Span`, so it does not borrow the input
+/// and converts into the error types of the callers of `map_tree()`. The other walks keep an explicit stack of
+/// their own and are not bounded.
+pub(crate) fn depth_error_generator(vis: &Visibility) -> TokenStream {
+    quote! {
+        /// The error of a pair nested deeper than the `max_depth` of the parser, returned by `parse_tree()` (as the
+        /// message of a `pest` error at the pair) and `map_tree()`.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        #vis struct DepthError {
+            /// the rule of the first pair below the limit.
+            pub rule: RuleKind,
+            /// the byte offset of that pair in the input.
+            pub offset: usize,
+            /// its line and column in the input, both starting at 1.
+            pub line_col: (usize, usize),
+            /// the limit, i.e. the depth of the deepest pairs allowed, the top-level ones being at depth 1.
+            pub max_depth: usize,
+        }
+
+        impl DepthError {
+            /// Returns the error of `pair`, at depth `max_depth + 1`.
+            fn new(pair: &::pest::iterators::Pair<'_, Rule>, max_depth: usize) -> Self {
+                Self {
+                    rule: pair.as_rule().kind(),
+                    offset: pair.as_span().start(),
+                    line_col: pair.line_col(),
+                    max_depth,
+                }
+            }
+        }
+
+        impl ::core::fmt::Display for DepthError {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                write!(
+                    f,
+                    "`{:?}` at {}:{} is nested deeper than the limit of {} levels",
+                    self.rule, self.line_col.0, self.line_col.1, self.max_depth
+                )
+            }
+        }
+
+        impl ::std::error::Error for DepthError {}
+    }
+}
@@ -0,0 +1,389 @@
+//! Generation of `#[pest_dispatch]`, the interface half of a parser split with `#[pest_rules]`.
+
+use crate::args::DispatchArgs;
+use crate::delegate::delegate_impls;
+use crate::output::check_output_interface;
+use crate::walk::walk_into_generator;
+use proc_macro2::{Ident, TokenStream};
+use quote::{format_ident, quote};
+use syn::{parse_quote, FnArg, ItemTrait, Path, ReturnType, TraitItem, TraitItemFn, Type};
+
+/// `#[pest_rules]` registers `enum Rule` with `enum_dispatch` without naming an interface, so the link is made
+/// from the other side: the trait gets `#[enum_dispatch(Rule)]`, and `enum_dispatch` generates the impl when
+/// it has seen both. None of this depends on the grammar, so the rule list is never needed here.
+///
+/// `enum_dispatch` only accepts a bare identifier, and may emit the impl next to the trait, hence `Rule` (and
+/// `RuleKind` for `walk_into()`) must be in scope of the trait. `rules` is still checked to be a parser over
+/// that `Rule`, so a wrong or stale `rules` is reported at the trait instead of in the generated impl.
+///
+/// A trait without methods (a marker trait) has nothing to dispatch: `enum_dispatch` would only implement it
+/// for `Rule`, not for the rule structs, which is easily mistaken for the opposite. Such a trait is left
+/// unlinked and `Rule` stays a plain enum. The items `enum_dispatch` cannot dispatch are reported here, see
+/// [`check_dispatchable`].
+///
+/// With `output_enum`, the trait is not linked at all: `rule_output!`, generated next to the rules, writes the
+/// `match` dispatching it instead.
+pub(crate) fn pest_dispatch_generator(args: &DispatchArgs, mut item: ItemTrait) -> TokenStream {
+    // a plain `#[enum_dispatch]` of the user (or one through a re-export) would register the trait a second time.
+    item.attrs.retain(|attr| {
+        attr.path()
+            .segments
+            .last()
+            .is_none_or(|segment| segment.ident != "enum_dispatch")
+    });
+    let rules = &args.rules;
+    let rule = args.rule_path();
+    let assert_rules = quote! {
+        const _: () = {
+            fn assert_parser<P: ::pest::Parser<R>, R: ::pest::RuleType>() {}
+            fn assert_rules() {
+                assert_parser::<#rules, #rule>();
+            }
+        };
+    };
+
+    if let Some(output_enum) = &args.output_enum {
+        check_output_interface(&item);
+        let mut interface = item.clone();
+        interface.attrs.clear();
+        return quote! {
+            #item
+
+            #assert_rules
+
+            rule_output! { #output_enum; #interface }
+        };
+    }
+
+    if item
+        .items
+        .iter()
+        .any(|item| matches!(item, TraitItem::Fn(_)))
+    {
+        check_dispatchable(&item);
+        let enum_dispatch = args.enum_dispatch_attr();
+        item.attrs.insert(0, parse_quote!(#enum_dispatch));
+    } else {
+        item.attrs.push(parse_quote!(#[doc = ""]));
+        item.attrs.push(parse_quote! {
+            #[doc = " NOTE: this trait has no methods, so it is not dispatched through `Rule` (nor implemented by it)."]
+        });
+    }
+
+    let handler_fn = args
+        .handler_fn
+        .as_ref()
+        .map(|handler_fn| handler_fn_generator(args, handler_fn, &item));
+    let delegate = args
+        .delegate_to
+        .as_ref()
+        .map(|delegate_to| delegate_generator(args, delegate_to, &item));
+    let fallback = args
+        .fallback
+        .as_ref()
+        .map(|fallback| fallback_generator(args, fallback, &item));
+
+    let inherent = args
+        .inherent_dispatch
+        .then(|| inherent_generator(args, &item));
+
+    let interface: Path = item.ident.clone().into();
+    let walk_into = args.walk_method.as_ref().map(|method| {
+        walk_into_generator(
+            &item.vis,
+            &interface,
+            method,
+            args.recover,
+            args.error_type.as_ref(),
+        )
+    });
+
+    quote! {
+        #item
+
+        #assert_rules
+
+        #handler_fn
+
+        #delegate
+
+        #fallback
+
+        #inherent
+
+        #walk_into
+    }
+}
+
+/// Panics on the items of the interface which cannot be dispatched through `Rule`.
+///
+/// `enum_dispatch` implements every item of the trait for `Rule` by matching on `self`: it panics with
+/// "Unsupported trait item" on anything but a method, and a method without receiver fails in the generated impl
+/// instead, where `self` is not available, like a method returning `impl Trait`, whose opaque types differ between
+/// the match arms. The generic parameters of a generic trait are not declared in the impl
+/// generated from the trait side. All of them are reported at the trait, along with what to do instead.
+fn check_dispatchable(item: &ItemTrait) {
+    if !item.generics.params.is_empty() {
+        panic!(
+            "a generic interface cannot be dispatched through `Rule`, `enum_dispatch` does not declare the \
+             parameters of `{}` in the impl for `Rule`; make its methods generic instead",
+            item.ident
+        );
+    }
+    for trait_item in &item.items {
+        match trait_item {
+            TraitItem::Fn(method) => {
+                if !matches!(method.sig.inputs.first(), Some(FnArg::Receiver(_))) {
+                    panic!(
+                        "`{}` of the interface takes no `self`, so it cannot be dispatched through `Rule`; \
+                         move it to another trait implemented by the rule structs",
+                        method.sig.ident
+                    );
+                }
+                if matches!(&method.sig.output, ReturnType::Type(_, ty) if matches!(**ty, Type::ImplTrait(_))) {
+                    panic!(
+                        "`{}` of the interface returns `impl Trait`, whose type differs between the rule structs, \
+                         so it cannot be dispatched through `Rule`; return a `Box<dyn Trait>` instead",
+                        method.sig.ident
+                    );
+                }
+            }
+            TraitItem::Const(item) => {
+                let (ident, ty) = (&item.ident, &item.ty);
+                panic!(
+                    "associated const `{ident}` of the interface cannot be dispatched through `Rule`, \
+                     `enum_dispatch` only dispatches methods; use a method instead (e.g. `fn {}(&self) -> {}`) \
+                     or move it to another trait implemented by the rule structs",
+                    ident.to_string().to_lowercase(),
+                    quote!(#ty)
+                );
+            }
+            TraitItem::Type(item) => panic!(
+                "associated type `{}` of the interface cannot be dispatched through `Rule`, `enum_dispatch` only \
+                 dispatches methods; use a concrete type, a generic parameter of the trait, or move it to another \
+                 trait implemented by the rule structs",
+                item.ident
+            ),
+            _ => panic!(
+                "`enum_dispatch` cannot see the items a macro in the interface expands to; write them out instead"
+            ),
+        }
+    }
+}
+
+/// `handler_fn` implements the interface for the rule structs by forwarding its `handler_method` to one function,
+/// e.g. a `match` over the kinds written before the rules got handlers of their own. The function takes the kind
+/// of the rule, then the arguments of the method, and returns what the method returns, so its signature follows
+/// from the interface, and a mismatch is reported by `rustc` at the call.
+fn handler_fn_generator(args: &DispatchArgs, handler_fn: &Path, item: &ItemTrait) -> TokenStream {
+    let method = forwarded_method(args, item, "handler_fn");
+    rule_struct_impls(&item.ident, args.handler_rules.as_deref(), |rule| {
+        forward_method(
+            method,
+            rule,
+            "handler_method",
+            |kind, arguments| quote!(#handler_fn(#kind, #(#arguments),*)),
+        )
+    })
+}
+
+/// `delegate_to` implements the interface for the rule structs by forwarding its `handler_method` to the function
+/// of the handler type named after each rule struct, e.g. `Handlers::statement` for `Statement`, see
+/// [`delegate_generator`](crate::delegate::delegate_generator). The structs listed by `handler_rules` are known
+/// here; all of them are only known next to the rules, so their impls are written by `delegate_rules!`.
+fn delegate_generator(args: &DispatchArgs, delegate_to: &Path, item: &ItemTrait) -> TokenStream {
+    let method = forwarded_method(args, item, "delegate_to");
+    if let Some(rules) = &args.handler_rules {
+        return delegate_impls(&item.ident, rules, delegate_to, method);
+    }
+    let method = &method.sig.ident;
+    let mut interface = item.clone();
+    interface.attrs.clear();
+    quote! {
+        delegate_rules! { #delegate_to; #method; #interface }
+    }
+}
+
+/// returns the method of the interface forwarded by `argument`: `handler_method`, or the only method.
+fn forwarded_method<'a>(
+    args: &DispatchArgs,
+    item: &'a ItemTrait,
+    argument: &str,
+) -> &'a TraitItemFn {
+    let methods = trait_methods(item);
+    match &args.handler_method {
+        Some(name) => methods
+            .into_iter()
+            .find(|method| method.sig.ident == *name)
+            .unwrap_or_else(|| panic!("`handler_method` `{name}` is not a method of the interface")),
+        None => match methods.as_slice() {
+            [method] => method,
+            [] => panic!("`{argument}` needs a method to forward, the interface has none"),
+            _ => panic!("the interface has several methods, choose the one forwarded to `{argument}` with `handler_method`"),
+        },
+    }
+}
+
+/// `fallback` implements every method of the interface for the `unhandled` rule structs by forwarding it to a
+/// value of the fallback type, built per call from the kind of the rule (`From<RuleKind>`), so the catch-all knows
+/// which rule it stands in for. A rule removed from `unhandled` loses its impl and fails to compile until it gets
+/// one of its own.
+fn fallback_generator(args: &DispatchArgs, fallback: &Path, item: &ItemTrait) -> TokenStream {
+    let methods = trait_methods(item);
+    let interface = &item.ident;
+    rule_struct_impls(interface, args.unhandled.as_deref(), |rule| {
+        let methods = methods.iter().map(|method| {
+            let ident = &method.sig.ident;
+            let receiver = match method.sig.receiver() {
+                Some(receiver) if receiver.reference.is_none() => quote!(),
+                Some(receiver) if receiver.mutability.is_some() => quote!(&mut),
+                _ => quote!(&),
+            };
+            forward_method(method, rule, "every method", |kind, arguments| {
+                quote! {
+                    <#fallback as #interface>::#ident(
+                        #receiver <#fallback as ::core::convert::From<RuleKind>>::from(#kind),
+                        #(#arguments),*
+                    )
+                }
+            })
+        });
+        quote!(#(#methods)*)
+    })
+}
+
+/// `inherent_dispatch` copies every method of the interface into an inherent impl of `Rule`, with the visibility
+/// of the trait and the doc comments of the method, forwarding to the dispatched call. Inherent methods are found
+/// without the trait in scope, so they show up in the completion of a `Rule` anywhere; the trait stays the
+/// dispatch itself. A method named like one of the helpers of `Rule` (e.g. `kind`) is reported by `rustc` as a
+/// duplicate definition.
+fn inherent_generator(args: &DispatchArgs, item: &ItemTrait) -> TokenStream {
+    let rule = args.rule_path();
+    let (vis, interface) = (&item.vis, &item.ident);
+    let methods = trait_methods(item).into_iter().map(|method| {
+        let docs = method
+            .attrs
+            .iter()
+            .filter(|attr| attr.path().is_ident("doc"));
+        let ident = &method.sig.ident;
+        let unsafety = &method.sig.unsafety;
+        let method = forward_method(method, None, "`inherent_dispatch`", |_, arguments| {
+            let call = quote!(<Self as #interface>::#ident(self, #(#arguments),*));
+            match unsafety {
+                Some(_) => quote!(unsafe { #call }),
+                None => call,
+            }
+        });
+        quote! {
+            #(#docs)*
+            #[inline]
+            #vis #method
+        }
+    });
+    quote! {
+        #[allow(dead_code)]
+        impl #rule {
+            #(#methods)*
+        }
+    }
+}
+
+fn trait_methods(item: &ItemTrait) -> Vec<&TraitItemFn> {
+    item.items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Fn(method) => Some(method),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Implements the interface for the rule structs `rules`, with the items returned by `items` for each of them.
+///
+/// The grammar is unknown here, so without `rules` the impl is a blanket impl over `RuleStruct`, finding the kind
+/// through the `From<T> for Rule` of `enum_dispatch`. It excludes impls of the interface for single rule structs;
+/// with `rules`, only the listed structs get an impl, and the others are implemented by hand.
+pub(crate) fn rule_struct_impls(
+    interface: &Ident,
+    rules: Option<&[Path]>,
+    items: impl Fn(Option<&Path>) -> TokenStream,
+) -> TokenStream {
+    match rules {
+        Some(rules) => {
+            let impls = rules.iter().map(|rule| {
+                let items = items(Some(rule));
+                quote! {
+                    impl #interface for #rule {
+                        #items
+                    }
+                }
+            });
+            quote!(#(#impls)*)
+        }
+        None => {
+            let items = items(None);
+            quote! {
+                impl<T: RuleStruct> #interface for T
+                where
+                    Rule: ::core::convert::From<T>,
+                {
+                    #items
+                }
+            }
+        }
+    }
+}
+
+/// Implements `method` for the rule struct `rule` (`None` for the blanket impl) with the body `call(kind, arguments)`,
+/// copying its signature; `role` names the method in the error for a method without receiver.
+pub(crate) fn forward_method(
+    method: &TraitItemFn,
+    rule: Option<&Path>,
+    role: &str,
+    call: impl FnOnce(TokenStream, &[Ident]) -> TokenStream,
+) -> TokenStream {
+    let sig = &method.sig;
+    let mut inputs = sig.inputs.iter();
+    let receiver = match inputs.next() {
+        Some(FnArg::Receiver(receiver)) => receiver,
+        _ => panic!(
+            "{role} of the interface must take `self`, `&self` or `&mut self`, `{}` does not",
+            sig.ident
+        ),
+    };
+    let (names, types): (Vec<_>, Vec<_>) = inputs
+        .enumerate()
+        .map(|(index, input)| match input {
+            FnArg::Typed(input) => (format_ident!("arg{index}"), &input.ty),
+            FnArg::Receiver(_) => unreachable!("only the first argument can be a receiver."),
+        })
+        .unzip();
+    let kind = match rule {
+        // the struct may be renamed from its rule (`rename_strategy`), so its kind is found through its variant.
+        Some(rule) => quote!(<Rule as ::core::convert::From<#rule>>::from(#rule {}).kind()),
+        None => {
+            let this = match receiver.reference {
+                Some(_) => quote!(*self),
+                None => quote!(self),
+            };
+            quote!(<Rule as ::core::convert::From<T>>::from(#this).kind())
+        }
+    };
+    let mut call = call(kind, &names);
+    if sig.asyncness.is_some() {
+        call = quote!(#call.await);
+    }
+    let (asyncness, unsafety, ident, generics, output) = (
+        &sig.asyncness,
+        &sig.unsafety,
+        &sig.ident,
+        &sig.generics,
+        &sig.output,
+    );
+    let where_clause = &generics.where_clause;
+    quote! {
+        #asyncness #unsafety fn #ident #generics (#receiver, #(#names: #types),*) #output #where_clause {
+            #call
+        }
+    }
+}
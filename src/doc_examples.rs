@@ -0,0 +1,223 @@
+//! Generation of the tests of `doc_examples`, parsing the examples written in the doc comments of the rules.
+
+use crate::args::{snake_case, PestParserArgs};
+use crate::grammar::{GrammarContext, GrammarSource};
+use pest_meta::ast::RuleType;
+use proc_macro2::{Ident, Literal, Span, TokenStream};
+use quote::{format_ident, quote, quote_spanned};
+use std::collections::HashMap;
+use syn::{ext::IdentExt, LitStr};
+
+/// An `example:` or `counterexample:` line of the doc comment of a rule.
+struct DocExample {
+    /// the documented rule, as a variant of `RuleKind`.
+    rule: Ident,
+    location: Location,
+    input: String,
+    /// whether the rule must parse `input` (`example:`) or must not (`counterexample:`).
+    parses: bool,
+}
+
+/// Where an example line is, for the messages.
+enum Location {
+    /// `path:line` in a grammar file.
+    File(String),
+    /// `offset` lines below the token at `span` in the code of the user: the literal of `grammar_inline`, or the
+    /// doc comment itself for a grammar given as tokens.
+    Source { span: Span, offset: usize },
+}
+
+impl Location {
+    /// the span the expansion errors about the line point at.
+    fn span(&self) -> Span {
+        match self {
+            Location::File(_) => Span::call_site(),
+            Location::Source { span, .. } => *span,
+        }
+    }
+
+    /// describes the line in the expansion errors, which point at it when it is in the code of the user.
+    fn describe(&self) -> String {
+        match self {
+            Location::File(location) => format!(" ({location})"),
+            Location::Source { .. } => String::new(),
+        }
+    }
+
+    /// returns the expression of the `file:line` of the example, for the messages of its test.
+    fn expr(&self) -> TokenStream {
+        match self {
+            Location::File(location) => quote!(::std::string::String::from(#location)),
+            Location::Source { span, offset } => {
+                let offset = Literal::usize_unsuffixed(*offset);
+                quote_spanned!(*span=> ::std::format!("{}:{}", ::core::file!(), ::core::line!() as usize + #offset))
+            }
+        }
+    }
+}
+
+/// The examples are read from the source of the grammar rather than from the docs `pest` puts on `enum Rule`,
+/// which have lost their line numbers. Each becomes a `#[test]` of a `#[cfg(test)]` module next to the parser
+/// (`__doc_examples_<parser>`, so several parsers of a module do not collide), named after its rule
+/// (`Command_example_1`, `Command_counterexample_1`), which parses it with the rule like `assert_parses!`: an
+/// example must match all of its input, a counterexample must fail or leave some of it. The module names
+/// everything through `super::`, so no rule struct shadows its bindings.
+///
+/// A failed test names its rule and the line of the example, in the grammar file or, for a grammar given inline or
+/// as tokens, in the code of the user, through `file!()` and `line!()` spanned at it.
+///
+/// A marker line must be followed by a string literal, written as in Rust (`example: "@print(\"hi\");"`), which
+/// is checked at expansion time, as is the rule: a silent rule has no variant to parse with. Both are reported by a
+/// `compile_error!` pointing at the line when it is in the code of the user.
+pub(crate) fn doc_examples_generator(
+    parser: &Ident,
+    grammar: &GrammarContext,
+    rules: &[Ident],
+    args: &PestParserArgs,
+) -> TokenStream {
+    // the number of the last example and counterexample of each rule.
+    let mut counts = HashMap::<(String, bool), usize>::new();
+    let mut tests = Vec::new();
+    let examples = match doc_examples(grammar, rules, &args.grammar) {
+        Ok(examples) => examples,
+        Err(error) => return error,
+    };
+    for example in examples {
+        let name = example.rule.unraw().to_string();
+        let count = counts.entry((name.clone(), example.parses)).or_default();
+        *count += 1;
+        let marker = if example.parses {
+            "example"
+        } else {
+            "counterexample"
+        };
+        let test = format_ident!("{name}_{marker}_{count}");
+        tests.push(doc_example_test(parser, &test, &name, &example));
+    }
+    if tests.is_empty() {
+        return quote! {};
+    }
+    let module = format_ident!("__doc_examples_{}", snake_case(&parser.unraw().to_string()));
+    quote! {
+        /// The examples of the rule docs, see `doc_examples`.
+        #[cfg(test)]
+        #[allow(non_snake_case)]
+        mod #module {
+            #(#tests)*
+        }
+    }
+}
+
+/// returns the test of `example`, named `test`, parsing it with the rule `name`.
+fn doc_example_test(parser: &Ident, test: &Ident, name: &str, example: &DocExample) -> TokenStream {
+    let DocExample {
+        rule,
+        location,
+        input,
+        parses,
+    } = example;
+    let location = location.expr();
+    let check = if *parses {
+        quote! {
+            match <super::#parser as ::pest::Parser<super::Rule>>::parse(super::Rule::from(super::RuleKind::#rule), #input) {
+                ::core::result::Result::Ok(mut pairs) => {
+                    let matched = pairs.next().map_or("", |pair| pair.as_str());
+                    ::core::assert!(
+                        matched.len() == #input.len(),
+                        "`{}` only matches {:?} of the example {:?} ({})",
+                        #name, matched, #input, #location
+                    );
+                }
+                ::core::result::Result::Err(error) => ::core::panic!(
+                    "`{}` does not parse the example {:?} ({}):\n{}",
+                    #name, #input, #location, error
+                ),
+            }
+        }
+    } else {
+        quote! {
+            let parsed = <super::#parser as ::pest::Parser<super::Rule>>::parse(super::Rule::from(super::RuleKind::#rule), #input)
+                .ok()
+                .and_then(|mut pairs| pairs.next())
+                .is_some_and(|pair| pair.as_str().len() == #input.len());
+            ::core::assert!(!parsed, "`{}` parses the counterexample {:?} ({})", #name, #input, #location);
+        }
+    };
+    quote! {
+        #[test]
+        fn #test() {
+            #check
+        }
+    }
+}
+
+/// returns the examples of the rule docs of `grammar`, in order, or the `compile_error!` of a malformed one.
+fn doc_examples(
+    grammar: &GrammarContext,
+    rules: &[Ident],
+    source: &GrammarSource,
+) -> Result<Vec<DocExample>, TokenStream> {
+    let silent = grammar
+        .parsed()
+        .rules
+        .iter()
+        .filter(|rule| rule.ty == RuleType::Silent)
+        .map(|rule| rule.name.as_str())
+        .collect::<Vec<_>>();
+    let mut examples = Vec::new();
+    for (name, lines) in grammar.rule_doc_lines() {
+        for (line, doc) in lines {
+            let doc = doc.trim();
+            let (marker, literal, parses) = match (
+                doc.strip_prefix("example:"),
+                doc.strip_prefix("counterexample:"),
+            ) {
+                (Some(literal), _) => ("example", literal.trim(), true),
+                (_, Some(literal)) => ("counterexample", literal.trim(), false),
+                _ => continue,
+            };
+            // the lines of a grammar given as tokens are the ones of its conversion, not of the tokens.
+            let location = match source {
+                GrammarSource::File(path) => Location::File(format!("{path}:{line}")),
+                GrammarSource::Inline(_, span) => Location::Source {
+                    span: *span,
+                    offset: line - 1,
+                },
+                GrammarSource::Tokens(_, doc_spans) => Location::Source {
+                    span: doc_spans
+                        .iter()
+                        .find(|(doc_line, _)| *doc_line == line)
+                        .map_or_else(Span::call_site, |(_, span)| *span),
+                    offset: 0,
+                },
+            };
+            let Ok(input) = syn::parse_str::<LitStr>(literal) else {
+                let message = format!(
+                    "malformed `{marker}:` of `{name}`{}: expected a string literal like `{marker}: \"...\"`, \
+                     found `{literal}`",
+                    location.describe()
+                );
+                return Err(quote_spanned!(location.span()=> ::core::compile_error!(#message);));
+            };
+            if silent.contains(&name.as_str()) {
+                let message = format!(
+                    "`{name}` has `{marker}:` docs{}, but is silent: `pest` has no `Rule` to parse it with",
+                    location.describe()
+                );
+                return Err(quote_spanned!(location.span()=> ::core::compile_error!(#message);));
+            }
+            let rule = rules
+                .iter()
+                .find(|rule| rule.unraw() == name)
+                .unwrap_or_else(|| panic!("`{name}` of the grammar has no variant in `enum Rule`"))
+                .clone();
+            examples.push(DocExample {
+                rule,
+                location,
+                input: input.value(),
+                parses,
+            });
+        }
+    }
+    Ok(examples)
+}
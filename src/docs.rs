@@ -0,0 +1,47 @@
+//! Generation of `RULE_DOCS`, the grammar's `///` doc comments at runtime.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{punctuated::Punctuated, Expr, Lit, Meta, Token, Variant};
+
+/// `pest` already turns the `///` comments of each rule into `#[doc = "..."]` attributes on the variants of
+/// `enum Rule` (lines joined with `\n`), so they are read back from there. `EOI` is skipped, its doc is
+/// made up by `pest` rather than written in the grammar.
+pub(crate) fn rule_docs_generator(variants: &Punctuated<Variant, Token![,]>) -> TokenStream {
+    let docs = variants
+        .iter()
+        .filter(|variant| variant.ident != "EOI")
+        .filter_map(|variant| {
+            let doc = variant
+                .attrs
+                .iter()
+                .filter_map(|attr| match &attr.meta {
+                    Meta::NameValue(meta) if meta.path.is_ident("doc") => match &meta.value {
+                        Expr::Lit(lit) => match &lit.lit {
+                            Lit::Str(doc) => Some(doc.value()),
+                            _ => None,
+                        },
+                        _ => None,
+                    },
+                    _ => None,
+                })
+                .collect::<Vec<_>>()
+                .join("\n");
+            let doc = doc.trim();
+            let ident = &variant.ident;
+            (!doc.is_empty()).then(|| quote! { (RuleKind::#ident, #doc) })
+        });
+
+    quote! {
+        /// Doc comments (`///`) of the grammar rules, rules without docs are omitted.
+        pub static RULE_DOCS: &[(RuleKind, &str)] = &[#(#docs),*];
+
+        /// Returns the doc comment of the rule in the grammar, if any.
+        pub fn doc_of(kind: RuleKind) -> Option<&'static str> {
+            RULE_DOCS
+                .iter()
+                .find(|(documented, _)| *documented == kind)
+                .map(|(_, doc)| *doc)
+        }
+    }
+}
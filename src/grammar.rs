@@ -0,0 +1,673 @@
+//! Resolution and loading of the grammar given to `#[pest_parser(grammar = "...")]`, `grammar_inline = "..."` or
+//! `pest_parser_tokens!`.
+
+use pest_generator::docs::{self, DocComment};
+use pest_generator::generator::generate;
+use pest_generator::parse_derive::ParsedDerive;
+use pest_meta::ast::{self, Expr, RuleType};
+use pest_meta::optimizer::{self, OptimizedExpr, OptimizedRule};
+use pest_meta::parser::{self, rename_meta_rule, Rule};
+use pest_meta::{unwrap_or_report, validator};
+use proc_macro2::{Delimiter, Ident, Spacing, Span, TokenStream, TokenTree};
+use std::cell::OnceCell;
+use std::collections::{HashMap, HashSet};
+use std::fmt::Write;
+use std::path::{Component, Path, PathBuf, MAIN_SEPARATOR_STR};
+
+/// Where the grammar of a parser comes from.
+pub(crate) enum GrammarSource {
+    /// a file, as given to `grammar = "..."`.
+    File(String),
+    /// the source itself, as given to `grammar_inline = "..."`, and the span of the literal.
+    Inline(String, Span),
+    /// the source, converted from the tokens given to `pest_parser_tokens!` (see [`grammar_from_tokens`]), and the
+    /// span of every doc comment by its line in the source.
+    Tokens(String, Vec<(usize, Span)>),
+}
+
+impl GrammarSource {
+    /// describes the grammar in messages, e.g. the timings.
+    pub fn label(&self) -> &str {
+        match self {
+            GrammarSource::File(path) => path,
+            GrammarSource::Inline(..) => "<inline>",
+            GrammarSource::Tokens(..) => "<tokens>",
+        }
+    }
+}
+
+/// The grammar of one expansion.
+///
+/// Invariant: the file is read exactly once, by [`GrammarContext::load`], and parsed with `pest_meta` at most
+/// once, on the first [`GrammarContext::parsed`]. Every consumer (the cache key, `pest`'s code generation,
+/// `RULE_META`, `Rule::children_of()`) is served from here, so they cannot see different versions of a
+/// grammar edited during the build, and a cache hit does not parse the grammar at all.
+pub(crate) struct GrammarContext {
+    /// `None` for a grammar given inline or as tokens.
+    path: Option<String>,
+    source: String,
+    parsed: OnceCell<ParsedGrammar>,
+}
+
+/// The grammar as parsed by `pest_meta`, the same steps as `pest_generator::derive_parser`.
+pub(crate) struct ParsedGrammar {
+    /// the built-in rules the grammar uses, e.g. `EOI`.
+    defaults: Vec<String>,
+    doc_comment: DocComment,
+    /// the rules in definition order, before `pest`'s optimizer.
+    pub rules: Vec<ast::Rule>,
+}
+
+impl GrammarContext {
+    /// resolves a grammar file (see [`resolve_grammar_path`]) and reads it.
+    pub fn load(grammar: &GrammarSource) -> Self {
+        let grammar = match grammar {
+            GrammarSource::File(grammar) => grammar,
+            GrammarSource::Inline(source, _) | GrammarSource::Tokens(source, _) => {
+                return Self {
+                    path: None,
+                    source: source.clone(),
+                    parsed: OnceCell::new(),
+                }
+            }
+        };
+        let path = resolve_grammar_path(grammar);
+        let path = path
+            .to_str()
+            .expect("path of the grammar file must be valid UTF-8")
+            .to_string();
+        let source = std::fs::read_to_string(&path)
+            .unwrap_or_else(|err| panic!("cannot read grammar file `{path}`: {err}"));
+        Self {
+            path: Some(path),
+            source,
+            parsed: OnceCell::new(),
+        }
+    }
+
+    /// the absolute path of the grammar file, `None` for a grammar given inline or as tokens.
+    pub fn path(&self) -> Option<&str> {
+        self.path.as_deref()
+    }
+
+    /// the content of the grammar file.
+    pub fn source(&self) -> &str {
+        &self.source
+    }
+
+    /// parses the grammar on first use, panicking with `pest`'s own messages if it is invalid.
+    pub fn parsed(&self) -> &ParsedGrammar {
+        self.parsed.get_or_init(|| {
+            let pairs = parser::parse(Rule::grammar_rules, &self.source).unwrap_or_else(|error| {
+                panic!("error parsing \n{}", error.renamed_rules(rename_meta_rule))
+            });
+            let defaults = unwrap_or_report(validator::validate_pairs(pairs.clone()));
+            ParsedGrammar {
+                defaults: defaults.into_iter().map(String::from).collect(),
+                doc_comment: docs::consume(pairs.clone()),
+                rules: unwrap_or_report(parser::consume_rules(pairs)),
+            }
+        })
+    }
+
+    /// Generates `pest`'s parser for `ident`, like `derive_parser` with `include_grammar`, but from the
+    /// loaded grammar instead of reading the file again. A grammar given inline or as tokens includes no file.
+    pub fn generate_parser(&self, ident: &Ident) -> TokenStream {
+        let parsed_derive = ParsedDerive {
+            name: ident.clone(),
+            generics: Default::default(),
+            non_exhaustive: false,
+        };
+        let parsed = self.parsed();
+        generate(
+            parsed_derive,
+            self.path.iter().map(PathBuf::from).collect(),
+            // not optional: besides optimizing, it lowers `e{n}` repetitions and restores the stack on failure.
+            optimize(parsed.rules.clone()),
+            parsed.defaults.iter().map(String::as_str).collect(),
+            &parsed.doc_comment,
+            true,
+        )
+    }
+
+    /// the `///` doc comments of the rules, in definition order: each documented rule with its lines, numbered
+    /// from 1 in the source. Read like `pest_generator::docs::consume`, which joins the lines and drops the numbers.
+    pub fn rule_doc_lines(&self) -> Vec<(String, Vec<(usize, String)>)> {
+        // reports an invalid grammar with `pest`'s messages first.
+        self.parsed();
+        let pairs =
+            parser::parse(Rule::grammar_rules, &self.source).expect("the grammar is parsed");
+        let mut docs = Vec::new();
+        let mut lines = Vec::new();
+        for pair in pairs.filter(|pair| pair.as_rule() == Rule::grammar_rule) {
+            let Some(inner) = pair.into_inner().next() else {
+                continue;
+            };
+            match inner.as_rule() {
+                Rule::line_doc => {
+                    let line = inner.line_col().0;
+                    if let Some(doc) = inner.into_inner().next() {
+                        lines.push((line, doc.as_str().to_string()));
+                    }
+                }
+                Rule::identifier if !lines.is_empty() => {
+                    docs.push((inner.as_str().to_string(), std::mem::take(&mut lines)));
+                }
+                _ => {}
+            }
+        }
+        docs
+    }
+}
+
+/// the number of alternatives from which a choice of literals is held out of `pest`'s optimizer.
+const HELD_CHOICE_MIN: usize = 64;
+
+/// the prefix of the rule names standing in for held choices while `pest`'s optimizer runs.
+const HELD_CHOICE: &str = "__enum_dispatch_pest_choice_";
+
+/// Runs `pest`'s optimizer, holding long choices of literals (e.g. the keywords of a language) out of it.
+///
+/// Its last pass checks every alternative of a choice for stack operations by walking (and cloning) all the
+/// alternatives after it, so it is cubic in the length of a choice: in a debug build, one rule of 500 keywords took
+/// 3.4s, 1000 took 30s and 2000 took 262s, far more than everything else of an expansion. Such a choice is replaced
+/// by a rule reference before the optimizer and by its right-nested chain after it, which is what the optimizer
+/// makes of a choice of literals. The other passes see the reference instead of the literals, so a choice is only
+/// held where none of them can tell the difference:
+/// - the skipper turns `(!choice ~ ANY)*` in an atomic rule into a jump to the next of the literals, looking through
+///   rule references, so the rules with such a loop and the rules it reaches are left alone;
+/// - the factorizer drops a last alternative `literal ~ rest` after that same literal, so a run of literals right
+///   before such an alternative is left alone;
+/// - the factorizer and the lister compare expressions, so the same run held twice gets the same reference, and
+///   the unroller copies them, so a reference is restored as often as it is found.
+///
+/// Shorter choices go through the optimizer as usual. The unit tests check that the output is the optimizer's.
+fn optimize(rules: Vec<ast::Rule>) -> Vec<OptimizedRule> {
+    let (holding, held) = hold_choices(&rules);
+    if held.is_empty() {
+        return optimizer::optimize(rules);
+    }
+    let mut restored = vec![false; held.len()];
+    let optimized = optimizer::optimize(holding)
+        .into_iter()
+        .map(|rule| OptimizedRule {
+            expr: restore_held(rule.expr, &held, &mut restored),
+            ..rule
+        })
+        .collect();
+    // a choice held under an expression `restore_held` cannot see into (those of `grammar-extras`).
+    if restored.contains(&false) {
+        return optimizer::optimize(rules);
+    }
+    optimized
+}
+
+/// returns `rules` with their long choices of literals replaced by references to the returned choices, see
+/// [`optimize`].
+fn hold_choices(rules: &[ast::Rule]) -> (Vec<ast::Rule>, Vec<Vec<OptimizedExpr>>) {
+    let skipped = skipped_rules(rules);
+    let mut held = Vec::new();
+    let holding = rules
+        .iter()
+        .cloned()
+        .map(|rule| {
+            if skipped.contains(rule.name.as_str()) {
+                return rule;
+            }
+            ast::Rule {
+                expr: hold_in(rule.expr, &mut held),
+                ..rule
+            }
+        })
+        .collect();
+    (holding, held)
+}
+
+/// returns the names of the rules `pest`'s skipper may rewrite or look into: the atomic rules with a
+/// `(!expr ~ ANY)*` loop, and the rules referenced by the choices of its `expr`.
+fn skipped_rules(rules: &[ast::Rule]) -> HashSet<&str> {
+    fn reach<'r>(expr: &Expr, bodies: &HashMap<&'r str, &'r Expr>, names: &mut HashSet<&'r str>) {
+        match expr {
+            Expr::Choice(lhs, rhs) => {
+                reach(lhs, bodies, names);
+                reach(rhs, bodies, names);
+            }
+            Expr::Ident(name) => {
+                if let Some((name, body)) = bodies.get_key_value(name.as_str()) {
+                    if names.insert(name) {
+                        reach(body, bodies, names);
+                    }
+                }
+            }
+            _ => {}
+        }
+    }
+
+    let bodies = rules
+        .iter()
+        .map(|rule| (rule.name.as_str(), &rule.expr))
+        .collect::<HashMap<_, _>>();
+    let mut names = HashSet::new();
+    for rule in rules.iter().filter(|rule| rule.ty == RuleType::Atomic) {
+        for expr in rule.expr.iter_top_down() {
+            let Expr::Rep(body) = expr else {
+                continue;
+            };
+            if let Expr::Seq(lhs, rhs) = *body {
+                if let (Expr::NegPred(skipped), Expr::Ident(any)) = (*lhs, *rhs) {
+                    if any == "ANY" {
+                        names.insert(rule.name.as_str());
+                        reach(&skipped, &bodies, &mut names);
+                    }
+                }
+            }
+        }
+    }
+    names
+}
+
+/// holds the long choices of literals in `expr`. A chain of choices is flattened once, at its outermost node, so
+/// its nested `Choice` nodes are not visited again; only its alternatives are recursed into. Node tags are left as
+/// they are, being cfg'd on a feature of `pest_meta`.
+fn hold_in(expr: Expr, held: &mut Vec<Vec<OptimizedExpr>>) -> Expr {
+    let mut hold = |expr: Box<Expr>| Box::new(hold_in(*expr, held));
+    match expr {
+        Expr::Choice(..) => hold_literal_choices(expr, held),
+        Expr::Seq(lhs, rhs) => {
+            let lhs = hold(lhs);
+            Expr::Seq(lhs, hold(rhs))
+        }
+        Expr::PosPred(expr) => Expr::PosPred(hold(expr)),
+        Expr::NegPred(expr) => Expr::NegPred(hold(expr)),
+        Expr::Opt(expr) => Expr::Opt(hold(expr)),
+        Expr::Rep(expr) => Expr::Rep(hold(expr)),
+        Expr::RepOnce(expr) => Expr::RepOnce(hold(expr)),
+        Expr::RepExact(expr, count) => Expr::RepExact(hold(expr), count),
+        Expr::RepMin(expr, min) => Expr::RepMin(hold(expr), min),
+        Expr::RepMax(expr, max) => Expr::RepMax(hold(expr), max),
+        Expr::RepMinMax(expr, min, max) => Expr::RepMinMax(hold(expr), min, max),
+        Expr::Push(expr) => Expr::Push(hold(expr)),
+        expr => expr,
+    }
+}
+
+/// rebuilds `choice` as a right-nested chain, like the optimizer does, with every run of at least
+/// `HELD_CHOICE_MIN` literal alternatives replaced by a reference to its entry in `held`.
+fn hold_literal_choices(choice: Expr, held: &mut Vec<Vec<OptimizedExpr>>) -> Expr {
+    // the alternatives in order, nested choices flattened, with the choices inside them held.
+    let mut alternatives = Vec::new();
+    let mut pending = vec![choice];
+    while let Some(expr) = pending.pop() {
+        match expr {
+            Expr::Choice(lhs, rhs) => {
+                pending.push(*rhs);
+                pending.push(*lhs);
+            }
+            expr => alternatives.push(hold_in(expr, held)),
+        }
+    }
+
+    let count = alternatives.len();
+    let mut rebuilt = Vec::with_capacity(count);
+    let mut run = Vec::new();
+    let mut end_run = |run: &mut Vec<Expr>, rebuilt: &mut Vec<Expr>| {
+        if run.len() < HELD_CHOICE_MIN {
+            rebuilt.append(run);
+            return;
+        }
+        let literals = run
+            .drain(..)
+            .map(|expr| match expr {
+                Expr::Str(string) => OptimizedExpr::Str(string),
+                Expr::Insens(string) => OptimizedExpr::Insens(string),
+                Expr::Range(start, end) => OptimizedExpr::Range(start, end),
+                _ => unreachable!("only literals are held."),
+            })
+            .collect::<Vec<_>>();
+        let index = match held.iter().position(|choice| *choice == literals) {
+            Some(index) => index,
+            None => {
+                held.push(literals);
+                held.len() - 1
+            }
+        };
+        rebuilt.push(Expr::Ident(format!("{HELD_CHOICE}{index}")));
+    };
+    for (index, alternative) in alternatives.into_iter().enumerate() {
+        match alternative {
+            Expr::Str(_) | Expr::Insens(_) | Expr::Range(..) => run.push(alternative),
+            // the factorizer compares the literal before a last `Seq` with its first expression.
+            Expr::Seq(..) if index == count - 1 => {
+                rebuilt.append(&mut run);
+                rebuilt.push(alternative);
+            }
+            alternative => {
+                end_run(&mut run, &mut rebuilt);
+                rebuilt.push(alternative);
+            }
+        }
+    }
+    end_run(&mut run, &mut rebuilt);
+
+    let mut rebuilt = rebuilt.into_iter().rev();
+    let last = rebuilt.next().expect("a choice has alternatives");
+    rebuilt.fold(last, |chain, alternative| {
+        Expr::Choice(Box::new(alternative), Box::new(chain))
+    })
+}
+
+/// replaces the references to held choices in `expr` by their chains, noting the restored ones in `restored`.
+///
+/// A run of a longer choice is spliced into the chain of its choice, which `pest` generates as a flat sequence of
+/// alternatives, and the optimizer keeps the reference as a direct alternative of it.
+fn restore_held(
+    expr: OptimizedExpr,
+    held: &[Vec<OptimizedExpr>],
+    restored: &mut [bool],
+) -> OptimizedExpr {
+    let mut restore = |expr: Box<OptimizedExpr>| Box::new(restore_held(*expr, held, restored));
+    match expr {
+        OptimizedExpr::Choice(lhs, rhs) => match (held_index(&lhs), held_index(&rhs)) {
+            (Some(index), _) => {
+                let rhs = restore_held(*rhs, held, restored);
+                restored[index] = true;
+                choice_chain(held[index].clone(), Some(rhs))
+            }
+            (None, Some(index)) => {
+                let lhs = restore_held(*lhs, held, restored);
+                restored[index] = true;
+                OptimizedExpr::Choice(
+                    Box::new(lhs),
+                    Box::new(choice_chain(held[index].clone(), None)),
+                )
+            }
+            (None, None) => {
+                let lhs = restore_held(*lhs, held, restored);
+                OptimizedExpr::Choice(Box::new(lhs), Box::new(restore_held(*rhs, held, restored)))
+            }
+        },
+        OptimizedExpr::Seq(lhs, rhs) => {
+            let lhs = restore(lhs);
+            OptimizedExpr::Seq(lhs, restore(rhs))
+        }
+        OptimizedExpr::PosPred(expr) => OptimizedExpr::PosPred(restore(expr)),
+        OptimizedExpr::NegPred(expr) => OptimizedExpr::NegPred(restore(expr)),
+        OptimizedExpr::Opt(expr) => OptimizedExpr::Opt(restore(expr)),
+        OptimizedExpr::Rep(expr) => OptimizedExpr::Rep(restore(expr)),
+        OptimizedExpr::Push(expr) => OptimizedExpr::Push(restore(expr)),
+        OptimizedExpr::RestoreOnErr(expr) => OptimizedExpr::RestoreOnErr(restore(expr)),
+        expr => match held_index(&expr) {
+            Some(index) => {
+                restored[index] = true;
+                choice_chain(held[index].clone(), None)
+            }
+            None => expr,
+        },
+    }
+}
+
+/// returns the index in `held` of the choice `expr` stands in for.
+fn held_index(expr: &OptimizedExpr) -> Option<usize> {
+    match expr {
+        OptimizedExpr::Ident(name) => name
+            .strip_prefix(HELD_CHOICE)
+            .map(|index| index.parse().expect("held choices are numbered")),
+        _ => None,
+    }
+}
+
+/// the right-nested chain of the held `alternatives` followed by the ones of `rest`, as the optimizer would have
+/// left them.
+fn choice_chain(alternatives: Vec<OptimizedExpr>, rest: Option<OptimizedExpr>) -> OptimizedExpr {
+    let mut alternatives = alternatives
+        .into_iter()
+        .map(Some)
+        .chain([rest])
+        .rev()
+        .flatten();
+    let last = alternatives.next().expect("a choice has alternatives");
+    alternatives.fold(last, |chain, alternative| {
+        OptimizedExpr::Choice(Box::new(alternative), Box::new(chain))
+    })
+}
+
+/// Resolves `grammar` the same way `pest` does (relative to `CARGO_MANIFEST_DIR`, then to its `src/`),
+/// but accepts both `/` and `\` as separators on every platform.
+///
+/// The returned path is absolute and normalized, so it can be forwarded to `pest` as is.
+fn resolve_grammar_path(grammar: &str) -> PathBuf {
+    let root = std::env::var("CARGO_MANIFEST_DIR").unwrap_or_else(|_| ".".into());
+    let root = std::path::absolute(root).expect("cannot resolve `CARGO_MANIFEST_DIR`");
+    let grammar = grammar.replace(['/', '\\'], MAIN_SEPARATOR_STR);
+
+    let candidates = [
+        normalize(&root.join(&grammar)),
+        normalize(&root.join("src").join(&grammar)),
+    ];
+    match candidates.iter().find(|candidate| candidate.is_file()) {
+        Some(path) => path.clone(),
+        None => panic!(
+            "cannot find grammar file `{grammar}`, tried `{}` and `{}`",
+            candidates[0].display(),
+            candidates[1].display()
+        ),
+    }
+}
+
+/// removes `.` and `..` components lexically, without touching the file system.
+fn normalize(path: &Path) -> PathBuf {
+    let mut normalized = PathBuf::new();
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                normalized.pop();
+            }
+            component => normalized.push(component),
+        }
+    }
+    normalized
+}
+
+/// Converts the tokens given as grammar to `pest_parser_tokens!` back to the source of the grammar.
+///
+/// `pest`'s syntax lexes as Rust tokens, and the tokens are printed separated by spaces, which `pest` ignores,
+/// except inside its own tokens: joint punctuation (`..`) is kept together, and `#` (tags), `^` (case-insensitive
+/// strings) and `-` (negative indices of `PEEK[..]`) are glued to the next token. Line comments are dropped by the lexer, while doc
+/// comments arrive as `#[doc = "..."]` and `#![doc = "..."]`, which are turned back into `///` and `//!`.
+pub(crate) fn grammar_from_tokens(tokens: TokenStream) -> GrammarSource {
+    let mut source = String::new();
+    let mut doc_spans = Vec::new();
+    write_tokens(&mut source, &mut doc_spans, tokens);
+    GrammarSource::Tokens(source, doc_spans)
+}
+
+fn write_tokens(source: &mut String, doc_spans: &mut Vec<(usize, Span)>, tokens: TokenStream) {
+    let mut tokens = tokens.into_iter().peekable();
+    while let Some(token) = tokens.next() {
+        match token {
+            TokenTree::Punct(punct) if punct.as_char() == '#' => {
+                let inner = tokens
+                    .next_if(
+                        |token| matches!(token, TokenTree::Punct(bang) if bang.as_char() == '!'),
+                    )
+                    .is_some();
+                let doc = tokens.next_if(|token| doc_comment(token).is_some());
+                match doc.as_ref().and_then(doc_comment) {
+                    Some(doc) => {
+                        doc_spans.push((source.matches('\n').count() + 1, punct.span()));
+                        let marker = if inner { "//!" } else { "///" };
+                        let _ = writeln!(source, "{marker}{doc}");
+                    }
+                    None => {
+                        source.push('#');
+                        if inner {
+                            source.push('!');
+                        }
+                    }
+                }
+            }
+            TokenTree::Punct(punct) => {
+                source.push(punct.as_char());
+                if punct.spacing() == Spacing::Alone && !matches!(punct.as_char(), '^' | '-') {
+                    source.push(' ');
+                }
+            }
+            TokenTree::Group(group) => {
+                let (open, close) = match group.delimiter() {
+                    Delimiter::Parenthesis => ("(", ")"),
+                    Delimiter::Brace => ("{", "}"),
+                    Delimiter::Bracket => ("[", "]"),
+                    Delimiter::None => ("", ""),
+                };
+                source.push_str(open);
+                source.push(' ');
+                write_tokens(source, doc_spans, group.stream());
+                source.push_str(close);
+                source.push('\n');
+            }
+            token => {
+                let _ = write!(source, "{token} ");
+            }
+        }
+    }
+}
+
+/// returns the text of `[doc = "..."]`, the bracketed part of a doc comment.
+fn doc_comment(token: &TokenTree) -> Option<String> {
+    let TokenTree::Group(group) = token else {
+        return None;
+    };
+    if group.delimiter() != Delimiter::Bracket {
+        return None;
+    }
+    let attr = syn::parse2::<syn::MetaNameValue>(group.stream()).ok()?;
+    if !attr.path.is_ident("doc") {
+        return None;
+    }
+    match attr.value {
+        syn::Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(doc),
+            ..
+        }) => Some(doc.value()),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// a choice of `count` keywords, long enough to be held from 64 on.
+    fn keywords(count: usize) -> String {
+        (0..count)
+            .map(|index| format!("\"kw{index}\""))
+            .collect::<Vec<_>>()
+            .join(" | ")
+    }
+
+    /// optimizes `grammar` with and without holding its long choices, checks that both give the same rules, and
+    /// returns the number of held choices.
+    fn held_choices(grammar: &str) -> usize {
+        let grammar =
+            GrammarContext::load(&GrammarSource::Inline(grammar.into(), Span::call_site()));
+        let rules = grammar.parsed().rules.clone();
+        assert_eq!(optimize(rules.clone()), optimizer::optimize(rules.clone()));
+        hold_choices(&rules).1.len()
+    }
+
+    #[test]
+    fn holds_long_choices() {
+        let keywords = keywords(100);
+        let grammar = format!("keyword = @{{ ({keywords}) ~ !ASCII_ALPHANUMERIC }}");
+        assert_eq!(held_choices(&grammar), 1);
+        let grammar =
+            format!("value = {{ ASCII_DIGIT+ | {keywords} | \"(\" ~ value ~ \")\" | {keywords} }}");
+        assert_eq!(held_choices(&grammar), 1);
+        // a chain is flattened at its outermost node only, the choices inside its alternatives are held on their own.
+        let nested = self::keywords(80);
+        let grammar = format!("nested = {{ \"(\" ~ ({nested}) ~ \")\" | {keywords} }}");
+        assert_eq!(held_choices(&grammar), 2);
+        assert_eq!(
+            held_choices(&format!("short = {{ {} }}", self::keywords(63))),
+            0
+        );
+    }
+
+    #[test]
+    fn holds_the_same_choice_once() {
+        let keywords = keywords(70);
+        // the factorizer compares the first expressions of the alternatives, the lister the repeated expressions.
+        assert_eq!(
+            held_choices(&format!(
+                "pair = @{{ ({keywords}) ~ \"a\" | ({keywords}) ~ \"b\" }}"
+            )),
+            1
+        );
+        assert_eq!(
+            held_choices(&format!(
+                "list = {{ (({keywords}) ~ \",\")* ~ ({keywords}) }}"
+            )),
+            1
+        );
+        // the unroller copies the choice.
+        assert_eq!(
+            held_choices(&format!("repeated = {{ ({keywords}){{2, 3}} }}")),
+            1
+        );
+    }
+
+    #[test]
+    fn leaves_the_skipped_choices_to_the_optimizer() {
+        let keywords = keywords(70);
+        assert_eq!(
+            held_choices(&format!("text = @{{ (!({keywords}) ~ ANY)* }}")),
+            0
+        );
+        let grammar =
+            format!("reserved = {{ {keywords} }}\ntext = @{{ (!(\"end\" | reserved) ~ ANY)* }}");
+        assert_eq!(held_choices(&grammar), 0);
+        // a negative predicate without the loop is not skipped.
+        let grammar =
+            format!("reserved = {{ {keywords} }}\nname = @{{ !reserved ~ ASCII_ALPHA+ }}");
+        assert_eq!(held_choices(&grammar), 1);
+    }
+
+    #[test]
+    fn leaves_a_choice_before_a_last_sequence_to_the_optimizer() {
+        let keywords = keywords(70);
+        assert_eq!(
+            held_choices(&format!("word = {{ {keywords} | \"kw69\" ~ \"!\" }}")),
+            0
+        );
+        assert_eq!(
+            held_choices(&format!(
+                "word = {{ {keywords} | \"kw69\" ~ \"!\" | \"?\" }}"
+            )),
+            1
+        );
+    }
+
+    #[test]
+    fn accepts_both_separators() {
+        let slash = resolve_grammar_path("examples/grammar.pest");
+        assert_eq!(resolve_grammar_path("examples\\grammar.pest"), slash);
+        assert_eq!(
+            resolve_grammar_path("tests\\..\\examples/./grammar.pest"),
+            slash
+        );
+        assert!(slash.is_absolute() && slash.ends_with("examples/grammar.pest"));
+    }
+
+    #[test]
+    fn falls_back_to_src() {
+        let path = resolve_grammar_path("..\\examples\\grammar.pest");
+        assert_eq!(path, resolve_grammar_path("examples/grammar.pest"));
+    }
+
+    #[test]
+    #[should_panic(expected = "cannot find grammar file")]
+    fn reports_a_missing_grammar() {
+        resolve_grammar_path("examples\\missing.pest");
+    }
+}
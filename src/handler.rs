@@ -0,0 +1,129 @@
+//! Generation of `#[pest_handler]`, implementing the interface for rule structs by forwarding to methods.
+
+use crate::args::HandlerArgs;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{punctuated::Punctuated, FnArg, ImplItem, ItemImpl, Path, Signature, Token, Type};
+
+/// Every handler becomes the interface `method` of each rule struct it handles, forwarding to the handler.
+///
+/// The macro cannot see the interface, so the method gets the signature of the handler, with the tokens (and
+/// spans) written by the user: if it does not line up with the interface, `rustc` reports the mismatch at the
+/// handler. Likewise, an unknown rule is reported where it is named, by `rule` or `#[rule(...)]`.
+///
+/// The first argument of a handler stands for the rule struct. It is either the receiver, for a block on the
+/// rule struct itself, or a typed argument (`rule: &Statement`), for a block on any other type. In the latter
+/// case the method takes `self` the same way (`&self`, `&mut self` or `self`).
+pub(crate) fn pest_handler_generator(args: &HandlerArgs, mut item: ItemImpl) -> TokenStream {
+    assert!(
+        item.trait_.is_none(),
+        "`#[pest_handler]` goes on an inherent impl block, not on a trait impl"
+    );
+    assert!(
+        item.generics.params.is_empty(),
+        "`#[pest_handler]` does not support generic impl blocks"
+    );
+
+    // the `#[rule(...)]` markers are not attributes rustc knows, so they are taken off the methods.
+    let mut handlers = Vec::new();
+    for impl_item in &mut item.items {
+        let ImplItem::Fn(function) = impl_item else {
+            continue;
+        };
+        let mut rules = Vec::new();
+        function.attrs.retain(|attr| {
+            if !attr.path().is_ident("rule") {
+                return true;
+            }
+            rules.extend(
+                attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+                    .unwrap_or_else(|err| panic!("`#[rule(...)]` must list rule structs: {err}")),
+            );
+            false
+        });
+        if !rules.is_empty() {
+            handlers.push((rules, function.sig.clone()));
+        }
+    }
+    if let Some(rule) = &args.rule {
+        assert!(
+            handlers.is_empty(),
+            "`rule` is given to `#[pest_handler]` and to `#[rule(...)]`, use one or the other"
+        );
+        let mut functions = item.items.iter().filter_map(|item| match item {
+            ImplItem::Fn(function) => Some(function),
+            _ => None,
+        });
+        let (Some(function), None) = (functions.next(), functions.next()) else {
+            panic!("`rule` requires a block with a single method, mark each handler with `#[rule(...)]` instead");
+        };
+        handlers.push((vec![rule.clone()], function.sig.clone()));
+    }
+    assert!(
+        !handlers.is_empty(),
+        "no handler in the block, give `rule` or mark the handlers with `#[rule(...)]`"
+    );
+
+    let impls = handlers
+        .iter()
+        .flat_map(|(rules, sig)| {
+            rules
+                .iter()
+                .map(|rule| forward(args, &item.self_ty, rule, sig))
+        })
+        .collect::<Vec<_>>();
+
+    quote! {
+        #item
+
+        #(#impls)*
+    }
+}
+
+/// returns the impl of the interface for `rule`, whose method forwards to the handler `sig` of `self_ty`.
+fn forward(args: &HandlerArgs, self_ty: &Type, rule: &Path, sig: &Signature) -> TokenStream {
+    let handler = &sig.ident;
+    let mut inputs = sig.inputs.iter();
+    let receiver = match inputs.next() {
+        Some(FnArg::Receiver(receiver)) => quote!(#receiver),
+        Some(FnArg::Typed(rule)) => match &*rule.ty {
+            Type::Reference(reference) => {
+                let (and, lifetime, mutability) =
+                    (reference.and_token, &reference.lifetime, reference.mutability);
+                quote!(#and #lifetime #mutability self)
+            }
+            _ => quote!(self),
+        },
+        None => panic!(
+            "handler `{handler}` has no arguments, it takes the rule struct as receiver or first argument"
+        ),
+    };
+    let (names, types): (Vec<_>, Vec<_>) = inputs
+        .enumerate()
+        .map(|(index, input)| match input {
+            FnArg::Typed(input) => (format_ident!("arg{index}"), &input.ty),
+            FnArg::Receiver(_) => unreachable!("only the first argument can be a receiver."),
+        })
+        .unzip();
+
+    let interface = &args.interface;
+    let method = &args.method;
+    let (asyncness, unsafety, generics, output) =
+        (&sig.asyncness, &sig.unsafety, &sig.generics, &sig.output);
+    let where_clause = &generics.where_clause;
+    let mut call = quote!(<#self_ty>::#handler(self, #(#names),*));
+    if asyncness.is_some() {
+        call = quote!(#call.await);
+    }
+    if unsafety.is_some() {
+        call = quote!(unsafe { #call });
+    }
+
+    quote! {
+        impl #interface for #rule {
+            #asyncness #unsafety fn #method #generics (#receiver, #(#names: #types),*) #output #where_clause {
+                #call
+            }
+        }
+    }
+}
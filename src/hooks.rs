@@ -0,0 +1,55 @@
+//! Generation of the hand-written `pest::Parser` impl calling `ParseHooks` (`hooked_parser`).
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Visibility;
+
+/// `pest`'s parser is generated for the hidden `inner` struct instead of `parser`, whose `pest::Parser` impl
+/// forwards to it between the hooks. So every parse through `parser` is hooked, including the ones of the
+/// generated helpers (`CompatParser`, `parse_tree()`, ...). `inner` is private to the module of the parser.
+pub(crate) fn hooked_parser_generator(
+    vis: &Visibility,
+    parser: &Ident,
+    inner: &Ident,
+) -> TokenStream {
+    let doc = format!(
+        " Hooks called around every parse of [`{parser}`], which must implement this trait."
+    );
+    quote! {
+        #[doc = #doc]
+        ///
+        /// `pre_parse` runs before `pest`, and its result is handed to `post_parse` together with the result
+        /// of the parse, e.g. an `Instant` to log the parse duration.
+        #vis trait ParseHooks {
+            /// what `pre_parse` hands to `post_parse`.
+            type State;
+
+            /// called before `input` is parsed with `rule`.
+            fn pre_parse(rule: Rule, input: &str) -> Self::State;
+
+            /// called with the result of parsing `input` with `rule`, before it is returned.
+            fn post_parse<'i>(
+                state: Self::State,
+                rule: Rule,
+                input: &'i str,
+                result: &::std::result::Result<::pest::iterators::Pairs<'i, Rule>, ::pest::error::Error<Rule>>,
+            );
+        }
+
+        /// The parser generated by `pest`, wrapped by the hooked parser.
+        #[doc(hidden)]
+        struct #inner;
+
+        impl ::pest::Parser<Rule> for #parser {
+            fn parse<'i>(
+                rule: Rule,
+                input: &'i str,
+            ) -> ::std::result::Result<::pest::iterators::Pairs<'i, Rule>, ::pest::error::Error<Rule>> {
+                let state = <#parser as ParseHooks>::pre_parse(rule, input);
+                let result = <#inner as ::pest::Parser<Rule>>::parse(rule, input);
+                <#parser as ParseHooks>::post_parse(state, rule, input, &result);
+                result
+            }
+        }
+    }
+}
@@ -0,0 +1,41 @@
+//! Generation of the `assert_impls` assertion, listing every rule struct without an impl of the interface.
+
+use crate::args::PestParserArgs;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+/// A missing impl is otherwise reported by the dispatch `enum_dispatch` generates, where the rules come and go
+/// with the errors of the other methods. The assertion calls a function bounded by the interface once per payload
+/// (like `thread_safe`), and each call is an obligation of its own, so `rustc` reports every payload without an
+/// impl in the same build rather than stopping at the first. The bound goes through a helper trait implemented
+/// for every implementor of the interface, whose `on_unimplemented` names the missing impl.
+pub(crate) fn assert_impls_generator(rules: &[Ident], args: &PestParserArgs) -> TokenStream {
+    assert!(
+        args.interface.is_some(),
+        "`assert_impls` needs `interface`, the trait whose impls it checks"
+    );
+    let interface = args.interface_path();
+    let name = quote!(#interface).to_string().replace(' ', "");
+    let message = format!("`{{Self}}` does not implement the interface `{name}`");
+    let label = format!("missing `impl {name} for {{Self}}`");
+    let payloads = args.payload_paths(rules);
+    quote! {
+        const _: () = {
+            #[diagnostic::on_unimplemented(
+                message = #message,
+                label = #label,
+                note = "`assert_impls` reports every rule struct of the parser without an impl of the interface"
+            )]
+            trait Implemented {}
+
+            impl<T: ?::core::marker::Sized + #interface> Implemented for T {}
+
+            fn implemented<T: ?::core::marker::Sized + Implemented>() {}
+
+            #[allow(dead_code)]
+            fn assert_impls() {
+                #(implemented::<#payloads>();)*
+            }
+        };
+    }
+}
@@ -0,0 +1,541 @@
+//! Generation of `enum RuleKind`, the fieldless mirror of the hooked `enum Rule`.
+
+use crate::args::{PestParserArgs, Strings};
+use crate::overrides::rules_slice;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{ext::IdentExt, Path, Visibility};
+
+/// `RuleKind` has exactly the same variants as `Rule`, but without the struct payloads, so it can be
+/// matched, compared and stored without caring about the dispatch structs.
+pub(crate) fn rule_kind_generator(rules: &[Ident], args: &PestParserArgs) -> TokenStream {
+    let kind_derives = &args.kind_derives;
+    let debug = (args.strings == Strings::Full).then(|| quote!(Debug,));
+    let to_kind = rules
+        .iter()
+        .map(|ident| quote! { Rule::#ident(_) => RuleKind::#ident });
+
+    let from_kind = rules.iter().map(|ident| {
+        let payload = args.payload(ident);
+        quote! { Rule::#ident(#payload) }
+    });
+
+    let iter = args.generates("iter").then(|| {
+        quote! {
+            /// Returns an iterator over all rule kinds, in definition order.
+            pub fn iter() -> impl Iterator<Item = RuleKind> {
+                RuleKind::all().iter().copied()
+            }
+        }
+    });
+
+    let count = rules.len();
+    // the table is a `static` built on first use once a payload cannot be built in a constant.
+    let from_kind = if args.overrides.is_empty() {
+        quote! {
+            const RULES: [Rule; #count] = [#(#from_kind),*];
+            RULES[kind.index()]
+        }
+    } else {
+        let rules = rules_slice(quote!(#(#from_kind),*), args);
+        quote! { (#rules)[kind.index()] }
+    };
+    // a table lookup instead of a `match`, which compiles to one arm per rule in debug builds.
+    let names = rules
+        .iter()
+        .map(|ident| ident.unraw().to_string())
+        .collect::<Vec<_>>();
+    let name = args.generates("name").then(|| {
+        // sorted while expanding, so looking a name up is a binary search instead of comparing every name.
+        let mut sorted = rules
+            .iter()
+            .map(|ident| (ident.unraw().to_string(), ident))
+            .collect::<Vec<_>>();
+        sorted.sort();
+        let (sorted_names, sorted_rules): (Vec<_>, Vec<_>) = sorted.into_iter().unzip();
+        quote! {
+            /// Returns the name of the rule as written in the grammar.
+            #[inline]
+            pub fn name(&self) -> &'static str {
+                RuleName::ALL[self.index()].0
+            }
+
+            /// Returns the rule named `name` as written in the grammar, in `O(log n)`.
+            pub fn from_name(name: &str) -> Option<RuleKind> {
+                const SORTED: [(&str, RuleKind); #count] = [#((#sorted_names, RuleKind::#sorted_rules)),*];
+                SORTED
+                    .binary_search_by(|(probe, _)| (*probe).cmp(name))
+                    .ok()
+                    .map(|index| SORTED[index].1)
+            }
+        }
+    });
+
+    // the lookups by name, which suggest a name from the name table. The types of the prelude are spelled out, a
+    // grammar may define rules named like them.
+    let from_str = args.generates("name").then(|| {
+        quote! {
+            impl UnknownRuleError {
+                /// Returns the error of looking up `input`, suggesting the rule name with the fewest edits from
+                /// it, if they are at most a third of its length (and at least one).
+                pub fn new(input: &str) -> Self {
+                    // the Levenshtein distance, with a single row of the table.
+                    fn distance(a: &str, b: &str) -> usize {
+                        let b = b.chars().collect::<::std::vec::Vec<_>>();
+                        let mut row = (0..=b.len()).collect::<::std::vec::Vec<_>>();
+                        for (i, a) in a.chars().enumerate() {
+                            let mut diagonal = row[0];
+                            row[0] = i + 1;
+                            for (j, b) in b.iter().enumerate() {
+                                let above = row[j + 1];
+                                row[j + 1] = if a == *b {
+                                    diagonal
+                                } else {
+                                    1 + diagonal.min(row[j]).min(above)
+                                };
+                                diagonal = above;
+                            }
+                        }
+                        row[b.len()]
+                    }
+
+                    let limit = input.chars().count().max(3) / 3;
+                    let suggestion = RuleKind::ALL
+                        .iter()
+                        .map(|kind| (distance(input, kind.name()), kind.name()))
+                        .filter(|(distance, _)| *distance <= limit)
+                        .min_by_key(|(distance, _)| *distance)
+                        .map(|(_, name)| name);
+                    Self {
+                        input: input.to_string(),
+                        suggestion,
+                    }
+                }
+            }
+
+            impl ::core::str::FromStr for RuleKind {
+                type Err = UnknownRuleError;
+
+                /// Returns the rule named `name` as written in the grammar, like [`RuleKind::from_name`].
+                fn from_str(name: &str) -> ::std::result::Result<Self, UnknownRuleError> {
+                    RuleKind::from_name(name).ok_or_else(|| UnknownRuleError::new(name))
+                }
+            }
+
+            impl ::core::str::FromStr for Rule {
+                type Err = UnknownRuleError;
+
+                /// Returns the rule named `name` as written in the grammar.
+                fn from_str(name: &str) -> ::std::result::Result<Self, UnknownRuleError> {
+                    name.parse::<RuleKind>().map(Rule::from)
+                }
+            }
+        }
+    });
+
+    // the names as a type of their own, which looks up by `&str` in maps keyed by it.
+    let rule_name = args.generates("name").then(|| {
+        quote! {
+            /// The name of a rule as written in the grammar, e.g. the key of a map from rule names, which can be
+            /// looked up by a `&str` (it borrows as one) without allocating.
+            #[derive(Clone, Copy, Debug, Eq, Hash, Ord, PartialEq, PartialOrd)]
+            pub struct RuleName(&'static str);
+
+            impl RuleName {
+                /// The names of all rules, in definition order.
+                pub const ALL: [RuleName; #count] = [#(RuleName(#names)),*];
+
+                /// Returns the name as a string.
+                #[inline]
+                pub const fn as_str(self) -> &'static str {
+                    self.0
+                }
+            }
+
+            impl ::core::convert::From<RuleKind> for RuleName {
+                #[inline]
+                fn from(kind: RuleKind) -> Self {
+                    RuleName::ALL[kind.index()]
+                }
+            }
+
+            impl ::core::ops::Deref for RuleName {
+                type Target = ::core::primitive::str;
+
+                #[inline]
+                fn deref(&self) -> &::core::primitive::str {
+                    self.0
+                }
+            }
+
+            impl ::core::borrow::Borrow<::core::primitive::str> for RuleName {
+                #[inline]
+                fn borrow(&self) -> &::core::primitive::str {
+                    self.0
+                }
+            }
+
+            impl ::core::convert::AsRef<::core::primitive::str> for RuleName {
+                #[inline]
+                fn as_ref(&self) -> &::core::primitive::str {
+                    self.0
+                }
+            }
+
+            impl ::core::fmt::Display for RuleName {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    f.write_str(self.0)
+                }
+            }
+        }
+    });
+    // numbering the rules for FFI, by their discriminants, which a `#[repr(u32)]` on `enum Rule` fixes.
+    let ffi = args.generates("ffi").then(|| {
+        quote! {
+            impl ::core::convert::From<RuleKind> for ::core::primitive::u32 {
+                #[inline]
+                fn from(kind: RuleKind) -> ::core::primitive::u32 {
+                    kind.to_u32()
+                }
+            }
+
+            impl ::core::convert::From<Rule> for ::core::primitive::u32 {
+                #[inline]
+                fn from(rule: Rule) -> ::core::primitive::u32 {
+                    rule.kind().to_u32()
+                }
+            }
+
+            impl ::core::convert::TryFrom<::core::primitive::u32> for RuleKind {
+                type Error = UnknownRuleError;
+
+                /// Returns the rule numbered `id`, like [`RuleKind::from_u32`].
+                fn try_from(id: ::core::primitive::u32) -> ::std::result::Result<Self, UnknownRuleError> {
+                    RuleKind::from_u32(id).ok_or_else(|| UnknownRuleError {
+                        input: id.to_string(),
+                        suggestion: ::core::option::Option::None,
+                    })
+                }
+            }
+
+            impl ::core::convert::TryFrom<::core::primitive::u32> for Rule {
+                type Error = UnknownRuleError;
+
+                /// Returns the rule numbered `id`.
+                fn try_from(id: ::core::primitive::u32) -> ::std::result::Result<Self, UnknownRuleError> {
+                    RuleKind::try_from(id).map(Rule::from)
+                }
+            }
+        }
+    });
+    let to_u32 = args.generates("ffi").then(|| {
+        quote! {
+            /// Returns the number of the rule, its position in definition order, which is also the discriminant
+            /// of its variant in `Rule` and in `RuleKind`.
+            #[inline]
+            pub const fn to_u32(self) -> ::core::primitive::u32 {
+                self as ::core::primitive::u32
+            }
+
+            /// Returns the rule numbered `id`, i.e. the one whose [`RuleKind::to_u32`] is `id`.
+            #[inline]
+            pub fn from_u32(id: ::core::primitive::u32) -> ::core::option::Option<RuleKind> {
+                RuleKind::ALL.get(id as ::core::primitive::usize).copied()
+            }
+        }
+    });
+    // the error of the fallible lookups, by name or by number.
+    let unknown_rule = (args.generates("name") || args.generates("ffi")).then(|| {
+        quote! {
+            /// The error of looking up a rule which is not one of the grammar, by a name (e.g. with
+            /// `"Statment".parse::<RuleKind>()`) or by a number.
+            #[derive(Clone, Debug, Eq, PartialEq)]
+            pub struct UnknownRuleError {
+                /// the name (or number) looked up.
+                pub input: ::std::string::String,
+                /// the rule name closest to `input`, if one is close enough to be a likely typo.
+                pub suggestion: ::core::option::Option<&'static str>,
+            }
+
+            impl ::core::fmt::Display for UnknownRuleError {
+                fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                    write!(f, "unknown rule `{}`", self.input)?;
+                    if let ::core::option::Option::Some(suggestion) = self.suggestion {
+                        write!(f, "; did you mean `{suggestion}`?")?;
+                    }
+                    ::core::result::Result::Ok(())
+                }
+            }
+
+            impl ::std::error::Error for UnknownRuleError {}
+
+            #from_str
+
+            #ffi
+        }
+    });
+
+    // the walkers check every pair, so the check is a `match` on a constant list, `false` without any.
+    let transparent = args.transparent_rules.iter().map(|rule| {
+        rules
+            .iter()
+            .find(|ident| ident.unraw() == rule)
+            .expect("`transparent_rules` is checked by `PestParserArgs::check_rule_names`")
+    });
+    let is_transparent = if args.transparent_rules.is_empty() {
+        quote!(false)
+    } else {
+        quote!(::core::matches!(self, #(RuleKind::#transparent)|*))
+    };
+
+    let arbitrary = cfg!(feature = "arbitrary").then(|| {
+        quote! {
+            impl ::quickcheck::Arbitrary for RuleKind {
+                fn arbitrary(g: &mut ::quickcheck::Gen) -> Self {
+                    *g.choose(&RuleKind::ALL).unwrap()
+                }
+
+                fn shrink(&self) -> Box<dyn Iterator<Item = Self>> {
+                    Box::new(RuleKind::ALL[..self.index()].iter().copied())
+                }
+            }
+        }
+    });
+
+    quote! {
+        /// Fieldless mirror of `enum Rule`, one variant per grammar rule.
+        #[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
+        #[derive(Clone, Copy, #debug Eq, Hash, Ord, PartialEq, PartialOrd, #(#kind_derives),*)]
+        pub enum RuleKind {
+            #(#rules),*
+        }
+
+        impl RuleKind {
+            /// All rule kinds, in definition order.
+            pub const ALL: [RuleKind; #count] = [#(RuleKind::#rules),*];
+
+            /// Returns all rule kinds, in definition order, which is also their `Ord` order.
+            #[inline]
+            pub fn all() -> &'static [RuleKind] {
+                &RuleKind::ALL
+            }
+
+            /// Returns the position of the rule in definition order, i.e. its index in [`RuleKind::ALL`].
+            #[inline]
+            pub const fn index(self) -> usize {
+                self as usize
+            }
+
+            /// Returns whether the walkers (`walk_into()`, `walk_until()`, `dispatch_with_context()` and
+            /// `walk_with_stack()`) splice the pairs of the rule out, visiting their children in their place, see
+            /// `transparent_rules`.
+            #[inline]
+            pub const fn is_transparent(self) -> bool {
+                #is_transparent
+            }
+
+            #iter
+            #name
+            #to_u32
+        }
+
+        impl Rule {
+            /// Returns the fieldless kind of this rule.
+            #[inline]
+            pub fn kind(&self) -> RuleKind {
+                match self {
+                    #(#to_kind),*
+                }
+            }
+        }
+
+        #rule_name
+
+        #unknown_rule
+
+        impl From<RuleKind> for Rule {
+            #[inline]
+            fn from(kind: RuleKind) -> Self {
+                #from_kind
+            }
+        }
+
+        impl From<Rule> for RuleKind {
+            /// Same as [`Rule::kind`].
+            #[inline]
+            // not `rule`, which a grammar may define, shadowing the binding with its unit struct.
+            fn from(dispatched: Rule) -> Self {
+                dispatched.kind()
+            }
+        }
+
+        // every variant carries a single payload value, so comparing the kinds is the same as comparing the
+        // rules, without a `match` over the payloads per trait.
+        impl PartialEq for Rule {
+            #[inline]
+            fn eq(&self, other: &Rule) -> ::core::primitive::bool {
+                self.kind() == other.kind()
+            }
+        }
+
+        impl Eq for Rule {}
+
+        impl PartialOrd for Rule {
+            #[inline]
+            fn partial_cmp(&self, other: &Rule) -> ::core::option::Option<::core::cmp::Ordering> {
+                ::core::option::Option::Some(self.cmp(other))
+            }
+        }
+
+        impl Ord for Rule {
+            #[inline]
+            fn cmp(&self, other: &Rule) -> ::core::cmp::Ordering {
+                self.kind().cmp(&other.kind())
+            }
+        }
+
+        impl ::core::hash::Hash for Rule {
+            #[inline]
+            fn hash<H: ::core::hash::Hasher>(&self, state: &mut H) {
+                self.kind().hash(state);
+            }
+        }
+
+        // grammars may define a `bool` rule, whose struct shadows the primitive type.
+        impl PartialEq<RuleKind> for Rule {
+            #[inline]
+            fn eq(&self, other: &RuleKind) -> ::core::primitive::bool {
+                self.kind() == *other
+            }
+        }
+
+        impl PartialEq<Rule> for RuleKind {
+            #[inline]
+            fn eq(&self, other: &Rule) -> ::core::primitive::bool {
+                *self == other.kind()
+            }
+        }
+
+        #arbitrary
+    }
+}
+
+/// `RuleKind::parse()` goes through `parser` rather than `pest`'s generated impl, so the hooks of a `hooked_parser`
+/// see these parses too.
+pub(crate) fn rule_kind_parse_generator(
+    vis: &Visibility,
+    parser: &Ident,
+    error: Option<&Path>,
+) -> TokenStream {
+    let (error, convert) = parse_error(error);
+    quote! {
+        impl RuleKind {
+            /// Parses `input` with the rule of this kind, e.g. `RuleKind::Statement.parse(input)`.
+            #vis fn parse(
+                self,
+                input: &str,
+            ) -> ::std::result::Result<::pest::iterators::Pairs<'_, Rule>, #error> {
+                <#parser as ::pest::Parser<Rule>>::parse(Rule::from(self), input)#convert
+            }
+        }
+    }
+}
+
+/// returns the error type of the parsing helpers, `pest`'s or the `error_type`, and the conversion appended to a
+/// parse into it, if any.
+fn parse_error(error: Option<&Path>) -> (TokenStream, Option<TokenStream>) {
+    match error {
+        Some(error) => (
+            quote!(#error),
+            Some(
+                quote!(.map_err(<#error as ::core::convert::From<::pest::error::Error<Rule>>>::from)),
+            ),
+        ),
+        None => (quote!(::pest::error::Error<Rule>), None),
+    }
+}
+
+/// The conversion in the parsing helpers would report a missing `From` at every helper, in terms of the generated
+/// code. The assertion bounds the `error_type` once through a helper trait whose `on_unimplemented` names the
+/// argument and the impl it needs, like the one of `assert_impls`.
+pub(crate) fn parse_error_assertion_generator(error: &Path) -> TokenStream {
+    let name = quote!(#error).to_string().replace(' ', "");
+    let message = format!("the `error_type` `{name}` cannot be built from the errors of `pest`");
+    let label = format!("missing `impl From<pest::error::Error<Rule>> for {name}`");
+    quote! {
+        const _: () = {
+            #[diagnostic::on_unimplemented(
+                message = #message,
+                label = #label,
+                note = "`RuleKind::parse()` and `parse_rule()` convert the errors of `pest` into the `error_type`"
+            )]
+            trait FromParseError {}
+
+            impl<T: ::core::convert::From<::pest::error::Error<Rule>>> FromParseError for T {}
+
+            fn from_parse_error<T: FromParseError>() {}
+
+            #[allow(dead_code)]
+            fn assert_error_type() {
+                from_parse_error::<#error>();
+            }
+        };
+    }
+}
+
+/// `RuleMarker` names the rule of a rule struct at the type level, so `ParserExt::parse_rule::<Statement>(input)`
+/// picks the start rule by type instead of by a `Rule` value. It extends the sealed `RuleStruct`, so it cannot be
+/// implemented elsewhere either. A group struct stands for several rules and does not implement it.
+pub(crate) fn parse_rule_generator(
+    vis: &Visibility,
+    parser: &Ident,
+    rules: &[Ident],
+    args: &PestParserArgs,
+) -> TokenStream {
+    let markers = rules
+        .iter()
+        .filter(|ident| args.group_of(&ident.unraw().to_string()).is_none())
+        .filter(|ident| args.override_of(&ident.to_string()).is_none())
+        .map(|ident| {
+            let ty = args
+                .payload_type(&ident.to_string())
+                .parse::<TokenStream>()
+                .expect("illegal payload type");
+            let payload = args.payload(ident);
+            quote! {
+                impl RuleMarker for #ty {
+                    #[inline]
+                    fn rule() -> Rule {
+                        Rule::#ident(#payload)
+                    }
+                }
+            }
+        });
+    let (error, convert) = parse_error(args.error_type.as_ref());
+    quote! {
+        /// Implemented by the struct of every rule, naming its rule for [`ParserExt::parse_rule`].
+        #vis trait RuleMarker: RuleStruct {
+            /// the rule of this struct.
+            fn rule() -> Rule;
+        }
+
+        #(#markers)*
+
+        /// Parsing with a start rule chosen by type, e.g. `LanguageParser::parse_rule::<Statement>(input)`.
+        #vis trait ParserExt {
+            /// Parses `input` with the rule of `R`.
+            fn parse_rule<R: RuleMarker>(
+                input: &str,
+            ) -> ::std::result::Result<::pest::iterators::Pairs<'_, Rule>, #error>;
+        }
+
+        impl ParserExt for #parser {
+            #[inline]
+            fn parse_rule<R: RuleMarker>(
+                input: &str,
+            ) -> ::std::result::Result<::pest::iterators::Pairs<'_, Rule>, #error> {
+                <#parser as ::pest::Parser<Rule>>::parse(R::rule(), input)#convert
+            }
+        }
+    }
+}
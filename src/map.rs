@@ -0,0 +1,37 @@
+//! Generation of `RULE_MAP`, a compile-time perfect hash map from rule names to rules.
+
+use crate::args::PestParserArgs;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::ext::IdentExt;
+
+/// The map is built by `phf_codegen` while expanding the macro, so looking up a name never hashes
+/// into a runtime `HashMap`. Values are constructors rather than `Rule`s, since `enum Rule` is
+/// only `Copy` as long as every payload is.
+pub(crate) fn rule_map_generator(rules: &[Ident], args: &PestParserArgs) -> TokenStream {
+    let names = rules
+        .iter()
+        .map(|ident| ident.unraw().to_string())
+        .collect::<Vec<_>>();
+    let mut map = phf_codegen::Map::<&str>::new();
+    map.phf_path("::phf");
+    for (name, ident) in names.iter().zip(rules) {
+        map.entry(
+            name,
+            &format!(
+                "|| Rule::{ident}({})",
+                args.payload_value(&ident.to_string())
+            ),
+        );
+    }
+    let map = map
+        .build()
+        .to_string()
+        .parse::<TokenStream>()
+        .expect("cannot parse `phf_codegen`'s generated map.");
+
+    quote! {
+        /// Constructors of all rules (including `EOI`), keyed by the rule name as written in the grammar.
+        pub static RULE_MAP: ::phf::Map<&'static str, fn() -> Rule> = #map;
+    }
+}
@@ -0,0 +1,411 @@
+//! Generation of `RULE_META`, `Rule::children_of()`, `Rule::sync_points()`, `Rule::first_literals()` and
+//! `TerminalRule`, the grammar introspection helpers.
+
+use crate::args::{PestParserArgs, Strings};
+use crate::grammar::GrammarContext;
+use pest_meta::ast::{Expr, Rule, RuleType};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{ext::IdentExt, ItemEnum};
+
+/// The rules are taken from the `pest_meta` parse of the grammar, since `pest`'s generated code does not
+/// keep the modifiers (nor the rule bodies) in a form which can be read back. Only grammar rules are
+/// listed, `EOI` has no modifier.
+///
+/// `children_of()` is indexed like `RuleKind::ALL`, so `rule_enum` is the enum `pest` generated for the
+/// same grammar. `RULE_META` holds the rule names, it is omitted with `strings = "minimal"`.
+pub(crate) fn rule_meta_generator(
+    grammar: &GrammarContext,
+    rule_enum: &ItemEnum,
+    args: &PestParserArgs,
+) -> TokenStream {
+    let rules = &grammar.parsed().rules;
+
+    let meta = (args.strings == Strings::Full).then(|| {
+        let meta = rules.iter().map(|rule| {
+            let name = &rule.name;
+            let modifier = match rule.ty {
+                RuleType::Normal => "normal",
+                RuleType::Silent => "silent",
+                RuleType::Atomic => "atomic",
+                RuleType::CompoundAtomic => "compound_atomic",
+                RuleType::NonAtomic => "non_atomic",
+            };
+            quote! { (#name, #modifier) }
+        });
+        quote! {
+            /// The modifier of every grammar rule, in definition order: `"normal"`, `"silent"` (`_`), `"atomic"` (`@`),
+            /// `"compound_atomic"` (`$`) or `"non_atomic"` (`!`).
+            pub static RULE_META: &[(&str, &str)] = &[#(#meta),*];
+        }
+    });
+
+    let kinds = rule_enum
+        .variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+    let children = kinds.iter().map(|kind| {
+        let children = rules
+            .iter()
+            .find(|rule| kind.unraw() == rule.name)
+            .map(|rule| referenced_rules(&rule.expr, &kinds))
+            .unwrap_or_default();
+        quote! { &[#(RuleKind::#children),*] }
+    });
+    let count = kinds.len();
+    let sync_points = match &args.sync_points {
+        Some(names) => names
+            .iter()
+            .map(|name| {
+                *kinds
+                    .iter()
+                    .find(|kind| kind.unraw() == name)
+                    .unwrap_or_else(|| panic!("unknown rule `{name}` in `sync_points`"))
+            })
+            .collect(),
+        None => sync_points(rules, &kinds),
+    };
+    let first = first_sets(rules);
+    let (first_literals, first_complete): (Vec<_>, Vec<_>) = kinds
+        .iter()
+        .map(|kind| {
+            let start = first.iter().find(|(name, _)| kind.unraw() == name);
+            match start {
+                Some((_, start)) => (start.literals.clone(), start.complete && !start.nullable),
+                // `EOI` matches the end of the input, which no literal starts.
+                None => (Vec::new(), false),
+            }
+        })
+        .unzip();
+    let terminals = terminal_rules(rules, &kinds);
+
+    quote! {
+        #meta
+
+        /// The terminal rules, whose pairs never have inner pairs (e.g. identifiers, numbers, strings), in definition
+        /// order: the atomic (`@`) rules, `EOI`, and the rules which only reference silent rules without pairs of
+        /// their own, built-in rules and rules under predicates. Silent rules have no pairs and are not listed.
+        ///
+        /// A non-silent `WHITESPACE` or `COMMENT` is not taken into account: its pairs, matched between the tokens
+        /// of a rule, are inner pairs of rules classified as terminal.
+        #[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
+        #[derive(Clone, Copy, Eq, Hash, Ord, PartialEq, PartialOrd)]
+        pub enum TerminalRule {
+            #(#terminals,)*
+        }
+
+        impl ::core::fmt::Debug for TerminalRule {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                ::core::fmt::Debug::fmt(&RuleKind::from(*self), f)
+            }
+        }
+
+        impl ::core::convert::From<TerminalRule> for RuleKind {
+            fn from(terminal: TerminalRule) -> Self {
+                match terminal {
+                    #(TerminalRule::#terminals => RuleKind::#terminals,)*
+                }
+            }
+        }
+
+        impl ::core::convert::TryFrom<RuleKind> for TerminalRule {
+            /// the kind of a rule which is not terminal.
+            type Error = RuleKind;
+
+            fn try_from(kind: RuleKind) -> ::core::result::Result<Self, RuleKind> {
+                match kind {
+                    #(RuleKind::#terminals => ::core::result::Result::Ok(TerminalRule::#terminals),)*
+                    #[allow(unreachable_patterns)]
+                    kind => ::core::result::Result::Err(kind),
+                }
+            }
+        }
+
+        impl RuleKind {
+            /// Returns the terminal rule of this kind, `None` for a rule with inner pairs or a silent rule, see
+            /// [`TerminalRule`].
+            pub fn as_terminal(&self) -> ::core::option::Option<TerminalRule> {
+                <TerminalRule as ::core::convert::TryFrom<RuleKind>>::try_from(*self).ok()
+            }
+        }
+
+        impl Rule {
+            /// Returns the rules referenced in the body of the rule `kind`, in order of first reference.
+            ///
+            /// This is a syntactic relation: every reference counts, also one under a predicate (`&a`, `!a`) or in
+            /// a branch which can never match, and silent rules are listed rather than looked through. Built-in
+            /// rules (e.g. `ASCII_DIGIT`) do not appear, `EOI` only where it is written.
+            pub fn children_of(kind: RuleKind) -> &'static [RuleKind] {
+                const CHILDREN: [&[RuleKind]; #count] = [#(#children),*];
+                CHILDREN[kind.index()]
+            }
+
+            /// Returns the rules to resynchronize on after an error, e.g. to skip to the next statement, in
+            /// definition order.
+            ///
+            /// Unless set with `sync_points`, these are the rules repeated in a body, i.e. the items of lists
+            /// (`Statement` in `Statement*`, `Argument` in `("," ~ Argument)*`), a heuristic: a failed item can be
+            /// skipped while its siblings are still parsed. Silent rules are left out, they have no pairs.
+            pub fn sync_points() -> &'static [RuleKind] {
+                &[#(RuleKind::#sync_points),*]
+            }
+
+            /// Returns the string literals a match of this rule can start with, e.g. to suggest what can come next,
+            /// in order of appearance in the grammar.
+            ///
+            /// They are looked up through the rules referenced at the start, and past what can match nothing
+            /// (`a?`, `a*`, predicates). What is not a literal is left out: character classes, ranges and
+            /// built-in rules (`ASCII_DIGIT`, `ANY`), and what the stack matches (`PEEK`, `POP`). Case-insensitive
+            /// literals (`^"select"`) are listed as written. See [`Rule::first_literals_complete`].
+            pub fn first_literals(&self) -> &'static [&'static str] {
+                const FIRST: [&[&str]; #count] = [#(&[#(#first_literals),*]),*];
+                FIRST[self.kind().index()]
+            }
+
+            /// Returns whether every match of this rule starts with one of [`Rule::first_literals`], i.e. nothing
+            /// else can start it and it cannot match nothing, in which case what follows it could start the match.
+            pub fn first_literals_complete(&self) -> ::core::primitive::bool {
+                const COMPLETE: [::core::primitive::bool; #count] = [#(#first_complete),*];
+                COMPLETE[self.kind().index()]
+            }
+        }
+    }
+}
+
+/// returns the variants of `enum Rule` referenced by `expr`, deduplicated in order of first reference.
+fn referenced_rules<'k>(expr: &Expr, kinds: &[&'k syn::Ident]) -> Vec<&'k syn::Ident> {
+    let mut referenced = Vec::new();
+    for expr in expr.iter_top_down() {
+        if let Expr::Ident(name) = expr {
+            if let Some(kind) = kinds.iter().find(|kind| kind.unraw() == name) {
+                if !referenced.contains(kind) {
+                    referenced.push(*kind);
+                }
+            }
+        }
+    }
+    referenced
+}
+
+/// returns the variants of `enum Rule` referenced in the body of a repetition of some rule, in definition order.
+///
+/// Predicates only look ahead, and the rules within an atomic rule produce no pairs, so neither is searched.
+fn sync_points<'k>(rules: &[Rule], kinds: &[&'k syn::Ident]) -> Vec<&'k syn::Ident> {
+    let mut repeated = Vec::new();
+    for rule in rules.iter().filter(|rule| rule.ty != RuleType::Atomic) {
+        for expr in rule.expr.iter_top_down() {
+            if let Expr::Rep(body)
+            | Expr::RepOnce(body)
+            | Expr::RepExact(body, _)
+            | Expr::RepMin(body, _)
+            | Expr::RepMax(body, _)
+            | Expr::RepMinMax(body, _, _) = expr
+            {
+                repeated_rules(&body, &mut repeated);
+            }
+        }
+    }
+    kinds
+        .iter()
+        .filter(|kind| {
+            rules.iter().any(|rule| {
+                kind.unraw() == rule.name
+                    && rule.ty != RuleType::Silent
+                    && repeated.contains(&rule.name)
+            })
+        })
+        .copied()
+        .collect()
+}
+
+/// collects the rules referenced by `expr` outside of predicates into `repeated`, the ones which can produce pairs.
+fn repeated_rules(expr: &Expr, repeated: &mut Vec<String>) {
+    match expr {
+        Expr::Ident(name) => repeated.push(name.clone()),
+        Expr::Seq(left, right) | Expr::Choice(left, right) => {
+            repeated_rules(left, repeated);
+            repeated_rules(right, repeated);
+        }
+        Expr::Opt(body)
+        | Expr::Rep(body)
+        | Expr::RepOnce(body)
+        | Expr::RepExact(body, _)
+        | Expr::RepMin(body, _)
+        | Expr::RepMax(body, _)
+        | Expr::RepMinMax(body, _, _)
+        | Expr::Push(body) => repeated_rules(body, repeated),
+        _ => {}
+    }
+}
+
+/// returns the variants of `enum Rule` whose pairs have no inner pairs, in definition order.
+///
+/// The rules called by an atomic rule produce no pairs, so it is terminal whatever it references. Otherwise, the
+/// rules referenced outside of predicates (whose pairs are dropped) are looked at: a silent one has no pair, and
+/// is looked through (once, it may be recursive), `EOI` has one, and a built-in rule has none.
+fn terminal_rules<'k>(rules: &[Rule], kinds: &[&'k syn::Ident]) -> Vec<&'k syn::Ident> {
+    kinds
+        .iter()
+        .filter(
+            |kind| match rules.iter().find(|rule| kind.unraw() == rule.name) {
+                Some(rule) => match rule.ty {
+                    RuleType::Atomic => true,
+                    RuleType::Silent => false,
+                    _ => !has_pairs(&rule.expr, rules, &mut Vec::new()),
+                },
+                // `EOI` matches the end of the input, its pair is empty.
+                None => true,
+            },
+        )
+        .copied()
+        .collect()
+}
+
+/// returns whether a match of `expr` can produce pairs, looking through the silent rules not in `visited` yet.
+fn has_pairs<'r>(expr: &Expr, rules: &'r [Rule], visited: &mut Vec<&'r str>) -> bool {
+    let mut referenced = Vec::new();
+    repeated_rules(expr, &mut referenced);
+    referenced
+        .iter()
+        .any(|name| match rules.iter().find(|rule| rule.name == *name) {
+            Some(rule) if rule.ty == RuleType::Silent => {
+                if visited.contains(&rule.name.as_str()) {
+                    return false;
+                }
+                visited.push(&rule.name);
+                has_pairs(&rule.expr, rules, visited)
+            }
+            Some(_) => true,
+            None => name == "EOI",
+        })
+}
+
+/// What a match of an expression can start with.
+#[derive(Clone, Debug, Default, PartialEq)]
+struct Start {
+    /// the string literals, deduplicated in order of appearance.
+    literals: Vec<String>,
+    /// whether the expression can match nothing, so what follows it starts the match as well.
+    nullable: bool,
+    /// whether every non-empty match starts with one of `literals`.
+    complete: bool,
+}
+
+impl Start {
+    fn literal(literal: &str) -> Self {
+        Self {
+            literals: vec![literal.to_string()],
+            nullable: literal.is_empty(),
+            complete: true,
+        }
+    }
+
+    /// an expression matching nothing, e.g. a predicate.
+    fn empty() -> Self {
+        Self {
+            literals: Vec::new(),
+            nullable: true,
+            complete: true,
+        }
+    }
+
+    /// an expression starting with something else than a literal, e.g. a character class.
+    fn unknown(nullable: bool) -> Self {
+        Self {
+            literals: Vec::new(),
+            nullable,
+            complete: false,
+        }
+    }
+
+    fn extend(&mut self, other: &Start) {
+        for literal in &other.literals {
+            if !self.literals.contains(literal) {
+                self.literals.push(literal.clone());
+            }
+        }
+        self.complete &= other.complete;
+    }
+}
+
+/// returns the FIRST set of every grammar rule, by name.
+///
+/// The sets are computed by a fixpoint over the rule references: starting from empty sets, the rules are
+/// recomputed from the sets of the rules they reference until none changes. The sets only grow (and the flags
+/// only go one way), so it ends; a left recursion, which `pest` rejects anyway, only contributes the other
+/// alternatives.
+fn first_sets(rules: &[Rule]) -> Vec<(String, Start)> {
+    let mut first = rules
+        .iter()
+        .map(|rule| {
+            let start = Start {
+                complete: true,
+                ..Start::default()
+            };
+            (rule.name.clone(), start)
+        })
+        .collect::<Vec<_>>();
+    loop {
+        let mut changed = false;
+        for (index, rule) in rules.iter().enumerate() {
+            let start = first_of(&rule.expr, &first);
+            if start != first[index].1 {
+                first[index].1 = start;
+                changed = true;
+            }
+        }
+        if !changed {
+            return first;
+        }
+    }
+}
+
+/// returns what `expr` can start with, given the current sets of the rules.
+fn first_of(expr: &Expr, rules: &[(String, Start)]) -> Start {
+    match expr {
+        Expr::Str(literal) | Expr::Insens(literal) => Start::literal(literal),
+        Expr::Range(..) => Start::unknown(false),
+        Expr::Ident(name) => match rules.iter().find(|(rule, _)| rule == name) {
+            Some((_, start)) => start.clone(),
+            None => match name.as_str() {
+                "SOI" | "EOI" | "DROP" => Start::empty(),
+                "PEEK" | "PEEK_ALL" | "POP" | "POP_ALL" => Start::unknown(true),
+                _ => Start::unknown(false),
+            },
+        },
+        Expr::PeekSlice(..) | Expr::Skip(_) => Start::unknown(true),
+        Expr::PosPred(_) | Expr::NegPred(_) => Start::empty(),
+        Expr::Seq(left, right) => {
+            let mut start = first_of(left, rules);
+            if start.nullable {
+                let right = first_of(right, rules);
+                start.extend(&right);
+                start.nullable = right.nullable;
+            }
+            start
+        }
+        Expr::Choice(left, right) => {
+            let mut start = first_of(left, rules);
+            let right = first_of(right, rules);
+            start.extend(&right);
+            start.nullable |= right.nullable;
+            start
+        }
+        Expr::Opt(body) | Expr::Rep(body) | Expr::RepMax(body, _) => Start {
+            nullable: true,
+            ..first_of(body, rules)
+        },
+        Expr::RepOnce(body) | Expr::Push(body) => first_of(body, rules),
+        Expr::RepExact(body, min) | Expr::RepMin(body, min) | Expr::RepMinMax(body, min, _) => {
+            let start = first_of(body, rules);
+            Start {
+                nullable: start.nullable || *min == 0,
+                ..start
+            }
+        }
+        // `NodeTag` (with the `grammar-extras` feature of `pest_meta`) labels an expression.
+        #[allow(unreachable_patterns)]
+        _ => Start::unknown(true),
+    }
+}
@@ -0,0 +1,246 @@
+//! Generation of `output_enum`, dispatching an interface whose rule structs return outputs of their own types.
+
+use crate::args::PestParserArgs;
+use proc_macro2::TokenStream;
+use quote::{format_ident, quote};
+use syn::{
+    bracketed,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    FnArg, Ident, ItemTrait, ReturnType, Token, TraitItem, Type,
+};
+
+/// `enum_dispatch` cannot dispatch an interface with an associated type, whose value differs between the rule
+/// structs, and `#[pest_dispatch]` does not know the rules to write a `match` of its own. The generated
+/// `rule_output!` bakes in the rules and their payloads, in definition order, and forwards the trait it is given
+/// by `#[pest_dispatch(output_enum = "...")]` to the hidden `__rule_output!` of this crate, which writes the enum
+/// of the outputs and the `match` dispatching the trait.
+///
+/// Like `match_pairs!`, it is re-exported with `pub(crate) use`, so it is in scope of the trait wherever `Rule` is.
+pub(crate) fn rule_output_generator(rules: &[Ident], args: &PestParserArgs) -> TokenStream {
+    let payloads = rules.iter().map(|rule| {
+        syn::parse_str::<Type>(&args.payload_type(&rule.to_string())).expect("illegal payload type")
+    });
+    quote! {
+        /// Writes the enum of the outputs of a trait and the methods of `Rule` dispatching it, see
+        /// `#[pest_dispatch(output_enum = "...")]`.
+        #[allow(unused_macros)]
+        macro_rules! __rule_output_rules {
+            ($($input:tt)*) => {
+                ::enum_dispatch_pest_parser::__rule_output! { [#(#rules: #payloads),*] $($input)* }
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use __rule_output_rules as rule_output;
+    }
+}
+
+/// The input of `__rule_output!`: the rules with their payloads baked in by the generated `rule_output!`, then
+/// the name of the enum and the trait given by `#[pest_dispatch]`.
+pub(crate) struct RuleOutputInput {
+    rules: Vec<(Ident, Type)>,
+    output_enum: Ident,
+    item: ItemTrait,
+}
+
+impl Parse for RuleOutputInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let content;
+        bracketed!(content in input);
+        let mut rules = Vec::new();
+        while !content.is_empty() {
+            let rule = content.call(Ident::parse_any)?;
+            content.parse::<Token![:]>()?;
+            rules.push((rule, content.parse()?));
+            if !content.is_empty() {
+                content.parse::<Token![,]>()?;
+            }
+        }
+        let output_enum = input.parse()?;
+        input.parse::<Token![;]>()?;
+        Ok(Self {
+            rules,
+            output_enum,
+            item: input.parse()?,
+        })
+    }
+}
+
+/// Writes `enum RuleOutput` with a variant per rule holding the output of its rule struct, and, per method of
+/// the trait, an inherent method of `Rule` calling the one of the rule struct and wrapping what it returns.
+pub(crate) fn rule_output_expand(input: RuleOutputInput) -> TokenStream {
+    let RuleOutputInput {
+        rules,
+        output_enum,
+        item,
+    } = input;
+    let (vis, interface) = (&item.vis, &item.ident);
+    let output = item
+        .items
+        .iter()
+        .find_map(|item| match item {
+            TraitItem::Type(output) => Some(&output.ident),
+            _ => None,
+        })
+        .expect("the interface of `output_enum` has an associated type");
+
+    let variants = rules.iter().map(|(rule, payload)| {
+        let doc = format!(
+            " the output of `{}`.",
+            rule.to_string().trim_start_matches("r#")
+        );
+        quote! {
+            #[doc = #doc]
+            #rule(<#payload as #interface>::#output)
+        }
+    });
+    let kinds = rules
+        .iter()
+        .map(|(rule, _)| quote!(Self::#rule(_) => RuleKind::#rule));
+    let methods = item.items.iter().filter_map(|item| match item {
+        TraitItem::Fn(method) => Some(method),
+        _ => None,
+    });
+    let methods = methods.map(|method| {
+        let sig = &method.sig;
+        let mut inputs = sig.inputs.iter();
+        let receiver = match inputs.next() {
+            Some(FnArg::Receiver(receiver)) => receiver,
+            _ => unreachable!("the methods of the interface are checked to take a receiver."),
+        };
+        // `self` is hygienic, the one of the `match` must come from the same tokens as the receiver.
+        let this = &receiver.self_token;
+        let (names, types): (Vec<_>, Vec<_>) = inputs
+            .enumerate()
+            .map(|(index, input)| match input {
+                FnArg::Typed(input) => (format_ident!("arg{index}"), &input.ty),
+                FnArg::Receiver(_) => unreachable!("only the first argument can be a receiver."),
+            })
+            .unzip();
+        let (asyncness, unsafety, ident, generics) =
+            (&sig.asyncness, &sig.unsafety, &sig.ident, &sig.generics);
+        let where_clause = &generics.where_clause;
+        let arms = rules.iter().map(|(rule, _)| {
+            let mut call = quote!(#interface::#ident(rule, #(#names),*));
+            if asyncness.is_some() {
+                call = quote!(#call.await);
+            }
+            quote!(Rule::#rule(rule) => #output_enum::#rule(#call))
+        });
+        let doc = format!(
+            " Calls `{interface}::{ident}` of the rule struct of `self`, and wraps its output in [`{output_enum}`]."
+        );
+        quote! {
+            #[doc = #doc]
+            #vis #asyncness #unsafety fn #ident #generics (#receiver, #(#names: #types),*) -> #output_enum
+            #where_clause
+            {
+                match #this {
+                    #(#arms,)*
+                }
+            }
+        }
+    });
+    let doc = format!(" The output of `{interface}` for each rule, returned by the methods of `Rule` dispatching it.");
+
+    quote! {
+        #[doc = #doc]
+        #vis enum #output_enum {
+            #(#variants,)*
+        }
+
+        impl #output_enum {
+            /// Returns the kind of the rule this output comes from.
+            #vis fn kind(&self) -> RuleKind {
+                match self {
+                    #(#kinds,)*
+                }
+            }
+        }
+
+        impl Rule {
+            #(#methods)*
+        }
+    }
+}
+
+/// Panics on the items of an `output_enum` interface which cannot be dispatched into the enum of its outputs.
+///
+/// The interface has a single associated type (the output), and its methods take a receiver, to find the rule
+/// struct, and return `Self::Output`, which is wrapped in the enum. It is not generic, so the enum is not either.
+pub(crate) fn check_output_interface(item: &ItemTrait) {
+    assert!(
+        item.generics.params.is_empty(),
+        "the interface of `output_enum` cannot be generic, the enum of its outputs would have to be as well"
+    );
+    let outputs = item
+        .items
+        .iter()
+        .filter_map(|item| match item {
+            TraitItem::Type(output) => Some(output),
+            _ => None,
+        })
+        .collect::<Vec<_>>();
+    let output = match outputs.as_slice() {
+        [output] => output,
+        [] => panic!(
+            "the interface of `output_enum` needs an associated type for the output of each rule struct, \
+             e.g. `type Output;`"
+        ),
+        _ => panic!("the interface of `output_enum` can only have one associated type, the output"),
+    };
+    assert!(
+        output.generics.params.is_empty(),
+        "the output `{}` of the interface cannot have generic parameters",
+        output.ident
+    );
+
+    let mut methods = 0;
+    for trait_item in &item.items {
+        match trait_item {
+            TraitItem::Type(_) => {}
+            TraitItem::Fn(method) => {
+                methods += 1;
+                let ident = &method.sig.ident;
+                if !matches!(method.sig.inputs.first(), Some(FnArg::Receiver(receiver)) if receiver.colon_token.is_none())
+                {
+                    panic!(
+                        "`{ident}` of the interface must take `self`, `&self` or `&mut self` to be dispatched \
+                         through `Rule`"
+                    );
+                }
+                let returns_output = match &method.sig.output {
+                    ReturnType::Type(_, ty) => match &**ty {
+                        Type::Path(path) => {
+                            path.qself.is_none()
+                                && path.path.segments.len() == 2
+                                && path.path.segments[0].ident == "Self"
+                                && path.path.segments[1].ident == output.ident
+                        }
+                        _ => false,
+                    },
+                    ReturnType::Default => false,
+                };
+                assert!(
+                    returns_output,
+                    "`{ident}` of the interface must return `Self::{}`, which `output_enum` wraps in the enum \
+                     of the outputs; move it to another trait",
+                    output.ident
+                );
+            }
+            TraitItem::Const(item) => panic!(
+                "associated const `{}` of the interface is not dispatched by `output_enum`; move it to another trait",
+                item.ident
+            ),
+            _ => panic!(
+                "`output_enum` cannot see the items a macro in the interface expands to; write them out instead"
+            ),
+        }
+    }
+    assert!(
+        methods > 0,
+        "the interface of `output_enum` needs a method returning `Self::{}`",
+        output.ident
+    );
+}
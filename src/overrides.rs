@@ -0,0 +1,101 @@
+//! Rules carrying a type of the user instead of a generated struct, see `override`.
+
+use crate::args::{PestParserArgs, Strings};
+use proc_macro2::{Delimiter, Group, TokenStream, TokenTree};
+use quote::{format_ident, quote};
+
+/// `pest` requires `Rule: Copy + Debug`, and the payload of an overridden rule is built with `Default::default()`
+/// wherever `Rule` is, so these bounds are checked for each override, in a function named after its rule: a type
+/// missing one is reported as "required by a bound in `override_of_Strings`". `Send + Sync` are required by the
+/// tables of rules, which are `static`s once a payload cannot be built in a constant, see [`rules_slice`].
+pub(crate) fn override_assertions_generator(args: &PestParserArgs) -> TokenStream {
+    let debug = (args.strings == Strings::Full).then(|| quote!(+ ::core::fmt::Debug));
+    let assertions = args.overrides.iter().map(|(rule, ty)| {
+        let assert_override = format_ident!("override_of_{}", rule);
+        quote! {
+            const _: () = {
+                #[allow(non_snake_case)]
+                fn #assert_override<
+                    T: ::core::marker::Copy
+                        + ::core::default::Default
+                        + ::core::marker::Send
+                        + ::core::marker::Sync
+                        #debug
+                        + 'static,
+                >() {
+                }
+                fn assert_override() {
+                    #assert_override::<#ty>();
+                }
+            };
+        }
+    });
+    quote! { #(#assertions)* }
+}
+
+/// returns an expression of type `&'static [Rule]` listing `rules`, as rule values: a promoted constant array, or a
+/// `static` built on first use if a payload is overridden, since `Default::default()` cannot run in a constant.
+pub(crate) fn rules_slice(rules: TokenStream, args: &PestParserArgs) -> TokenStream {
+    if args.overrides.is_empty() {
+        return quote! { &[#rules] };
+    }
+    quote! {
+        {
+            static RULES: ::std::sync::OnceLock<::std::vec::Vec<Rule>> = ::std::sync::OnceLock::new();
+            RULES.get_or_init(|| ::std::vec![#rules]).as_slice()
+        }
+    }
+}
+
+/// replaces the array returned by `pest`'s `Rule::all_rules()` (`&[Rule::r#a(..), ..]` once hooked) with
+/// [`rules_slice`], if a payload is overridden.
+pub(crate) fn lazy_all_rules(code: TokenStream, args: &PestParserArgs) -> TokenStream {
+    if args.overrides.is_empty() {
+        return code;
+    }
+    let tokens = code.into_iter().collect::<Vec<_>>();
+    let mut hooked = Vec::with_capacity(tokens.len());
+    for (index, token) in tokens.iter().enumerate() {
+        match token {
+            // the body of `impl Rule { .. }`, the only impl of `Rule` written by `pest`.
+            TokenTree::Group(body)
+                if body.delimiter() == Delimiter::Brace
+                    && index >= 2
+                    && matches!(&tokens[index - 2], TokenTree::Ident(item) if item == "impl")
+                    && matches!(&tokens[index - 1], TokenTree::Ident(name) if name == "Rule") =>
+            {
+                let mut group = Group::new(Delimiter::Brace, lazy_arrays(body.stream(), args));
+                group.set_span(body.span());
+                hooked.push(group.into());
+            }
+            token => hooked.push(token.clone()),
+        }
+    }
+    hooked.into_iter().collect()
+}
+
+/// replaces every `&[..]` in `stream` with [`rules_slice`], recursing into nested groups.
+fn lazy_arrays(stream: TokenStream, args: &PestParserArgs) -> TokenStream {
+    let tokens = stream.into_iter().collect::<Vec<_>>();
+    let mut hooked = Vec::with_capacity(tokens.len());
+    let mut index = 0;
+    while index < tokens.len() {
+        match (&tokens[index], tokens.get(index + 1)) {
+            (TokenTree::Punct(punct), Some(TokenTree::Group(array)))
+                if punct.as_char() == '&' && array.delimiter() == Delimiter::Bracket =>
+            {
+                hooked.extend(rules_slice(array.stream(), args));
+                index += 2;
+                continue;
+            }
+            (TokenTree::Group(group), _) => {
+                let mut nested = Group::new(group.delimiter(), lazy_arrays(group.stream(), args));
+                nested.set_span(group.span());
+                hooked.push(nested.into());
+            }
+            (token, _) => hooked.push(token.clone()),
+        }
+        index += 1;
+    }
+    hooked.into_iter().collect()
+}
@@ -0,0 +1,246 @@
+//! Generation of `match_pairs!`, destructuring the children of a pair by their expected rules.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    bracketed,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    punctuated::Punctuated,
+    Expr, Ident, Token,
+};
+
+/// The generated `match_pairs!` only bakes in the rules, in definition order, and forwards to the hidden
+/// `__match_pairs!` of this crate, which checks the rule names and turns them into indices while expanding.
+///
+/// The code it expands to only calls methods on the pair (`as_rule()`, then the inherent methods of `Rule`
+/// and `MatchPairs`), so it compiles in any module, without importing anything next to the parser. A
+/// `macro_rules!` cannot be exported by path when it is generated by a macro, so `match_pairs!` is re-exported
+/// with `pub(crate) use`, whatever the visibility of the parser. The items it calls are `pub` for the same
+/// reason, like `Rule` and `RuleKind`.
+pub(crate) fn match_pairs_generator(rules: &[Ident]) -> TokenStream {
+    quote! {
+        /// The error of `match_pairs!`, when the children of a pair are not the expected rules.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        pub struct MatchPairsError {
+            /// the rule of the pair whose children are matched.
+            pub parent: RuleKind,
+            /// the rule expected next, `None` where the children should have ended.
+            pub expected: Option<RuleKind>,
+            /// the rule of the child found instead, `None` if the children ended.
+            pub found: Option<RuleKind>,
+            /// line and column of the child found, or of the end of the pair if the children ended.
+            pub line_col: (usize, usize),
+        }
+
+        impl ::core::fmt::Display for MatchPairsError {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let (line, col) = self.line_col;
+                match (self.expected, self.found) {
+                    (Some(expected), Some(found)) => write!(
+                        f,
+                        "expected `{expected:?}`, found `{found:?}` in `{:?}` at {line}:{col}",
+                        self.parent
+                    ),
+                    (Some(expected), None) => write!(
+                        f,
+                        "expected `{expected:?}`, found the end of `{:?}` at {line}:{col}",
+                        self.parent
+                    ),
+                    (None, Some(found)) => write!(
+                        f,
+                        "unexpected `{found:?}` after the last expected child of `{:?}` at {line}:{col}",
+                        self.parent
+                    ),
+                    (None, None) => write!(
+                        f,
+                        "the children of `{:?}` do not match at {line}:{col}",
+                        self.parent
+                    ),
+                }
+            }
+        }
+
+        impl ::std::error::Error for MatchPairsError {}
+
+        /// The children of a pair being destructured by `match_pairs!`.
+        #[doc(hidden)]
+        pub struct MatchPairs<'i> {
+            parent: RuleKind,
+            end: (usize, usize),
+            children: ::core::iter::Peekable<::pest::iterators::Pairs<'i, Rule>>,
+        }
+
+        impl<'i> MatchPairs<'i> {
+            fn error(
+                &mut self,
+                expected: Option<RuleKind>,
+            ) -> MatchPairsError {
+                let (found, line_col) = match self.children.peek() {
+                    Some(child) => (Some(child.as_rule().kind()), child.as_span().start_pos().line_col()),
+                    None => (None, self.end),
+                };
+                MatchPairsError {
+                    parent: self.parent,
+                    expected,
+                    found,
+                    line_col,
+                }
+            }
+
+            /// takes the next child, which must be of the rule at `expected`.
+            pub fn one(&mut self, expected: usize) -> Result<::pest::iterators::Pair<'i, Rule>, MatchPairsError> {
+                let expected = RuleKind::ALL[expected];
+                match self.children.next_if(|child| child.as_rule() == expected) {
+                    Some(child) => Ok(child),
+                    None => Err(self.error(Some(expected))),
+                }
+            }
+
+            /// takes the next child if it is of the rule at `expected`.
+            pub fn optional(&mut self, expected: usize) -> Option<::pest::iterators::Pair<'i, Rule>> {
+                let expected = RuleKind::ALL[expected];
+                self.children.next_if(|child| child.as_rule() == expected)
+            }
+
+            /// takes the next children as long as they are of the rule at `expected`.
+            pub fn many(&mut self, expected: usize) -> Vec<::pest::iterators::Pair<'i, Rule>> {
+                let expected = RuleKind::ALL[expected];
+                ::core::iter::from_fn(|| self.children.next_if(|child| child.as_rule() == expected)).collect()
+            }
+
+            /// checks that every child has been taken.
+            pub fn end(mut self) -> Result<(), MatchPairsError> {
+                match self.children.peek() {
+                    Some(_) => Err(self.error(None)),
+                    None => Ok(()),
+                }
+            }
+        }
+
+        impl Rule {
+            /// Starts destructuring the children of `pair`, whose rule is `self`, see `match_pairs!`.
+            #[doc(hidden)]
+            pub fn match_pairs<'i>(self, pair: &::pest::iterators::Pair<'i, Rule>) -> MatchPairs<'i> {
+                MatchPairs {
+                    parent: self.kind(),
+                    end: pair.as_span().end_pos().line_col(),
+                    children: pair.clone().into_inner().peekable(),
+                }
+            }
+        }
+
+        /// Destructures the children of a pair by their expected rules, in order, e.g.
+        /// `match_pairs!(pair => [Identifier as name, Arguments? as args])`.
+        ///
+        /// `Rule as name` binds the next child, which must be of `Rule`; `Rule? as name` binds it as an `Option`,
+        /// `None` if the next child is of another rule; `Rule* as name` binds the next children of `Rule` as a
+        /// `Vec`. Every child must be matched. Otherwise, the enclosing function returns
+        /// `Err(MatchPairsError.into())`, saying which rule was expected where.
+        #[allow(unused_macros)]
+        macro_rules! __match_pairs_rules {
+            ($($input:tt)*) => {
+                ::enum_dispatch_pest_parser::__match_pairs! { [#(#rules)*] $($input)* }
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use __match_pairs_rules as match_pairs;
+    }
+}
+
+/// The input of `__match_pairs!`: the rules baked in by the generated `match_pairs!`, then the pair and the
+/// expected children given by the user.
+pub(crate) struct MatchPairsInput {
+    rules: Vec<Ident>,
+    pair: Expr,
+    children: Punctuated<Child, Token![,]>,
+}
+
+/// One expected child, e.g. `Strings? as doc`.
+struct Child {
+    rule: Ident,
+    quantifier: Option<char>,
+    name: Ident,
+}
+
+impl Parse for MatchPairsInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let rules;
+        bracketed!(rules in input);
+        let mut known = Vec::new();
+        while !rules.is_empty() {
+            known.push(rules.call(Ident::parse_any)?);
+        }
+        let pair = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        let children;
+        bracketed!(children in input);
+        Ok(Self {
+            rules: known,
+            pair,
+            children: children.parse_terminated(Child::parse, Token![,])?,
+        })
+    }
+}
+
+impl Parse for Child {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let rule = input.call(Ident::parse_any)?;
+        let quantifier = if input.parse::<Option<Token![?]>>()?.is_some() {
+            Some('?')
+        } else if input.parse::<Option<Token![*]>>()?.is_some() {
+            Some('*')
+        } else {
+            None
+        };
+        input.parse::<Token![as]>()?;
+        Ok(Self {
+            rule,
+            quantifier,
+            name: input.parse()?,
+        })
+    }
+}
+
+/// Expands `__match_pairs!` to one `let` per expected child, returning early on the first mismatch.
+///
+/// The locals of the expansion are `mixed_site`, so they cannot be seen (or shadowed) by the user's code, while
+/// the bindings keep the user's spans and stay in scope after the macro.
+pub(crate) fn match_pairs_expand(input: MatchPairsInput) -> TokenStream {
+    let span = Span::mixed_site();
+    let (pair, children) = (Ident::new("pair", span), Ident::new("children", span));
+    let user_pair = &input.pair;
+    let mut lets = Vec::new();
+    for child in &input.children {
+        let Some(index) = input
+            .rules
+            .iter()
+            .position(|rule| rule.unraw() == child.rule.unraw())
+        else {
+            let message = format!("`{}` is not a rule of the grammar", child.rule.unraw());
+            return quote_spanned!(child.rule.span()=> ::core::compile_error!(#message););
+        };
+        let name = &child.name;
+        lets.push(match child.quantifier {
+            None => quote_spanned! {span=>
+                let #name = match #children.one(#index) {
+                    ::core::result::Result::Ok(child) => child,
+                    ::core::result::Result::Err(error) => {
+                        return ::core::result::Result::Err(::core::convert::From::from(error))
+                    }
+                };
+            },
+            Some('?') => quote_spanned!(span=> let #name = #children.optional(#index);),
+            Some(_) => quote_spanned!(span=> let #name = #children.many(#index);),
+        });
+    }
+    quote_spanned! {span=>
+        let #pair = &#user_pair;
+        let mut #children = #pair.as_rule().match_pairs(#pair);
+        #(#lets)*
+        if let ::core::result::Result::Err(error) = #children.end() {
+            return ::core::result::Result::Err(::core::convert::From::from(error));
+        }
+    }
+}
@@ -0,0 +1,111 @@
+//! Generation of the dispatch `enum Rule` for a parser generated elsewhere (`reuse_parser`, `parser_derive`).
+
+use crate::args::{snake_case, PestParserArgs, Strings, StructStyle};
+use crate::grammar::GrammarContext;
+use proc_macro2::{Span, TokenStream};
+use quote::{format_ident, quote};
+use syn::{ext::IdentExt, parse_quote, Ident, ItemEnum, Path};
+
+/// `pest` emits `enum Rule` next to the parser, so the external rule type is found by replacing the last
+/// segment of the parser path. Since the external `Rule` is fieldless, it cannot be dispatched itself: a
+/// separate `enum Rule` carrying the payloads is generated instead, converting from and into the external
+/// one by variant name. The external `Rule` must therefore have exactly the rules of the grammar, any
+/// mismatch is reported by the compiler as a non-exhaustive `match` or an unknown variant.
+pub(crate) fn reused_rule_generator(
+    rule_enum: &ItemEnum,
+    args: &PestParserArgs,
+    parser: &Path,
+) -> TokenStream {
+    let mut external = parser.clone();
+    external
+        .segments
+        .last_mut()
+        .expect("`reuse_parser` must not be empty")
+        .ident = Ident::new("Rule", Span::call_site());
+
+    let rules = rule_enum
+        .variants
+        .iter()
+        .map(|variant| &variant.ident)
+        .collect::<Vec<_>>();
+    let types = rules
+        .iter()
+        .map(|ident| {
+            args.payload_type(&ident.to_string())
+                .parse::<TokenStream>()
+                .expect("illegal payload type")
+        })
+        .collect::<Vec<_>>();
+    let payloads = rules.iter().map(|ident| args.payload(ident));
+
+    let enum_dispatch =
+        (args.struct_style == StructStyle::Structs).then(|| args.enum_dispatch_attr());
+    let rule_attrs = &args.rule_attrs;
+    let debug = (args.strings == Strings::Full).then(|| quote!(Debug));
+
+    quote! {
+        /// The rules of the reused parser, carrying the dispatch payloads.
+        #enum_dispatch
+        #(#rule_attrs)*
+        #[allow(dead_code, non_camel_case_types, clippy::upper_case_acronyms)]
+        // the comparisons are implemented through `RuleKind`, see `rule_kind_generator`.
+        #[derive(Clone, Copy, #debug)]
+        pub enum Rule {
+            #(#rules(#types)),*
+        }
+
+        impl From<#external> for Rule {
+            #[inline]
+            fn from(rule: #external) -> Self {
+                match rule {
+                    #(#external::#rules => Rule::#rules(#payloads)),*
+                }
+            }
+        }
+
+        impl From<Rule> for #external {
+            #[inline]
+            fn from(rule: Rule) -> Self {
+                match rule {
+                    #(Rule::#rules(_) => #external::#rules),*
+                }
+            }
+        }
+    }
+}
+
+/// `parser_derive` applies the derive of the user (e.g. of a fork of `pest`) to a parser struct of the same name in
+/// a hidden module, next to the fieldless `enum Rule` it emits, which would collide with the dispatch one. The
+/// parser is then reused from there, so the derive must emit what `reuse_parser` expects of a parser: `enum Rule`
+/// next to it, with a variant per rule of the grammar, and an impl of `pest::Parser`. The rules themselves are
+/// still extracted by `pest_generator` from the grammar.
+///
+/// The module imports everything from its parent, so the derive is named as next to the parser. The grammar is
+/// given by its resolved path (or inline, for `pest_parser_tokens!`), which a derive resolving paths from
+/// `CARGO_MANIFEST_DIR` like `pest`'s finds as is.
+pub(crate) fn derived_parser_generator(
+    ident: &Ident,
+    derive: &Path,
+    grammar: &GrammarContext,
+) -> (Path, TokenStream) {
+    let module = format_ident!("__{}_derived", snake_case(&ident.unraw().to_string()));
+    let grammar = match grammar.path() {
+        Some(path) => quote!(#[grammar = #path]),
+        None => {
+            let source = grammar.source();
+            quote!(#[grammar_inline = #source])
+        }
+    };
+    let parser = quote! {
+        #[doc(hidden)]
+        mod #module {
+            #[allow(unused_imports)]
+            use super::*;
+
+            #[derive(#derive)]
+            #grammar
+            pub struct #ident;
+        }
+    };
+    (parse_quote!(#module::#ident), parser)
+}
@@ -0,0 +1,102 @@
+//! Generation of `rewrite()`, a span based source-to-source rewriter.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+/// Replacements are collected innermost-first (children before their parent), then sorted by span and
+/// spliced into the input in one pass. Since a replaced node owns its whole span, a replacement may
+/// neither overlap nor contain another one; adjacent and empty spans (e.g. `EOI`) are fine. Containment is
+/// checked on the tree while collecting, since an empty span at either end of its parent's (`EOI` in
+/// `Script`) is adjacent to it by range; the ranges are checked for overlaps while splicing.
+///
+/// `rewrite()` itself is a shim handing `f` as `&mut dyn FnMut` to the nested functions, so the traversal
+/// and the splicing are compiled once instead of once per closure.
+pub(crate) fn rewrite_generator(vis: &Visibility) -> TokenStream {
+    quote! {
+        /// Two replacements returned to [`rewrite`] cover overlapping spans.
+        #[derive(Clone, Debug, Eq, PartialEq)]
+        #vis struct RewriteError {
+            /// kind and byte range of the replacement which comes first in the input.
+            pub first: (RuleKind, ::std::ops::Range<usize>),
+            /// kind and byte range of the replacement overlapping `first`.
+            pub second: (RuleKind, ::std::ops::Range<usize>),
+        }
+
+        impl ::std::fmt::Display for RewriteError {
+            fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                write!(
+                    f,
+                    "replacement of `{:?}` at {:?} overlaps replacement of `{:?}` at {:?}",
+                    self.first.0, self.first.1, self.second.0, self.second.1
+                )
+            }
+        }
+
+        impl ::std::error::Error for RewriteError {}
+
+        /// Rewrites `input` by replacing the text of every node for which `f` returns `Some`, the remaining
+        /// text is kept verbatim. `pairs` must be parsed from `input`.
+        ///
+        /// `f` is called innermost-first, so children are seen before their parent. Replacing both a node
+        /// and one of its descendants (or any other overlapping spans) is rejected with a [`RewriteError`].
+        #vis fn rewrite(
+            input: &str,
+            pairs: ::pest::iterators::Pairs<'_, Rule>,
+            mut f: impl FnMut(RuleKind, &::pest::iterators::Pair<'_, Rule>) -> Option<String>,
+        ) -> ::std::result::Result<String, RewriteError> {
+            fn collect(
+                pairs: ::pest::iterators::Pairs<'_, Rule>,
+                f: &mut dyn FnMut(RuleKind, &::pest::iterators::Pair<'_, Rule>) -> Option<String>,
+                replacements: &mut Vec<(RuleKind, ::std::ops::Range<usize>, String)>,
+            ) -> ::std::result::Result<(), RewriteError> {
+                for pair in pairs {
+                    let descendants = replacements.len();
+                    collect(pair.clone().into_inner(), f, replacements)?;
+                    if let Some(replacement) = f(pair.as_rule().kind(), &pair) {
+                        let span = pair.as_span();
+                        let range = span.start()..span.end();
+                        if let Some((kind, descendant, _)) = replacements.get(descendants) {
+                            return Err(RewriteError {
+                                first: (pair.as_rule().kind(), range),
+                                second: (*kind, descendant.clone()),
+                            });
+                        }
+                        replacements.push((pair.as_rule().kind(), range, replacement));
+                    }
+                }
+                Ok(())
+            }
+
+            fn splice(
+                input: &str,
+                pairs: ::pest::iterators::Pairs<'_, Rule>,
+                f: &mut dyn FnMut(RuleKind, &::pest::iterators::Pair<'_, Rule>) -> Option<String>,
+            ) -> ::std::result::Result<String, RewriteError> {
+                let mut replacements = Vec::new();
+                collect(pairs, f, &mut replacements)?;
+                // stable sort keeps innermost-first order for replacements with identical (empty) spans.
+                replacements.sort_by_key(|(_, range, _)| (range.start, range.end));
+
+                let mut output = String::with_capacity(input.len());
+                let mut cursor = 0;
+                for (index, (kind, range, replacement)) in replacements.iter().enumerate() {
+                    if range.start < cursor {
+                        let (previous_kind, previous_range, _) = &replacements[index - 1];
+                        return Err(RewriteError {
+                            first: (*previous_kind, previous_range.clone()),
+                            second: (*kind, range.clone()),
+                        });
+                    }
+                    output.push_str(&input[cursor..range.start]);
+                    output.push_str(replacement);
+                    cursor = range.end;
+                }
+                output.push_str(&input[cursor..]);
+                Ok(output)
+            }
+
+            splice(input, pairs, &mut f)
+        }
+    }
+}
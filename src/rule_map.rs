@@ -0,0 +1,93 @@
+//! Generation of `RuleMap<T>`, a value for every rule stored in an array.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+/// The rules are few and numbered densely by `RuleKind::index()`, so per-rule data fits in an array of
+/// `Rule::RULE_COUNT` values, with `EOI` included, rather than in a `HashMap<RuleKind, T>`: no hashing, no
+/// allocation, and no missing keys. The iteration zips the values with `RuleKind::all()`, whose order is the
+/// definition order the array is indexed in.
+pub(crate) fn rule_map_struct_generator(vis: &Visibility) -> TokenStream {
+    quote! {
+        /// A value of type `T` for every rule (including `EOI`), stored in an array indexed by
+        /// [`RuleKind::index`], e.g. a count or a symbol table per rule.
+        #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+        #vis struct RuleMap<T>([T; Rule::RULE_COUNT]);
+
+        #[allow(dead_code)]
+        impl<T> RuleMap<T> {
+            /// Returns a map with a clone of `default` for every rule.
+            pub fn new(default: T) -> Self
+            where
+                T: Clone,
+            {
+                Self(::core::array::from_fn(|_| default.clone()))
+            }
+
+            /// Returns a map with `f(kind)` for every rule, called in definition order.
+            pub fn from_fn(mut f: impl FnMut(RuleKind) -> T) -> Self {
+                Self(::core::array::from_fn(|index| f(RuleKind::ALL[index])))
+            }
+
+            /// Returns the value of `rule`.
+            #[inline]
+            pub fn get(&self, rule: Rule) -> &T {
+                &self.0[rule.kind().index()]
+            }
+
+            /// Returns the value of `rule`, mutably.
+            #[inline]
+            pub fn get_mut(&mut self, rule: Rule) -> &mut T {
+                &mut self.0[rule.kind().index()]
+            }
+
+            /// Returns the kinds with their values, in definition order.
+            pub fn iter(&self) -> RuleMapIter<'_, T> {
+                RuleKind::all().iter().copied().zip(self.0.iter())
+            }
+
+            /// Returns the kinds with their values, mutably, in definition order.
+            pub fn iter_mut(
+                &mut self,
+            ) -> ::core::iter::Zip<
+                ::core::iter::Copied<::core::slice::Iter<'static, RuleKind>>,
+                ::core::slice::IterMut<'_, T>,
+            > {
+                RuleKind::all().iter().copied().zip(self.0.iter_mut())
+            }
+        }
+
+        /// The iterator over the kinds and values of a [`RuleMap`], in definition order.
+        #[allow(dead_code)]
+        #vis type RuleMapIter<'a, T> = ::core::iter::Zip<
+            ::core::iter::Copied<::core::slice::Iter<'static, RuleKind>>,
+            ::core::slice::Iter<'a, T>,
+        >;
+
+        impl<'a, T> IntoIterator for &'a RuleMap<T> {
+            type Item = (RuleKind, &'a T);
+            type IntoIter = RuleMapIter<'a, T>;
+
+            fn into_iter(self) -> Self::IntoIter {
+                self.iter()
+            }
+        }
+
+        impl<T> ::core::ops::Index<RuleKind> for RuleMap<T> {
+            type Output = T;
+
+            #[inline]
+            fn index(&self, kind: RuleKind) -> &T {
+                &self.0[kind.index()]
+            }
+        }
+
+        impl<T> ::core::ops::IndexMut<RuleKind> for RuleMap<T> {
+            #[inline]
+            fn index_mut(&mut self, kind: RuleKind) -> &mut T {
+                &mut self.0[kind.index()]
+            }
+        }
+    }
+}
@@ -0,0 +1,147 @@
+//! Generation of `RuleTimings`, the time spent in the handlers of every rule (the `timing` feature).
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+/// Like `RuleCoverage`, the timings are a `RuleMap` of atomics installed for the current thread, so the helpers
+/// keep their signatures and one `RuleTimings` can be shared by several threads. The helpers handing a pair to
+/// the user (`walk_into()`, `dispatch_next()`, `dispatch_with_context()` and `walk_with_stack()`) run that call
+/// through `RuleKind::__time_handler()`, which reads the clock only while a `RuleTimings` is installed. The
+/// children of a pair are visited after its call returns, so a call is timed on its own.
+///
+/// Durations are kept as nanoseconds in a `u64` (over five centuries), the adds saturate rather than wrap.
+pub(crate) fn rule_timings_generator(vis: &Visibility) -> TokenStream {
+    quote! {
+        /// The time spent in the handlers of each rule, and the number of calls, measured with a monotonic clock
+        /// on the threads these timings were installed on.
+        #[derive(Debug)]
+        #vis struct RuleTimings(
+            RuleMap<(::std::sync::atomic::AtomicU64, ::std::sync::atomic::AtomicU64)>,
+        );
+
+        ::std::thread_local! {
+            static __RULE_TIMINGS: ::core::cell::RefCell<
+                ::core::option::Option<::std::sync::Arc<RuleTimings>>,
+            > = const { ::core::cell::RefCell::new(::core::option::Option::None) };
+        }
+
+        #[allow(dead_code)]
+        impl RuleTimings {
+            /// Returns timings with no call recorded.
+            pub fn new() -> Self {
+                Self(RuleMap::from_fn(|_| {
+                    (
+                        ::std::sync::atomic::AtomicU64::new(0),
+                        ::std::sync::atomic::AtomicU64::new(0),
+                    )
+                }))
+            }
+
+            /// Adds a call of a handler of `kind` which took `elapsed`.
+            pub fn record(&self, kind: RuleKind, elapsed: ::core::time::Duration) {
+                fn saturating_add(counter: &::std::sync::atomic::AtomicU64, amount: u64) {
+                    let _ = counter.fetch_update(
+                        ::std::sync::atomic::Ordering::Relaxed,
+                        ::std::sync::atomic::Ordering::Relaxed,
+                        |current| ::core::option::Option::Some(current.saturating_add(amount)),
+                    );
+                }
+
+                let (total, calls) = &self.0[kind];
+                saturating_add(total, u64::try_from(elapsed.as_nanos()).unwrap_or(u64::MAX));
+                saturating_add(calls, 1);
+            }
+
+            /// Returns the time spent in the handlers of `kind`.
+            pub fn total(&self, kind: RuleKind) -> ::core::time::Duration {
+                ::core::time::Duration::from_nanos(
+                    self.0[kind].0.load(::std::sync::atomic::Ordering::Relaxed),
+                )
+            }
+
+            /// Returns the number of calls of the handlers of `kind`.
+            pub fn calls(&self, kind: RuleKind) -> u64 {
+                self.0[kind].1.load(::std::sync::atomic::Ordering::Relaxed)
+            }
+
+            /// Returns the rules called at least once with their total time and number of calls, the slowest
+            /// first (ties in definition order).
+            pub fn report_sorted(&self) -> Vec<(RuleKind, ::core::time::Duration, u64)> {
+                let mut report = RuleKind::all()
+                    .iter()
+                    .map(|kind| (*kind, self.total(*kind), self.calls(*kind)))
+                    .filter(|(_, _, calls)| *calls > 0)
+                    .collect::<Vec<_>>();
+                report.sort_by(|a, b| b.1.cmp(&a.1));
+                report
+            }
+
+            /// Makes the helpers of the current thread record into `timings`, returning the timings installed
+            /// before, if any.
+            pub fn install(
+                timings: ::std::sync::Arc<Self>,
+            ) -> ::core::option::Option<::std::sync::Arc<Self>> {
+                __RULE_TIMINGS.with_borrow_mut(|installed| installed.replace(timings))
+            }
+
+            /// Stops the recording on the current thread, returning the installed timings, if any.
+            pub fn uninstall() -> ::core::option::Option<::std::sync::Arc<Self>> {
+                __RULE_TIMINGS.with_borrow_mut(::core::option::Option::take)
+            }
+        }
+
+        impl ::core::default::Default for RuleTimings {
+            fn default() -> Self {
+                Self::new()
+            }
+        }
+
+        /// A table of [`RuleTimings::report_sorted`], one rule per line with its calls, total and mean time.
+        impl ::core::fmt::Display for RuleTimings {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let rows = self
+                    .report_sorted()
+                    .into_iter()
+                    .map(|(kind, total, calls)| {
+                        let mean = total / u32::try_from(calls).unwrap_or(u32::MAX);
+                        (format!("{kind:?}"), calls, format!("{total:?}"), format!("{mean:?}"))
+                    })
+                    .collect::<Vec<_>>();
+                let width = rows.iter().map(|row| row.0.len()).max().unwrap_or(0).max(4);
+                writeln!(f, "{:<width$} {:>10} {:>14} {:>14}", "rule", "calls", "total", "mean")?;
+                for (rule, calls, total, mean) in rows {
+                    writeln!(f, "{rule:<width$} {calls:>10} {total:>14} {mean:>14}")?;
+                }
+                Ok(())
+            }
+        }
+
+        impl RuleKind {
+            #[doc(hidden)]
+            #[inline]
+            pub fn __time_handler<R>(self, f: impl ::core::ops::FnOnce() -> R) -> R {
+                let timings = __RULE_TIMINGS.with_borrow(::core::clone::Clone::clone);
+                match timings {
+                    ::core::option::Option::Some(timings) => {
+                        let started = ::std::time::Instant::now();
+                        let returned = f();
+                        timings.record(self, started.elapsed());
+                        returned
+                    }
+                    ::core::option::Option::None => f(),
+                }
+            }
+        }
+    }
+}
+
+/// returns `call`, a call of a handler on the pair `pair`, timed into the installed `RuleTimings`, or `call`
+/// itself without the `timing` feature.
+pub(crate) fn time_handler(pair: TokenStream, call: TokenStream) -> TokenStream {
+    if cfg!(feature = "timing") {
+        quote!(#pair.as_rule().kind().__time_handler(|| #call))
+    } else {
+        call
+    }
+}
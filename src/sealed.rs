@@ -0,0 +1,63 @@
+//! Generation of `sealed`, restricting the implementations of the interface to the rule structs.
+
+use crate::args::PestParserArgs;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Path;
+
+/// The usual sealed-trait pattern: `Sealed` is public in a module only visible in the crate, so it can be named as
+/// a supertrait of the interface there, but not implemented (nor named) by another crate, and neither can the
+/// interface. It is implemented for `Rule` and every payload of its variants (the rule structs, the group structs,
+/// `RuleStruct` or the overriding types), which are exactly the types the dispatch implements the interface for.
+///
+/// The interface adopts it by hand (`trait ParserInterface: sealed::Sealed`), since `#[pest_parser]` only knows it
+/// by name; the generated assertion fails to compile until it does, so sealing cannot be forgotten. A trait of
+/// another crate cannot adopt it, which is reported earlier with a message of its own, see
+/// [`check_local_interface`].
+pub(crate) fn sealed_generator(rules: &[Ident], args: &PestParserArgs) -> TokenStream {
+    let payloads = args.payload_paths(rules);
+    let adopted = args.interface.is_some().then(|| {
+        let interface = args.interface_path();
+        check_local_interface(&interface);
+        quote! {
+            const _: fn() = || {
+                fn is_sealed<T: ?::core::marker::Sized + sealed::Sealed>() {}
+                fn adopted<T: ?::core::marker::Sized + #interface>() {
+                    is_sealed::<T>();
+                }
+            };
+        }
+    });
+    quote! {
+        #[doc(hidden)]
+        pub(crate) mod sealed {
+            /// Implemented by `Rule` and the rule structs only. An interface declared as
+            /// `trait ParserInterface: sealed::Sealed` cannot be implemented outside of this crate, nor for
+            /// other types than these.
+            pub trait Sealed {}
+        }
+
+        impl sealed::Sealed for Rule {}
+        #(impl sealed::Sealed for #payloads {})*
+
+        #adopted
+    }
+}
+
+/// Panics when `interface` is a path into another crate, whose trait cannot have the `Sealed` of this crate as
+/// supertrait. A bare name may still be imported from another crate, which the assertion of [`sealed_generator`]
+/// then reports.
+fn check_local_interface(interface: &Path) {
+    let first = &interface.segments.first().expect("empty interface").ident;
+    let local = interface.leading_colon.is_none()
+        && (interface.segments.len() == 1
+            || first == "crate"
+            || first == "self"
+            || first == "super");
+    assert!(
+        local,
+        "`sealed` seals an interface of this crate, `{}` is not one: a trait of another crate cannot have \
+         `sealed::Sealed` as supertrait",
+        quote!(#interface).to_string().replace(' ', "")
+    );
+}
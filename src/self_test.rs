@@ -0,0 +1,112 @@
+//! The early warning of the `self_test` feature: runs the `pest_generator` this crate is built with on a pinned
+//! grammar and checks that its output still has the shape the extraction and the hooking rely on.
+//!
+//! The fixtures of `src/adapter.rs` are outputs captured once, so they keep passing when a new `pest` changes its
+//! code generation; these tests run `derive_parser` itself, and fail with the step that no longer works after a
+//! `cargo update` of `pest`. They are behind a feature since they test the resolved `pest`, not this crate:
+//! `cargo test --features self_test`.
+
+use crate::adapter::{Landmarks, ADAPTERS};
+use crate::args::PestParserArgs;
+use crate::grammar::{GrammarContext, GrammarSource};
+use crate::{check_hooked_codes, hook_raw_codes, pest_rule_enum};
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::{parse_quote, ItemEnum};
+
+/// the grammar of the tests, covering every kind of rule (silent, atomic, compound-atomic, tagged, keyword-named,
+/// with the stack and with repetitions), pinned in `tests/fixtures`.
+const GRAMMAR: &str = include_str!("../tests/fixtures/self_test.pest");
+
+/// the variants of `enum Rule` for [`GRAMMAR`], as `pest` emits them: `EOI` first, then the rules in definition
+/// order as raw identifiers.
+const VARIANTS: &[&str] = &[
+    "EOI",
+    "r#WHITESPACE",
+    "r#COMMENT",
+    "r#document",
+    "r#item",
+    "r#match",
+    "r#arm",
+    "r#block",
+    "r#fence",
+    "r#pair",
+    "r#value",
+    "r#number",
+    "r#string",
+    "r#content",
+    "r#ident",
+    "r#keyword",
+];
+
+/// runs `pest`'s public entry point, as `#[derive(Parser)]` would, and extracts its `enum Rule`.
+fn derive_parser() -> (TokenStream, ItemEnum) {
+    let code = pest_generator::derive_parser(
+        quote! {
+            #[grammar_inline = #GRAMMAR]
+            pub struct SelfTestParser;
+        },
+        true,
+    );
+    let rule_enum = pest_rule_enum(code.clone());
+    (code, rule_enum)
+}
+
+#[test]
+fn extracts_every_rule() {
+    let (_, rule_enum) = derive_parser();
+    let variants = rule_enum
+        .variants
+        .iter()
+        .map(|variant| variant.ident.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        variants, VARIANTS,
+        "`enum Rule` of `pest_generator` has other variants than the pinned grammar's rules"
+    );
+}
+
+#[test]
+fn output_matches_a_supported_version() {
+    let (code, rule_enum) = derive_parser();
+    let found = Landmarks::find(&rule_enum, &code);
+    if ADAPTERS.iter().all(|adapter| adapter.check(found).is_err()) {
+        let newest = ADAPTERS.last().expect("no supported version");
+        panic!(
+            "the output of `pest_generator` matches no supported `pest_version`: {}",
+            newest.check(found).unwrap_err()
+        );
+    }
+}
+
+#[test]
+fn internal_api_generates_the_same_parser() {
+    let (code, _) = derive_parser();
+    let grammar = GrammarContext::load(&GrammarSource::Tokens(GRAMMAR.to_string(), Vec::new()));
+    let generated = grammar.generate_parser(&parse_quote!(SelfTestParser));
+    assert_eq!(
+        generated.to_string(),
+        code.to_string(),
+        "`generate_parser` (driving the internals of `pest_generator`) no longer generates what `derive_parser` does"
+    );
+}
+
+#[test]
+fn output_is_hooked() {
+    let (code, rule_enum) = derive_parser();
+    for args in [
+        quote!(grammar = "self_test.pest", interface = "SelfTestInterface"),
+        quote!(
+            grammar = "self_test.pest",
+            interface = "SelfTestInterface",
+            struct_style = "enum"
+        ),
+    ] {
+        let args = PestParserArgs::from_arguments(parse_quote!(#args));
+        let hooked = hook_raw_codes(code.clone(), &rule_enum, &args, quote!(SelfTestInterface));
+        check_hooked_codes(&hooked, &rule_enum);
+        if let Err(error) = syn::parse2::<syn::File>(hooked) {
+            panic!("the hooked output of `pest_generator` is not valid Rust: {error}");
+        }
+    }
+}
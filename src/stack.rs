@@ -0,0 +1,95 @@
+//! Generation of `RuleStack` and `walk_with_stack()`, a traversal keeping the rules from the root to a pair.
+
+use crate::coverage::record_coverage;
+use crate::rule_timings::time_handler;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+/// The rules of the path are pushed when a pair is visited and popped once its children are exhausted, i.e.
+/// before its next sibling is visited, so the path always ends with the visited pair. `EOI` is not visited and
+/// never on the path.
+///
+/// Like `dispatch_with_context()`, it is a shim over a nested function taking `f` as `&mut dyn FnMut` and keeping
+/// the error aside, so it is compiled once whatever `E` and the closure are.
+pub(crate) fn rule_stack_generator(vis: &Visibility) -> TokenStream {
+    let record = record_coverage(quote!(pair));
+    let visit = time_handler(quote!(pair), quote!(f(&stack, &pair)));
+    quote! {
+        /// The rules from the root of the walked pairs to the pair visited by [`walk_with_stack`], both included.
+        #[derive(Clone, Debug, Default)]
+        #vis struct RuleStack {
+            path: Vec<RuleKind>,
+        }
+
+        impl RuleStack {
+            /// Returns whether the visited pair or one of its ancestors is of the rule `kind`.
+            #vis fn contains(&self, kind: RuleKind) -> bool {
+                self.path.contains(&kind)
+            }
+
+            /// Returns the number of ancestors of the visited pair, `0` for the pairs given.
+            #vis fn depth(&self) -> usize {
+                self.path.len().saturating_sub(1)
+            }
+
+            /// Returns the rule of the visited pair.
+            #vis fn top(&self) -> Option<RuleKind> {
+                self.path.last().copied()
+            }
+
+            /// Returns the rules from the root to the visited pair.
+            #vis fn path(&self) -> &[RuleKind] {
+                &self.path
+            }
+        }
+
+        /// Visits every pair of `pairs` in pre-order (skipping `EOI`) and hands it to `f` with the rules of its
+        /// ancestors, stopping at the first error.
+        #vis fn walk_with_stack<'i, E>(
+            pairs: ::pest::iterators::Pairs<'i, Rule>,
+            mut f: impl FnMut(&RuleStack, &::pest::iterators::Pair<'i, Rule>) -> Result<(), E>,
+        ) -> Result<(), E> {
+            fn walk<'i>(
+                pairs: ::pest::iterators::Pairs<'i, Rule>,
+                f: &mut dyn FnMut(&RuleStack, &::pest::iterators::Pair<'i, Rule>) -> ::core::ops::ControlFlow<()>,
+            ) {
+                let mut stack = RuleStack::default();
+                // the siblings left to visit, the children of the last rule of `stack` but for the pairs given and
+                // the children of a transparent pair, whose rule is not pushed.
+                let mut siblings = vec![(pairs, false)];
+                while let Some((pairs, _)) = siblings.last_mut() {
+                    let Some(pair) = pairs.next() else {
+                        if let Some((_, true)) = siblings.pop() {
+                            stack.path.pop();
+                        }
+                        continue;
+                    };
+                    if pair.as_rule() == RuleKind::EOI {
+                        continue;
+                    }
+                    #record
+                    if pair.as_rule().kind().is_transparent() {
+                        siblings.push((pair.into_inner(), false));
+                        continue;
+                    }
+                    stack.path.push(pair.as_rule().kind());
+                    if #visit.is_break() {
+                        return;
+                    }
+                    siblings.push((pair.into_inner(), true));
+                }
+            }
+
+            let mut error = None;
+            walk(pairs, &mut |stack, pair| match f(stack, pair) {
+                Ok(()) => ::core::ops::ControlFlow::Continue(()),
+                Err(e) => {
+                    error = Some(e);
+                    ::core::ops::ControlFlow::Break(())
+                }
+            });
+            error.map_or(Ok(()), Err)
+        }
+    }
+}
@@ -0,0 +1,31 @@
+//! Generation of `dispatch_next()`, pulling one pair at a time out of `Pairs`.
+
+use crate::coverage::record_coverage;
+use crate::rule_timings::time_handler;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+/// `Pairs` is advanced in place rather than consumed, so the caller decides when (and whether) the next pair is
+/// dispatched, e.g. to stop after the first error or interleave parsing with other work.
+pub(crate) fn dispatch_next_generator(vis: &Visibility) -> TokenStream {
+    let record = record_coverage(quote!(pair));
+    let visit = time_handler(quote!(pair), quote!(visitor(pair.as_rule(), pair)));
+    quote! {
+        /// Advances `pairs` by one pair and hands its rule and the pair to `visitor`, skipping `EOI`.
+        ///
+        /// Returns `None` once `pairs` is exhausted. Only the siblings of `pairs` are visited, call it on
+        /// `pair.into_inner()` within `visitor` to stream into a pair.
+        #vis fn dispatch_next<'i, V, Out, E>(
+            pairs: &mut ::pest::iterators::Pairs<'i, Rule>,
+            visitor: &V,
+        ) -> Option<Result<Out, E>>
+        where
+            V: Fn(Rule, ::pest::iterators::Pair<'i, Rule>) -> Result<Out, E>,
+        {
+            let pair = pairs.find(|pair| pair.as_rule() != RuleKind::EOI)?;
+            #record
+            Some(#visit)
+        }
+    }
+}
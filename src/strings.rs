@@ -0,0 +1,84 @@
+//! Generation of the stubs standing in for the rule name APIs with `strings = "minimal"`.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// The stubs keep the names of the omitted APIs, so using one is a compile error saying why it is missing,
+/// rather than an unresolved name.
+///
+/// Functions are bounded by a trait without impls, whose `#[diagnostic::on_unimplemented]` message is the
+/// error. The bound mentions a lifetime of the function, otherwise it would be checked (and fail) where the
+/// stub is defined instead of where it is called. Statics cannot be bounded, they are configured out with
+/// `#[cfg(any())]`, for which `rustc` points at the stub.
+///
+/// The derived `Debug` of `Rule` and `RuleKind` would embed the names as well, as soon as an error of `pest`
+/// is formatted or unwrapped, so they print the index of the rule instead (e.g. `Rule(3)`). The payloads are
+/// not printed, which leaves their derived `Debug` unused.
+pub(crate) fn minimal_strings_generator() -> TokenStream {
+    let rule_map = cfg!(feature = "phf").then(|| {
+        quote! {
+            #[cfg(any())]
+            pub static RULE_MAP: () = ();
+        }
+    });
+    let rule_meta = cfg!(feature = "pest_meta").then(|| {
+        quote! {
+            #[cfg(any())]
+            pub static RULE_META: () = ();
+        }
+    });
+
+    quote! {
+        #[doc(hidden)]
+        #[diagnostic::on_unimplemented(
+            message = "this API is disabled by `strings = \"minimal\"`, which omits the rule names and docs",
+            label = "not generated with `strings = \"minimal\"`"
+        )]
+        pub trait DisabledByStringsMinimal {}
+
+        impl ::core::fmt::Debug for RuleKind {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_tuple("RuleKind").field(&self.index()).finish()
+            }
+        }
+
+        impl ::core::fmt::Debug for Rule {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                f.debug_tuple("Rule").field(&self.kind().index()).finish()
+            }
+        }
+
+        impl RuleKind {
+            #[doc(hidden)]
+            pub fn name<'a>(&'a self) -> &'static str
+            where
+                &'a RuleKind: DisabledByStringsMinimal,
+            {
+                ::core::unreachable!()
+            }
+
+            #[doc(hidden)]
+            pub fn from_name<'a>(_name: &'a str) -> Option<RuleKind>
+            where
+                &'a str: DisabledByStringsMinimal,
+            {
+                ::core::unreachable!()
+            }
+        }
+
+        #[doc(hidden)]
+        pub fn doc_of<'a>(_kind: RuleKind) -> Option<&'a str>
+        where
+            &'a str: DisabledByStringsMinimal,
+        {
+            ::core::unreachable!()
+        }
+
+        #[cfg(any())]
+        pub static RULE_DOCS: () = ();
+
+        #rule_map
+
+        #rule_meta
+    }
+}
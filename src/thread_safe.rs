@@ -0,0 +1,23 @@
+//! Generation of the `thread_safe` assertion, keeping the dispatch usable from a thread pool.
+
+use crate::args::PestParserArgs;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+
+/// The generated payloads are unit structs (or a fieldless enum) and the parser is a unit struct, so `Rule`,
+/// `RuleKind` and the parser are `Send + Sync` as long as no type of the user is involved: an `override`, whose
+/// bound already requires both, or the parser of `reuse_parser`. The assertion makes a regression a compile
+/// error at the parser rather than at its first use from another thread. `ParseNode` is checked as well with
+/// `generate = "tree"`, being the owned tree which can be sent across threads, unlike `pest`'s `Pairs`.
+pub(crate) fn thread_safe_generator(parser: &Ident, args: &PestParserArgs) -> TokenStream {
+    let parse_node = args.generates("tree").then(|| quote!(a::<ParseNode>();));
+    quote! {
+        const _: fn() = || {
+            fn a<T: ::core::marker::Send + ::core::marker::Sync>() {}
+            a::<Rule>();
+            a::<RuleKind>();
+            a::<#parser>();
+            #parse_node
+        };
+    }
+}
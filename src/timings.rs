@@ -0,0 +1,55 @@
+//! Expansion-time phase timings, reported when `ENUM_DISPATCH_PEST_TIMINGS` is set.
+
+use proc_macro2::Ident;
+use std::time::{Duration, Instant};
+
+/// set (to anything but `0`) to print the time spent in every phase of each expansion to stderr.
+const TIMINGS: &str = "ENUM_DISPATCH_PEST_TIMINGS";
+
+/// Times the phases of one expansion.
+///
+/// When the variable is unset, no clock is read and nothing is recorded, every [`PhaseTimings::phase`]
+/// only checks an `Option`. The timings never influence the emitted tokens.
+pub(crate) struct PhaseTimings {
+    /// start of the current phase, `None` when disabled.
+    start: Option<Instant>,
+    phases: Vec<(&'static str, Duration)>,
+}
+
+impl PhaseTimings {
+    pub fn new() -> Self {
+        let enabled = std::env::var_os(TIMINGS).is_some_and(|value| value != "0");
+        Self {
+            start: enabled.then(Instant::now),
+            phases: Vec::new(),
+        }
+    }
+
+    /// ends the current phase as `name`, the next phase starts now.
+    pub fn phase(&mut self, name: &'static str) {
+        if let Some(start) = &mut self.start {
+            let now = Instant::now();
+            self.phases.push((name, now - *start));
+            *start = now;
+        }
+    }
+
+    /// prints the one-line report of the expansion of `parser` to stderr, if enabled.
+    pub fn report(&self, parser: &Ident, grammar: &str, rules: usize) {
+        if self.start.is_none() {
+            return;
+        }
+        let ms = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        let phases = self
+            .phases
+            .iter()
+            .map(|(name, duration)| format!("{name} {:.3}ms", ms(*duration)))
+            .collect::<Vec<_>>()
+            .join(", ");
+        let total = self.phases.iter().map(|(_, duration)| *duration).sum();
+        eprintln!(
+            "enum_dispatch_pest_parser: {parser} (`{grammar}`, {rules} rules): {phases}, total {:.3}ms",
+            ms(total)
+        );
+    }
+}
@@ -0,0 +1,59 @@
+//! Generation of `tokens()`, a flat view over the leaves of a parse tree.
+
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Visibility;
+
+pub(crate) fn tokens_generator(vis: &Visibility) -> TokenStream {
+    quote! {
+        /// A matched leaf rule, as yielded by [`tokens`].
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        #vis struct Token<'i> {
+            pub kind: RuleKind,
+            pub text: &'i str,
+            pub span: ::pest::Span<'i>,
+        }
+
+        /// Iterator over the leaves of a parse tree, see [`tokens`].
+        #vis struct Tokens<'i, 'k> {
+            stack: Vec<::pest::iterators::Pairs<'i, Rule>>,
+            whole: &'k [RuleKind],
+        }
+
+        impl<'i, 'k> Iterator for Tokens<'i, 'k> {
+            type Item = Token<'i>;
+
+            fn next(&mut self) -> Option<Self::Item> {
+                loop {
+                    let Some(pair) = self.stack.last_mut()?.next() else {
+                        self.stack.pop();
+                        continue;
+                    };
+                    let mut inner = pair.clone().into_inner();
+                    if inner.peek().is_none() || self.whole.contains(&pair.as_rule().kind()) {
+                        return Some(Token {
+                            kind: pair.as_rule().kind(),
+                            text: pair.as_str(),
+                            span: pair.as_span(),
+                        });
+                    }
+                    self.stack.push(inner);
+                }
+            }
+        }
+
+        /// Returns the leaves of the parse tree (pairs without inner pairs, including `EOI`) in source order.
+        #vis fn tokens(pairs: ::pest::iterators::Pairs<'_, Rule>) -> Tokens<'_, 'static> {
+            tokens_with(pairs, &[])
+        }
+
+        /// Same as [`tokens`], but pairs of the `whole` kinds are yielded as one token instead of their leaves,
+        /// e.g. to keep compound-atomic rules in one piece.
+        #vis fn tokens_with<'i, 'k>(pairs: ::pest::iterators::Pairs<'i, Rule>, whole: &'k [RuleKind]) -> Tokens<'i, 'k> {
+            Tokens {
+                stack: vec![pairs],
+                whole,
+            }
+        }
+    }
+}
@@ -0,0 +1,146 @@
+//! Generation of `ParseNode` and `parse_tree()`, an owned copy of a parse tree.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Visibility;
+
+/// `Pairs` borrow the input and share one queue of tokens, so they can neither outlive the input nor be
+/// restructured. `parse_tree()` copies them into plain nested nodes; the text of each node is optional since
+/// it repeats the text of all its ancestors, which dominates the memory of deep trees.
+///
+/// `Display` prints a node as an outline, one rule per line, indented by its depth. Nodes built without text
+/// print their rules only; the width of the formatter (`{:4}`) sets the indentation of one level, and the
+/// alternate flag (`{:#}`) leaves the text out of a tree built with it.
+///
+/// With `max_depth`, `parse_tree()` builds the nodes with `from_pairs_limited()`, and reports a pair nested too
+/// deep as a `pest` error at that pair, so its signature stays the same.
+pub(crate) fn parse_tree_generator(
+    vis: &Visibility,
+    parser: &Ident,
+    max_depth: Option<usize>,
+) -> TokenStream {
+    let limited = max_depth.map(|_| {
+        quote! {
+            impl ParseNode {
+                /// Same as `from_pairs()`, but fails on the first pair nested more than `max_depth` levels deep,
+                /// `pairs` being at depth 1, instead of descending into it.
+                #vis fn from_pairs_limited(
+                    pairs: ::pest::iterators::Pairs<'_, Rule>,
+                    text: bool,
+                    max_depth: usize,
+                ) -> ::std::result::Result<Vec<Self>, DepthError> {
+                    fn build(
+                        pairs: ::pest::iterators::Pairs<'_, Rule>,
+                        text: bool,
+                        depth: usize,
+                        max_depth: usize,
+                    ) -> ::std::result::Result<Vec<ParseNode>, DepthError> {
+                        pairs
+                            .filter(|pair| pair.as_rule() != RuleKind::EOI)
+                            .map(|pair| {
+                                if depth > max_depth {
+                                    return Err(DepthError::new(&pair, max_depth));
+                                }
+                                Ok(ParseNode {
+                                    rule: pair.as_rule().kind(),
+                                    text: if text { pair.as_str().to_owned() } else { String::new() },
+                                    children: build(pair.into_inner(), text, depth + 1, max_depth)?,
+                                })
+                            })
+                            .collect()
+                    }
+
+                    build(pairs, text, 1, max_depth)
+                }
+            }
+        }
+    });
+    let from_pairs = match max_depth {
+        Some(max_depth) => quote! {
+            ParseNode::from_pairs_limited(pairs, text, #max_depth).map_err(|error| {
+                ::pest::error::Error::new_from_pos(
+                    ::pest::error::ErrorVariant::CustomError {
+                        message: error.to_string(),
+                    },
+                    ::pest::Position::new(input, error.offset).expect("the pair is in the input"),
+                )
+            })
+        },
+        None => quote!(Ok(ParseNode::from_pairs(pairs, text))),
+    };
+    quote! {
+        /// An owned node of a parse tree, as returned by `parse_tree()`.
+        #[derive(Clone, Debug, Eq, Hash, PartialEq)]
+        #vis struct ParseNode {
+            pub rule: RuleKind,
+            /// the matched text, empty if the tree was built without text.
+            pub text: String,
+            pub children: Vec<ParseNode>,
+        }
+
+        impl ParseNode {
+            /// Copies `pairs` and all their descendants (skipping `EOI`), with or without their text.
+            #vis fn from_pairs(pairs: ::pest::iterators::Pairs<'_, Rule>, text: bool) -> Vec<Self> {
+                pairs
+                    .filter(|pair| pair.as_rule() != RuleKind::EOI)
+                    .map(|pair| Self {
+                        rule: pair.as_rule().kind(),
+                        text: if text { pair.as_str().to_owned() } else { String::new() },
+                        children: Self::from_pairs(pair.into_inner(), text),
+                    })
+                    .collect()
+            }
+
+            /// Writes this node at `depth`, then its children one level deeper.
+            fn fmt_outline(
+                &self,
+                f: &mut ::core::fmt::Formatter<'_>,
+                depth: usize,
+                indent: usize,
+            ) -> ::core::fmt::Result {
+                write!(f, "{:width$}{:?}", "", self.rule, width = depth * indent)?;
+                if !self.text.is_empty() && !f.alternate() {
+                    write!(f, " {:?}", self.text)?;
+                }
+                writeln!(f)?;
+                self.children
+                    .iter()
+                    .try_for_each(|child| child.fmt_outline(f, depth + 1, indent))
+            }
+        }
+
+        /// Prints the node and its descendants as an outline, e.g. `Command "@a(1)"` with its children on the
+        /// next lines, indented by 2 spaces per level or the width of the formatter (`{:4}`); `{:#}` omits the
+        /// text.
+        impl ::core::fmt::Display for ParseNode {
+            fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+                let indent = f.width().unwrap_or(2);
+                self.fmt_outline(f, 0, indent)
+            }
+        }
+
+        // the error is `pest`'s, which the crate using the macro cannot box.
+        #[allow(clippy::result_large_err)]
+        impl #parser {
+            /// Parses `input` with `rule` and returns the whole parse tree as owned nodes.
+            #vis fn parse_tree(
+                rule: impl Into<Rule>,
+                input: &str,
+            ) -> ::std::result::Result<Vec<ParseNode>, ::pest::error::Error<Rule>> {
+                Self::parse_tree_with(rule, input, true)
+            }
+
+            /// Same as `parse_tree()`, but the `text` of the nodes is only captured if `text` is set.
+            #vis fn parse_tree_with(
+                rule: impl Into<Rule>,
+                input: &str,
+                text: bool,
+            ) -> ::std::result::Result<Vec<ParseNode>, ::pest::error::Error<Rule>> {
+                let pairs = <#parser as ::pest::Parser<Rule>>::parse(rule.into(), input)?;
+                #from_pairs
+            }
+        }
+
+        #limited
+    }
+}
@@ -0,0 +1,48 @@
+//! Generation of `validate()`, checking sample inputs against the rules of the grammar.
+
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::Visibility;
+
+/// `pest` accepts a match of a prefix of the input, which would let a grammar regression slip through a table
+/// of known-good samples. A sample therefore only passes if its rule matches it entirely.
+pub(crate) fn validate_generator(vis: &Visibility, parser: &Ident) -> TokenStream {
+    quote! {
+        impl #parser {
+            /// Parses every sample with the rule of its kind, and returns all failed samples with their errors.
+            ///
+            /// A sample passes if the rule matches the whole sample; a partial match fails with an error at its
+            /// end. Samples of silent rules produce no pairs, so they only need to parse.
+            #vis fn validate(
+                samples: &[(RuleKind, &str)],
+            ) -> ::std::result::Result<(), Vec<(RuleKind, ::pest::error::Error<Rule>)>> {
+                let failures = samples
+                    .iter()
+                    .filter_map(|&(kind, input)| {
+                        let error = match <#parser as ::pest::Parser<Rule>>::parse(Rule::from(kind), input) {
+                            Ok(pairs) => {
+                                let end = pairs.last()?.as_span().end();
+                                if end == input.len() {
+                                    return None;
+                                }
+                                ::pest::error::Error::new_from_pos(
+                                    ::pest::error::ErrorVariant::CustomError {
+                                        message: format!("`{kind:?}` does not match the whole sample"),
+                                    },
+                                    ::pest::Position::new(input, end).expect("end of a pair lies within the input"),
+                                )
+                            }
+                            Err(error) => error,
+                        };
+                        Some((kind, error))
+                    })
+                    .collect::<Vec<_>>();
+                if failures.is_empty() {
+                    Ok(())
+                } else {
+                    Err(failures)
+                }
+            }
+        }
+    }
+}
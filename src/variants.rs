@@ -0,0 +1,164 @@
+//! Generation of `all_variants!`, an exhaustive `match` over `RuleKind` whose arms may share a body.
+
+use proc_macro2::{Span, TokenStream};
+use quote::{quote, quote_spanned};
+use syn::{
+    braced,
+    ext::IdentExt,
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Arm, Expr, Ident, Pat, Token,
+};
+
+/// Like `match_pairs!`, the generated `all_variants!` only bakes in the rules, in definition order, and forwards
+/// to the hidden `__all_variants!` of this crate, which writes the `match`. It is re-exported with `pub(crate) use`.
+pub(crate) fn all_variants_generator(rules: &[Ident]) -> TokenStream {
+    quote! {
+        /// Matches a `RuleKind` exhaustively, e.g.
+        /// `all_variants!(kind, { Statement => handle(), Command | Arguments => skip(), _rest => default() })`.
+        ///
+        /// An arm lists one or more rules, `_rest` stands for the rules of no other arm, and is expanded into
+        /// them rather than into a `_` wildcard. Without `_rest`, a rule added to the grammar is reported as
+        /// not covered until it is given an arm.
+        #[allow(unused_macros)]
+        macro_rules! __all_variants_rules {
+            ($($input:tt)*) => {
+                ::enum_dispatch_pest_parser::__all_variants! { [#(#rules)*] $($input)* }
+            };
+        }
+
+        #[allow(unused_imports)]
+        pub(crate) use __all_variants_rules as all_variants;
+    }
+}
+
+/// The input of `__all_variants!`: the rules baked in by the generated `all_variants!`, then the matched kind
+/// and the arms given by the user.
+pub(crate) struct AllVariantsInput {
+    rules: Vec<Ident>,
+    kind: Expr,
+    arms: Vec<Arm>,
+}
+
+impl Parse for AllVariantsInput {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let rules;
+        syn::bracketed!(rules in input);
+        let mut known = Vec::new();
+        while !rules.is_empty() {
+            known.push(rules.call(Ident::parse_any)?);
+        }
+        let kind = input.parse()?;
+        input.parse::<Token![,]>()?;
+        let content;
+        braced!(content in input);
+        let mut arms = Vec::new();
+        while !content.is_empty() {
+            arms.push(content.parse()?);
+        }
+        input.parse::<Option<Token![,]>>()?;
+        Ok(Self {
+            rules: known,
+            kind,
+            arms,
+        })
+    }
+}
+
+/// Expands `__all_variants!` to a `match` on the kind, with the rules of every arm as `RuleKind` patterns and
+/// `_rest` replaced by the rules no other arm names. The `match` has no wildcard, so a rule covered by no arm
+/// is reported by `rustc` as a non-exhaustive pattern.
+pub(crate) fn all_variants_expand(input: AllVariantsInput) -> TokenStream {
+    let mut covered = vec![false; input.rules.len()];
+    let mut arms = Vec::new();
+    let mut rest = None;
+    for arm in &input.arms {
+        if let Some((_, guard)) = &arm.guard {
+            return compile_error(
+                guard.span(),
+                "`all_variants!` arms cannot have guards, they would make the match non-exhaustive",
+            );
+        }
+        let names = match arm_rules(&arm.pat) {
+            Ok(names) => names,
+            Err(span) => {
+                return compile_error(
+                    span,
+                    "expected rule names separated by `|`, or `_rest` for the other rules",
+                )
+            }
+        };
+        if let [name] = names.as_slice() {
+            if name == "_rest" {
+                if rest.is_some() {
+                    return compile_error(name.span(), "`_rest` is given more than once");
+                }
+                rest = Some(&arm.body);
+                continue;
+            }
+        }
+        let mut patterns = Vec::new();
+        for name in names {
+            let Some(index) = input
+                .rules
+                .iter()
+                .position(|rule| rule.unraw() == name.unraw())
+            else {
+                let message = format!("`{}` is not a rule of the grammar", name.unraw());
+                return compile_error(name.span(), &message);
+            };
+            if std::mem::replace(&mut covered[index], true) {
+                let message = format!("`{}` is in more than one arm", name.unraw());
+                return compile_error(name.span(), &message);
+            }
+            let rule = &input.rules[index];
+            patterns.push(quote_spanned!(name.span()=> RuleKind::#rule));
+        }
+        let body = &arm.body;
+        arms.push(quote!(#(#patterns)|* => #body));
+    }
+    if let Some(body) = rest {
+        let patterns = input
+            .rules
+            .iter()
+            .zip(&covered)
+            .filter(|(_, covered)| !**covered)
+            .map(|(rule, _)| quote!(RuleKind::#rule))
+            .collect::<Vec<_>>();
+        // every rule has an arm already, an empty `_rest` is left out rather than matching nothing.
+        if !patterns.is_empty() {
+            arms.push(quote!(#(#patterns)|* => #body));
+        }
+    }
+    let kind = &input.kind;
+    quote! {
+        match #kind {
+            #(#arms,)*
+        }
+    }
+}
+
+/// returns the rule names of the pattern of an arm, `Rule` or `RuleA | RuleB`, or the span of what is not one.
+fn arm_rules(pat: &Pat) -> Result<Vec<Ident>, Span> {
+    let cases = match pat {
+        Pat::Or(or) if or.leading_vert.is_none() => or.cases.iter().collect::<Vec<_>>(),
+        pat => vec![pat],
+    };
+    cases
+        .into_iter()
+        .map(|case| match case {
+            Pat::Ident(ident)
+                if ident.by_ref.is_none()
+                    && ident.mutability.is_none()
+                    && ident.subpat.is_none() =>
+            {
+                Ok(ident.ident.clone())
+            }
+            case => Err(case.span()),
+        })
+        .collect()
+}
+
+fn compile_error(span: Span, message: &str) -> TokenStream {
+    quote_spanned!(span=> ::core::compile_error!(#message))
+}
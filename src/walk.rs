@@ -0,0 +1,166 @@
+//! Generation of the tree walking helpers.
+
+use crate::coverage::record_coverage;
+use crate::rule_timings::time_handler;
+use proc_macro2::{Ident, TokenStream};
+use quote::quote;
+use syn::{Path, Visibility};
+
+/// `walk_into()` folds a parse tree into an accumulator implementing the interface: the `walk_method`
+/// takes `&mut self` (the accumulator) and the visited pair.
+///
+/// With `recover`, the method returns a `Result<(), E>` instead. An error is recorded with the span of
+/// the failed pair, whose subtree is skipped, and the walk goes on with the next sibling, so all errors of
+/// a document are reported at once. `E` is boxed since the walker cannot name it, or converted into the
+/// `error_type` if given (`E: Into<ScriptError>`), so a project without `dyn Error` keeps its own type.
+///
+/// Both forms are shims over a nested function taking the method as `&mut dyn FnMut`, so the traversal is
+/// compiled once rather than once per accumulator type.
+pub(crate) fn walk_into_generator(
+    vis: &Visibility,
+    interface: &Path,
+    method: &Ident,
+    recover: bool,
+    error: Option<&Path>,
+) -> TokenStream {
+    let doc = format!(
+        " Visits every pair of `pairs` in pre-order (skipping `EOI`) and hands it to `{}::{method}` of `acc`.",
+        quote!(#interface)
+    );
+    let record = record_coverage(quote!(pair));
+    let visit = time_handler(quote!(pair), quote!(visit(&pair)));
+    if recover {
+        let error = match error {
+            Some(error) => quote!(#error),
+            None => quote!(Box<dyn ::std::error::Error + Send + Sync>),
+        };
+        return quote! {
+            #[doc = #doc]
+            ///
+            /// A pair for which the method fails is not descended into. Instead, its span and the error are
+            /// collected, and the walk continues with the next sibling.
+            #vis fn walk_into<'i, A: #interface>(
+                pairs: ::pest::iterators::Pairs<'i, Rule>,
+                acc: &mut A,
+            ) -> Vec<(::pest::Span<'i>, #error)> {
+                fn walk<'i>(
+                    pairs: ::pest::iterators::Pairs<'i, Rule>,
+                    visit: &mut dyn FnMut(&::pest::iterators::Pair<'i, Rule>) -> Result<(), #error>,
+                ) -> Vec<(::pest::Span<'i>, #error)> {
+                    let mut errors = Vec::new();
+                    let mut stack = vec![pairs];
+                    while let Some(pairs) = stack.last_mut() {
+                        let Some(pair) = pairs.next() else {
+                            stack.pop();
+                            continue;
+                        };
+                        if pair.as_rule() == RuleKind::EOI {
+                            continue;
+                        }
+                        #record
+                        if pair.as_rule().kind().is_transparent() {
+                            stack.push(pair.into_inner());
+                            continue;
+                        }
+                        match #visit {
+                            Ok(()) => stack.push(pair.into_inner()),
+                            Err(error) => errors.push((pair.as_span(), error)),
+                        }
+                    }
+                    errors
+                }
+
+                walk(pairs, &mut |pair| #interface::#method(acc, pair).map_err(Into::into))
+            }
+        };
+    }
+    quote! {
+        #[doc = #doc]
+        #vis fn walk_into<'i, A: #interface>(pairs: ::pest::iterators::Pairs<'i, Rule>, acc: &mut A) {
+            fn walk<'i>(
+                pairs: ::pest::iterators::Pairs<'i, Rule>,
+                visit: &mut dyn FnMut(&::pest::iterators::Pair<'i, Rule>),
+            ) {
+                for pair in pairs.flatten() {
+                    if pair.as_rule() == RuleKind::EOI {
+                        continue;
+                    }
+                    #record
+                    if !pair.as_rule().kind().is_transparent() {
+                        #visit;
+                    }
+                }
+            }
+
+            walk(pairs, &mut |pair| #interface::#method(acc, pair))
+        }
+    }
+}
+
+/// `walk_until()` and `walk_until_with()` are pre-order traversals driven by `ControlFlow`, so
+/// search-style analyses can stop at the first match (and, with the latter, prune subtrees).
+///
+/// The traversal takes `f` as `&mut dyn FnMut` and keeps the break value aside, so it is compiled once
+/// whatever `B` and the closure are.
+pub(crate) fn walk_until_generator(vis: &Visibility) -> TokenStream {
+    let record = record_coverage(quote!(pair));
+    quote! {
+        /// Whether [`walk_until_with`] should visit the children of the current pair.
+        #[derive(Clone, Copy, Debug, Eq, PartialEq)]
+        #vis enum Descend {
+            /// visit the children of the current pair.
+            Into,
+            /// skip the children of the current pair and continue with its next sibling.
+            Skip,
+        }
+
+        /// Visits every pair of `pairs` in pre-order, stopping as soon as `f` breaks.
+        ///
+        /// Returns the break value, or `None` if the whole tree was visited.
+        #vis fn walk_until<'i, B>(
+            pairs: ::pest::iterators::Pairs<'i, Rule>,
+            mut f: impl FnMut(&::pest::iterators::Pair<'i, Rule>) -> ::core::ops::ControlFlow<B>,
+        ) -> Option<B> {
+            walk_until_with(pairs, |pair| f(pair).map_continue(|()| Descend::Into))
+        }
+
+        /// Same as [`walk_until`], but `f` also decides whether the children of each pair are visited.
+        #vis fn walk_until_with<'i, B>(
+            pairs: ::pest::iterators::Pairs<'i, Rule>,
+            mut f: impl FnMut(&::pest::iterators::Pair<'i, Rule>) -> ::core::ops::ControlFlow<B, Descend>,
+        ) -> Option<B> {
+            fn walk<'i>(
+                pairs: ::pest::iterators::Pairs<'i, Rule>,
+                f: &mut dyn FnMut(&::pest::iterators::Pair<'i, Rule>) -> ::core::ops::ControlFlow<(), Descend>,
+            ) {
+                let mut stack = vec![pairs];
+                while let Some(pairs) = stack.last_mut() {
+                    let Some(pair) = pairs.next() else {
+                        stack.pop();
+                        continue;
+                    };
+                    #record
+                    if pair.as_rule().kind().is_transparent() {
+                        stack.push(pair.into_inner());
+                        continue;
+                    }
+                    match f(&pair) {
+                        ::core::ops::ControlFlow::Break(()) => return,
+                        ::core::ops::ControlFlow::Continue(Descend::Into) => stack.push(pair.into_inner()),
+                        ::core::ops::ControlFlow::Continue(Descend::Skip) => {}
+                    }
+                }
+            }
+
+            let mut value = None;
+            walk(pairs, &mut |pair| match f(pair) {
+                ::core::ops::ControlFlow::Break(b) => {
+                    value = Some(b);
+                    ::core::ops::ControlFlow::Break(())
+                }
+                ::core::ops::ControlFlow::Continue(descend) => ::core::ops::ControlFlow::Continue(descend),
+            });
+            value
+        }
+    }
+}
@@ -0,0 +1,24 @@
+//! Properties of the kinds checked on the `quickcheck::Arbitrary` of the `arbitrary` feature.
+
+use enum_dispatch::enum_dispatch;
+use quickcheck::{quickcheck, Arbitrary};
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+language_parser!();
+
+quickcheck! {
+    fn converts_through_rule(kind: RuleKind) -> bool {
+        RuleKind::from(Rule::from(kind)) == kind
+    }
+
+    fn is_found_at_its_index(kind: RuleKind) -> bool {
+        RuleKind::ALL[kind.index()] == kind
+    }
+
+    fn shrinks_towards_the_first_kind(kind: RuleKind) -> bool {
+        kind.shrink().eq(RuleKind::ALL[..kind.index()].iter().copied())
+    }
+}
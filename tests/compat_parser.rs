@@ -0,0 +1,66 @@
+//! `CompatParser`, parsing with a `RuleKind` and reporting errors over `RuleKind`.
+
+use enum_dispatch::enum_dispatch;
+use pest::error::{ErrorVariant, InputLocation};
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+language_parser!(generate = "compat");
+
+#[test]
+fn parses_with_a_kind() {
+    let mut pairs = CompatParser::parse_kind(RuleKind::Command, "@f(1, x)").unwrap();
+    let command = pairs.next().unwrap();
+    assert!(command.as_rule() == RuleKind::Command);
+    assert_eq!(command.as_str(), "@f(1, x)");
+    let kinds = command
+        .into_inner()
+        .flatten()
+        .map(|pair| pair.as_rule().kind())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            RuleKind::Identifier,
+            RuleKind::Arguments,
+            RuleKind::Argument,
+            RuleKind::Number,
+            RuleKind::Argument,
+            RuleKind::Identifier
+        ]
+    );
+}
+
+#[test]
+fn converts_the_errors_to_kinds() {
+    let input = "@f(1,)";
+    let error = CompatParser::parse_kind(RuleKind::Command, input).unwrap_err();
+    let error = CompatParser::kind_error(error.with_path("script"), input);
+    assert_eq!(error.location, InputLocation::Pos(5));
+    assert_eq!(error.path(), Some("script"));
+    match error.variant {
+        ErrorVariant::ParsingError { positives, .. } => assert_eq!(positives, [RuleKind::Argument]),
+        ErrorVariant::CustomError { message } => panic!("unexpected custom error {message:?}"),
+    }
+}
+
+#[test]
+fn keeps_custom_errors() {
+    let input = "@f()";
+    let error = pest::error::Error::new_from_span(
+        ErrorVariant::CustomError {
+            message: "unknown command".into(),
+        },
+        pest::Span::new(input, 1, 2).unwrap(),
+    );
+    let error = CompatParser::kind_error(error, input);
+    assert_eq!(error.location, InputLocation::Span((1, 2)));
+    assert_eq!(
+        error.variant,
+        ErrorVariant::CustomError {
+            message: "unknown command".into()
+        }
+    );
+}
@@ -0,0 +1,47 @@
+//! The parser of a workspace, generated once in a crate of its own and depended on by the crates using it.
+//!
+//! Everything the macro generates is `pub` like the parser struct, and refers to the other generated items by
+//! `crate::` paths, which resolve in this crate wherever the items are used from. The interface is declared here
+//! too: the rule structs are foreign to the other crates, which cannot implement a trait for them.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::iterators::Pair;
+
+/// `pest` is re-exported, so the crates using the parser name its types (`Parser`, `Pairs`, ...) in the same
+/// version, without depending on it themselves.
+pub use pest;
+
+/// Implemented by the rule structs below, and by the accumulators of `walk_into()` in the other crates.
+#[enum_dispatch]
+pub trait LanguageInterface {
+    /// whether the rule is a statement.
+    fn is_statement(&self) -> bool {
+        false
+    }
+
+    /// collects `pair`, called by `walk_into()` on the accumulator.
+    fn collect(&mut self, _pair: &Pair<'_, Rule>) {}
+}
+
+#[pest_parser(
+    grammar = "grammars/language.pest",
+    interface = "LanguageInterface",
+    walk_method = "collect",
+    generate = "iter, name, compat, tokens, docs, walk_until, tree, validate, parse, context, parse_rule"
+)]
+pub struct LanguageParser;
+
+impl LanguageInterface for Statement {
+    fn is_statement(&self) -> bool {
+        true
+    }
+}
+
+macro_rules! rule_structs {
+    ($($rule:ident),*) => {
+        $(impl LanguageInterface for $rule {})*
+    };
+}
+
+rule_structs!(EOI, WHITESPACE, Script, Command, Arguments, Argument, Strings, Number, Identifier);
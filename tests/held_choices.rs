@@ -0,0 +1,97 @@
+//! Long choices of literals, which are held out of `pest`'s optimizer, parse like the same choices split in short
+//! ones, which are not.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(
+    grammar_inline = r#"
+WHITESPACE = _{ " " }
+keyword    = @{ ("kw0" | "kw1" | "kw2" | "kw3" | "kw4" | "kw5" | "kw6" | "kw7" | "kw8" | "kw9" | "kw10" | "kw11" | "kw12" | "kw13" | "kw14" | "kw15" | "kw16" | "kw17" | "kw18" | "kw19" | "kw20" | "kw21" | "kw22" | "kw23" | "kw24" | "kw25" | "kw26" | "kw27" | "kw28" | "kw29" | "kw30" | "kw31" | "kw32" | "kw33" | "kw34" | "kw35" | "kw36" | "kw37" | "kw38" | "kw39" | "kw40" | "kw41" | "kw42" | "kw43" | "kw44" | "kw45" | "kw46" | "kw47" | "kw48" | "kw49" | "kw50" | "kw51" | "kw52" | "kw53" | "kw54" | "kw55" | "kw56" | "kw57" | "kw58" | "kw59" | "kw60" | "kw61" | "kw62" | "kw63" | "kw64" | "kw65" | "kw66" | "kw67" | "kw68" | "kw69") ~ !ASCII_ALPHANUMERIC }
+keywords   = { SOI ~ (keyword ~ ",")* ~ keyword ~ EOI }
+text       = @{ (!("kw0" | "kw1" | "kw2" | "kw3" | "kw4" | "kw5" | "kw6" | "kw7" | "kw8" | "kw9" | "kw10" | "kw11" | "kw12" | "kw13" | "kw14" | "kw15" | "kw16" | "kw17" | "kw18" | "kw19" | "kw20" | "kw21" | "kw22" | "kw23" | "kw24" | "kw25" | "kw26" | "kw27" | "kw28" | "kw29" | "kw30" | "kw31" | "kw32" | "kw33" | "kw34" | "kw35" | "kw36" | "kw37" | "kw38" | "kw39" | "kw40" | "kw41" | "kw42" | "kw43" | "kw44" | "kw45" | "kw46" | "kw47" | "kw48" | "kw49" | "kw50" | "kw51" | "kw52" | "kw53" | "kw54" | "kw55" | "kw56" | "kw57" | "kw58" | "kw59" | "kw60" | "kw61" | "kw62" | "kw63" | "kw64" | "kw65" | "kw66" | "kw67" | "kw68" | "kw69") ~ ANY)* }
+"#,
+    interface = "ParserInterface",
+    namespaced = "true"
+)]
+pub struct HeldParser;
+
+#[pest_parser(
+    grammar_inline = r#"
+WHITESPACE = _{ " " }
+low        = _{ "kw0" | "kw1" | "kw2" | "kw3" | "kw4" | "kw5" | "kw6" | "kw7" | "kw8" | "kw9" | "kw10" | "kw11" | "kw12" | "kw13" | "kw14" | "kw15" | "kw16" | "kw17" | "kw18" | "kw19" | "kw20" | "kw21" | "kw22" | "kw23" | "kw24" | "kw25" | "kw26" | "kw27" | "kw28" | "kw29" | "kw30" | "kw31" | "kw32" | "kw33" | "kw34" }
+high       = _{ "kw35" | "kw36" | "kw37" | "kw38" | "kw39" | "kw40" | "kw41" | "kw42" | "kw43" | "kw44" | "kw45" | "kw46" | "kw47" | "kw48" | "kw49" | "kw50" | "kw51" | "kw52" | "kw53" | "kw54" | "kw55" | "kw56" | "kw57" | "kw58" | "kw59" | "kw60" | "kw61" | "kw62" | "kw63" | "kw64" | "kw65" | "kw66" | "kw67" | "kw68" | "kw69" }
+keyword    = @{ (low | high) ~ !ASCII_ALPHANUMERIC }
+keywords   = { SOI ~ (keyword ~ ",")* ~ keyword ~ EOI }
+text       = @{ (!(low | high) ~ ANY)* }
+"#,
+    interface = "ParserInterface",
+    namespaced = "true"
+)]
+pub struct SplitParser;
+
+rule_structs!(ParserInterface for held_parser: EOI, WHITESPACE, keyword, keywords, text);
+rule_structs!(
+    ParserInterface for split_parser: EOI, WHITESPACE, low, high, keyword, keywords, text
+);
+
+/// the rule names and spans of the pairs of a parse, or the position of its error.
+type Parsed = Result<Vec<(String, usize, usize)>, usize>;
+
+/// returns the spans of the pairs `HeldParser` and `SplitParser` parse `input` into, with the names of their rules.
+fn parse_both(rule: &str, input: &str) -> [Parsed; 2] {
+    fn spans<R: pest::RuleType>(
+        parsed: Result<pest::iterators::Pairs<'_, R>, pest::error::Error<R>>,
+    ) -> Parsed {
+        match parsed {
+            Ok(pairs) => Ok(pairs
+                .flatten()
+                .map(|pair| {
+                    let name = format!("{:?}", pair.as_rule());
+                    let name = name.split('(').next().unwrap().to_string();
+                    (name, pair.as_span().start(), pair.as_span().end())
+                })
+                .collect()),
+            Err(error) => Err(match error.location {
+                pest::error::InputLocation::Pos(pos) => pos,
+                pest::error::InputLocation::Span((start, _)) => start,
+            }),
+        }
+    }
+    let held = held_parser::RuleKind::from_name(rule).unwrap();
+    let split = split_parser::RuleKind::from_name(rule).unwrap();
+    [
+        spans(HeldParser::parse(held_parser::Rule::from(held), input)),
+        spans(SplitParser::parse(split_parser::Rule::from(split), input)),
+    ]
+}
+
+#[test]
+fn parses_like_the_optimized_choices() {
+    let inputs = [
+        ("keyword", "kw0"),
+        ("keyword", "kw69"),
+        ("keyword", "kw6"),
+        ("keyword", "kw70"),
+        ("keyword", "kw6x"),
+        ("keyword", "kw"),
+        ("keywords", "kw1, kw12,kw69 , kw35"),
+        ("keywords", "kw1, kw12,, kw35"),
+        ("keywords", "kw1 kw2"),
+        ("text", "some text before kw42 and after"),
+        ("text", "no keyword at all"),
+        ("text", "kw7"),
+    ];
+    for (rule, input) in inputs {
+        let [held, split] = parse_both(rule, input);
+        assert_eq!(held, split, "`{rule}` on {input:?}");
+    }
+}
@@ -0,0 +1,46 @@
+//! `Rule` and `RuleKind` compared with each other, in both directions.
+
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+language_parser!();
+
+#[test]
+fn compares_a_rule_with_a_kind() {
+    let rule = Rule::Command(Command);
+    assert!(rule == RuleKind::Command);
+    assert!(rule != RuleKind::Statement);
+}
+
+#[test]
+fn compares_a_kind_with_a_rule() {
+    let rule = Rule::Command(Command);
+    assert!(RuleKind::Command == rule);
+    assert!(RuleKind::Statement != rule);
+}
+
+#[test]
+fn compares_every_rule_with_its_kind_only() {
+    for rule in Rule::user_rules() {
+        for kind in RuleKind::ALL {
+            assert_eq!(*rule == kind, rule.kind() == kind);
+            assert_eq!(kind == *rule, rule.kind() == kind);
+        }
+    }
+}
+
+#[test]
+fn compares_the_rules_of_parsed_pairs() {
+    let pairs = LanguageParser::parse(Rule::Statement(Statement), "@f(1);").unwrap();
+    let rules = pairs
+        .flatten()
+        .map(|pair| pair.as_rule())
+        .collect::<Vec<_>>();
+    assert!(rules[0] == RuleKind::Statement);
+    assert!(RuleKind::Command == rules[1]);
+    assert!(rules.iter().any(|rule| *rule == RuleKind::Number));
+}
@@ -0,0 +1,41 @@
+//! Derives of another crate placed on `RuleKind` with `kind_derives`.
+
+use enum_dispatch::enum_dispatch;
+use strum::IntoEnumIterator;
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+language_parser!(
+    kind_derives = "strum::EnumIter, strum::Display",
+    generate = "name"
+);
+
+#[test]
+fn iterates_the_kinds_in_definition_order() {
+    assert!(RuleKind::iter().eq(RuleKind::ALL));
+}
+
+#[test]
+fn displays_the_kinds_by_name() {
+    let names = RuleKind::iter()
+        .map(|kind| kind.to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        names,
+        [
+            "EOI",
+            "WHITESPACE",
+            "Script",
+            "Statement",
+            "Command",
+            "Arguments",
+            "Argument",
+            "Strings",
+            "Number",
+            "Identifier"
+        ]
+    );
+    assert!(RuleKind::iter().all(|kind| kind.to_string() == kind.name()));
+}
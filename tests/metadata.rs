@@ -0,0 +1,118 @@
+//! The metadata tables of the `pest_meta` feature, computed while expanding the macro.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_rules;
+use std::path::Path;
+use std::process::Command;
+
+// every rule modifier, references under predicates and repetitions, and single and multi-line docs.
+#[pest_rules(
+    grammar_inline = r#"
+WHITESPACE = _{ " " }
+
+/// A list of items.
+list   = { SOI ~ item ~ ("," ~ item)* ~ EOI }
+/// An item,
+/// keyed.
+item   = ${ key ~ ":" ~ value }
+key    = @{ ASCII_ALPHA+ }
+value  = !{ number | &key ~ key | quoted }
+number = @{ ASCII_DIGIT+ }
+quoted = _{ "\"" ~ key ~ "\"" }
+"#,
+    generate = "iter, name, docs"
+)]
+pub struct MetaParser;
+
+#[test]
+fn lists_the_modifiers_in_definition_order() {
+    assert_eq!(
+        RULE_META,
+        [
+            ("WHITESPACE", "silent"),
+            ("list", "normal"),
+            ("item", "compound_atomic"),
+            ("key", "atomic"),
+            ("value", "non_atomic"),
+            ("number", "atomic"),
+            ("quoted", "silent")
+        ]
+    );
+}
+
+#[test]
+fn keeps_the_docs_of_the_rules() {
+    assert_eq!(doc_of(RuleKind::list), Some("A list of items."));
+    assert_eq!(doc_of(RuleKind::item), Some("An item,\nkeyed."));
+    assert_eq!(doc_of(RuleKind::key), None);
+}
+
+#[test]
+fn lists_the_references_of_the_rules() {
+    let children = |kind| Rule::children_of(kind).to_vec();
+    let none: [RuleKind; 0] = [];
+    assert_eq!(children(RuleKind::list), [RuleKind::item, RuleKind::EOI]);
+    assert_eq!(children(RuleKind::item), [RuleKind::key, RuleKind::value]);
+    // `key` is listed once, though referenced under the predicate and after it; the silent `quoted` is listed
+    // itself rather than looked through.
+    assert_eq!(
+        children(RuleKind::value),
+        [RuleKind::number, RuleKind::key, RuleKind::quoted]
+    );
+    assert_eq!(children(RuleKind::quoted), [RuleKind::key]);
+    assert_eq!(children(RuleKind::key), none);
+    assert_eq!(children(RuleKind::number), none);
+    assert_eq!(children(RuleKind::WHITESPACE), none);
+}
+
+/// `pest_meta` is run by the macro only, so it is not linked into a crate using the `pest_meta` feature.
+#[test]
+fn does_not_link_pest_meta() {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("metadata_fixture");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    let manifest = format!(
+        "[package]\n\
+         name = \"metadata_fixture\"\n\
+         version = \"0.0.0\"\n\
+         edition = \"2021\"\n\
+         publish = false\n\
+         \n\
+         [dependencies]\n\
+         enum_dispatch_pest_parser = {{ path = {:?}, features = [\"pest_meta\"] }}\n\
+         \n\
+         [workspace]\n",
+        env!("CARGO_MANIFEST_DIR")
+    );
+    std::fs::write(root.join("Cargo.toml"), manifest).unwrap();
+    std::fs::write(root.join("src/lib.rs"), "").unwrap();
+    std::fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.lock"),
+        root.join("Cargo.lock"),
+    )
+    .unwrap();
+
+    let tree = |edges: &str| {
+        let output = Command::new(std::env::var_os("CARGO").unwrap_or("cargo".into()))
+            .args([
+                "tree",
+                "--offline",
+                "--quiet",
+                "--prefix",
+                "none",
+                "--edges",
+                edges,
+            ])
+            .current_dir(&root)
+            .output()
+            .unwrap();
+        assert!(
+            output.status.success(),
+            "{}",
+            String::from_utf8_lossy(&output.stderr)
+        );
+        String::from_utf8(output.stdout).unwrap()
+    };
+    let links_pest_meta = |tree: &str| tree.lines().any(|line| line.starts_with("pest_meta "));
+    assert!(links_pest_meta(&tree("normal")));
+    assert!(!links_pest_meta(&tree("normal,no-proc-macro")));
+}
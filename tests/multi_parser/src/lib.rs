@@ -0,0 +1,91 @@
+//! Two parsers over two grammars in one crate, each in a module of its own.
+//!
+//! Both grammars have a rule `value`, so the rule structs, `enum Rule`, `RuleKind` and the helpers would collide
+//! next to each other. `JsonParser` is namespaced in `crate::json_parser` (named after the parser), `CsvParser`
+//! in the module given with `module`, and its interface is linked with `#[pest_dispatch]`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+use std::error::Error;
+
+/// Describes a JSON rule, dispatched through `json_parser::Rule`.
+#[enum_dispatch]
+pub trait JsonInterface {
+    fn describe(&self) -> &'static str {
+        "punctuation"
+    }
+}
+
+#[pest_parser(
+    grammar = "grammars/json.pest",
+    interface = "JsonInterface",
+    namespaced = "true",
+    generate = "iter, name, match_pairs"
+)]
+pub struct JsonParser;
+
+impl JsonInterface for json_parser::value {
+    fn describe(&self) -> &'static str {
+        "value"
+    }
+}
+
+impl JsonInterface for json_parser::number {
+    fn describe(&self) -> &'static str {
+        "number"
+    }
+}
+
+impl JsonInterface for json_parser::string {
+    fn describe(&self) -> &'static str {
+        "string"
+    }
+}
+
+impl JsonInterface for json_parser::document {}
+impl JsonInterface for json_parser::array {}
+impl JsonInterface for json_parser::WHITESPACE {}
+impl JsonInterface for json_parser::EOI {}
+
+/// Returns the items of a JSON array, destructured with the `match_pairs!` of `json_parser`.
+pub fn array_items(input: &str) -> Result<Vec<String>, Box<dyn Error>> {
+    use json_parser::{match_pairs, Rule};
+
+    let document = JsonParser::parse(Rule::document(json_parser::document), input)?
+        .next()
+        .unwrap();
+    match_pairs!(document => [value as value, EOI as _eoi]);
+    match_pairs!(value => [array as array]);
+    match_pairs!(array => [value* as items]);
+    Ok(items.iter().map(|item| item.as_str().to_string()).collect())
+}
+
+pub mod csv {
+    use enum_dispatch::enum_dispatch;
+    use enum_dispatch_pest_parser::{pest_dispatch, pest_rules};
+    use pest::iterators::Pair;
+    // `Rule`, and `CsvParserRule`, which `enum_dispatch` may implement the interface for next to the trait.
+    use records::*;
+
+    #[pest_rules(grammar = "grammars/csv.pest", module = "crate::csv::records")]
+    pub struct CsvParser;
+
+    /// Measures a CSV rule, dispatched through `records::Rule`.
+    #[pest_dispatch(rules = "CsvParser", namespaced = "true")]
+    pub trait CsvInterface {
+        fn width(&self, _pair: &Pair<'_, Rule>) -> usize {
+            0
+        }
+    }
+
+    impl CsvInterface for records::value {
+        fn width(&self, pair: &Pair<'_, Rule>) -> usize {
+            pair.as_str().len()
+        }
+    }
+
+    impl CsvInterface for records::file {}
+    impl CsvInterface for records::record {}
+    impl CsvInterface for records::EOI {}
+}
@@ -0,0 +1,67 @@
+//! The two parsers parse their inputs independently, each dispatching its own interface.
+
+use multi_parser::csv::{records, CsvInterface, CsvParser};
+use multi_parser::{json_parser, JsonInterface, JsonParser};
+use pest::Parser;
+
+#[test]
+fn json_dispatches_its_interface() {
+    let described = JsonParser::parse(
+        json_parser::Rule::document(json_parser::document),
+        "[1, \"a\"]",
+    )
+    .unwrap()
+    .flatten()
+    .map(|pair| pair.as_rule().describe())
+    .collect::<Vec<_>>();
+    assert_eq!(
+        described,
+        [
+            "punctuation",
+            "value",
+            "punctuation",
+            "value",
+            "number",
+            "value",
+            "string",
+            "punctuation"
+        ]
+    );
+}
+
+#[test]
+fn csv_dispatches_its_interface() {
+    let widths = CsvParser::parse(records::Rule::file(records::file), "ab,c\ndef")
+        .unwrap()
+        .flatten()
+        .map(|pair| pair.as_rule().width(&pair))
+        .collect::<Vec<_>>();
+    assert_eq!(widths, [0, 0, 2, 1, 0, 3, 0]);
+}
+
+#[test]
+fn rule_kinds_are_apart() {
+    assert_eq!(json_parser::RuleKind::value.name(), "value");
+    assert_eq!(records::RuleKind::value.name(), "value");
+    assert_eq!(json_parser::RuleKind::iter().count(), 7);
+    assert_eq!(records::RuleKind::iter().count(), 4);
+    assert_eq!(json_parser::RuleKind::from_name("record"), None);
+    assert!(records::RuleKind::from_name("array").is_none());
+}
+
+#[test]
+fn each_grammar_rejects_the_other() {
+    assert!(JsonParser::parse(json_parser::Rule::document(json_parser::document), "a,b").is_err());
+    assert!(CsvParser::parse(records::Rule::file(records::file), "[1]").is_ok());
+    assert!(CsvParser::parse(records::Rule::file(records::file), "").is_err());
+}
+
+#[test]
+fn helpers_are_namespaced() {
+    assert_eq!(
+        multi_parser::array_items("[2, \"b\"]").unwrap(),
+        ["2", "\"b\""]
+    );
+    let error = multi_parser::array_items("3").unwrap_err();
+    assert!(error.to_string().contains("array"), "{error}");
+}
@@ -0,0 +1,41 @@
+//! A crate of the workspace using the parser of `generated_parser`, which is not generated again here.
+//!
+//! The parser, its rules and helpers are used through their paths in `generated_parser`, and an accumulator of
+//! this crate implements the interface for `walk_into()`.
+
+use generated_parser::pest::{error::Error, iterators::Pair, Parser};
+use generated_parser::{walk_into, LanguageInterface, LanguageParser, Rule, RuleKind, Script};
+
+/// Collects the names of the commands of a script, in order.
+#[derive(Debug, Default)]
+pub struct CommandNames(pub Vec<String>);
+
+impl LanguageInterface for CommandNames {
+    fn collect(&mut self, pair: &Pair<'_, Rule>) {
+        if pair.as_rule() == RuleKind::Command {
+            let name = pair
+                .clone()
+                .into_inner()
+                .next()
+                .expect("a command has a name");
+            self.0.push(name.as_str().to_string());
+        }
+    }
+}
+
+/// Returns the names of the commands of `input`.
+pub fn command_names(input: &str) -> Result<Vec<String>, Box<Error<Rule>>> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), input).map_err(Box::new)?;
+    let mut names = CommandNames::default();
+    walk_into(pairs, &mut names);
+    Ok(names.0)
+}
+
+/// Returns the number of statements of `input`, dispatching the interface on every rule.
+pub fn statement_count(input: &str) -> Result<usize, Box<Error<Rule>>> {
+    let pairs = LanguageParser::parse(Rule::Script(Script), input).map_err(Box::new)?;
+    Ok(pairs
+        .flatten()
+        .filter(|pair| pair.as_rule().is_statement())
+        .count())
+}
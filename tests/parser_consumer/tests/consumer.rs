@@ -0,0 +1,69 @@
+//! The generated items of `generated_parser`, used from another crate.
+
+use generated_parser::pest::Parser;
+use generated_parser::{
+    dispatch_with_context, doc_of, tokens, CompatParser, LanguageParser, ParserExt, Rule, RuleKind,
+};
+use parser_consumer::{command_names, statement_count};
+
+const SCRIPT: &str = "@print(\"hi\");@exit(0);";
+
+#[test]
+fn walks_into_an_accumulator_of_this_crate() {
+    assert_eq!(command_names(SCRIPT).unwrap(), ["print", "exit"]);
+}
+
+#[test]
+fn dispatches_the_interface() {
+    assert_eq!(statement_count(SCRIPT).unwrap(), 2);
+}
+
+#[test]
+fn uses_the_kinds_and_their_names() {
+    assert_eq!(RuleKind::from_name("Statement"), Some(RuleKind::Statement));
+    assert_eq!(RuleKind::Statement.name(), "Statement");
+    assert_eq!(RuleKind::iter().count(), Rule::RULE_COUNT);
+    assert_eq!(doc_of(RuleKind::Number), None);
+}
+
+#[test]
+fn parses_with_the_generated_helpers() {
+    let pairs = RuleKind::Number.parse("42").unwrap();
+    assert_eq!(pairs.as_str(), "42");
+    let pairs = LanguageParser::parse_rule::<generated_parser::Identifier>("name").unwrap();
+    assert_eq!(pairs.as_str(), "name");
+    let pairs = CompatParser::parse_kind(RuleKind::Command, "@f()").unwrap();
+    assert_eq!(pairs.as_str(), "@f()");
+
+    let tree = LanguageParser::parse_tree(RuleKind::Script, SCRIPT).unwrap();
+    assert_eq!(tree[0].rule, RuleKind::Script);
+    assert_eq!(tree[0].children.len(), 2);
+    assert!(
+        LanguageParser::validate(&[(RuleKind::Number, "7"), (RuleKind::Identifier, "x1")]).is_ok()
+    );
+}
+
+#[test]
+fn walks_with_the_generated_walkers() {
+    let pairs = LanguageParser::parse(Rule::from(RuleKind::Script), SCRIPT).unwrap();
+    let leaves = tokens(pairs.clone())
+        .map(|token| token.kind)
+        .collect::<Vec<_>>();
+    assert_eq!(
+        leaves,
+        [
+            RuleKind::Identifier,
+            RuleKind::Strings,
+            RuleKind::Identifier,
+            RuleKind::Number,
+            RuleKind::EOI
+        ]
+    );
+    let mut deepest = 0;
+    dispatch_with_context(pairs, |context| {
+        deepest = deepest.max(context.depth);
+        Ok::<(), ()>(())
+    })
+    .unwrap();
+    assert_eq!(deepest, 5);
+}
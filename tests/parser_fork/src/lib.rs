@@ -0,0 +1,27 @@
+//! A stand-in for a fork of `pest_derive` (or a derive wrapping it), given to `parser_derive` by
+//! `examples/parser_derive.rs`.
+//!
+//! It derives the parser with `pest_generator`, like `pest_derive`, and marks it with `FORK`, so the example can
+//! tell its parser from one generated by `enum_dispatch_pest_parser`.
+
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::DeriveInput;
+
+/// Derives `pest::Parser` from the `#[grammar = "..."]` or `#[grammar_inline = "..."]` of the struct.
+#[proc_macro_derive(Parser, attributes(grammar, grammar_inline))]
+pub fn derive_parser(input: TokenStream) -> TokenStream {
+    let ident = syn::parse::<DeriveInput>(input.clone())
+        .expect("`Parser` derives a struct")
+        .ident;
+    let parser = pest_generator::derive_parser(input.into(), true);
+    quote! {
+        #parser
+
+        impl #ident {
+            /// the derive which generated this parser.
+            pub const FORK: &'static str = "parser_fork";
+        }
+    }
+    .into()
+}
@@ -0,0 +1,89 @@
+//! `rewrite()` on the grammar of the examples.
+
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+language_parser!(generate = "rewrite");
+
+fn script(input: &str) -> pest::iterators::Pairs<'_, Rule> {
+    LanguageParser::parse(Rule::Script(Script), input).unwrap()
+}
+
+#[test]
+fn keeps_untouched_text() {
+    let input = "@print( \"hi\" ,  1 );\n\t@exit();";
+    assert_eq!(rewrite(input, script(input), |_, _| None).unwrap(), input);
+
+    let output = rewrite(input, script(input), |kind, _| {
+        (kind == RuleKind::Number).then(|| "2".to_string())
+    });
+    assert_eq!(output.unwrap(), "@print( \"hi\" ,  2 );\n\t@exit();");
+}
+
+#[test]
+fn replaces_the_innermost_node() {
+    let input = "@print(1);";
+    let output = rewrite(input, script(input), |kind, pair| {
+        (kind == RuleKind::Identifier).then(|| pair.as_str().to_uppercase())
+    });
+    assert_eq!(output.unwrap(), "@PRINT(1);");
+}
+
+#[test]
+fn rejects_a_node_replaced_with_its_descendant() {
+    let input = " @print(1);";
+    let error = rewrite(input, script(input), |kind, _| {
+        matches!(kind, RuleKind::Command | RuleKind::Identifier).then(String::new)
+    })
+    .unwrap_err();
+    assert_eq!(error.first, (RuleKind::Command, 1..10));
+    assert_eq!(error.second, (RuleKind::Identifier, 2..7));
+    assert_eq!(
+        error.to_string(),
+        "replacement of `Command` at 1..10 overlaps replacement of `Identifier` at 2..7"
+    );
+}
+
+#[test]
+fn replaces_adjacent_nodes() {
+    let input = "@a();@b();";
+    let output = rewrite(input, script(input), |kind, pair| {
+        (kind == RuleKind::Statement).then(|| format!("[{}]", pair.as_str()))
+    });
+    assert_eq!(output.unwrap(), "[@a();][@b();]");
+}
+
+#[test]
+fn replaces_a_node_ending_the_input() {
+    let input = "@a(1); @b(2);";
+    let output = rewrite(input, script(input), |kind, pair| {
+        (kind == RuleKind::Statement && pair.as_str() == "@b(2);").then(|| "@c();".to_string())
+    });
+    assert_eq!(output.unwrap(), "@a(1); @c();");
+}
+
+#[test]
+fn rejects_a_node_replaced_with_an_empty_descendant_at_its_end() {
+    let input = "@a();";
+    let error = rewrite(input, script(input), |kind, _| {
+        matches!(kind, RuleKind::Script | RuleKind::EOI).then(String::new)
+    })
+    .unwrap_err();
+    assert_eq!(error.first, (RuleKind::Script, 0..5));
+    assert_eq!(error.second, (RuleKind::EOI, 5..5));
+}
+
+#[test]
+fn replaces_an_empty_node_after_its_sibling() {
+    let input = "@a();";
+    let output = rewrite(input, script(input), |kind, pair| match kind {
+        RuleKind::Statement => Some(format!("[{}]", pair.as_str())),
+        RuleKind::EOI => Some("<EOI>".to_string()),
+        _ => None,
+    });
+    assert_eq!(output.unwrap(), "[@a();]<EOI>");
+}
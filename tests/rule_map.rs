@@ -0,0 +1,41 @@
+//! `RULE_MAP` of the `phf` feature, the rules looked up by their names in the grammar.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(
+    grammar_inline = r#"
+WHITESPACE = _{ " " }
+statement  = { SOI ~ match ~ ";" ~ EOI }
+match      = { "match" ~ ident }
+ident      = @{ ASCII_ALPHA+ }
+"#,
+    interface = "ParserInterface"
+)]
+pub struct KeywordParser;
+
+impl ParserInterface for statement {}
+impl ParserInterface for r#match {}
+impl ParserInterface for ident {}
+impl ParserInterface for EOI {}
+
+#[test]
+fn finds_every_rule_by_name() {
+    assert_eq!(RULE_MAP.len(), Rule::RULE_COUNT);
+    for kind in RuleKind::ALL {
+        let rule = RULE_MAP[kind.name()]();
+        assert!(rule == kind, "`{}` built {rule:?}", kind.name());
+    }
+}
+
+#[test]
+fn names_the_rules_as_written_in_the_grammar() {
+    assert!(RULE_MAP["EOI"]() == RuleKind::EOI);
+    assert!(RULE_MAP["match"]() == RuleKind::r#match);
+    assert!(RULE_MAP.get("r#match").is_none());
+    assert!(RULE_MAP["WHITESPACE"]() == RuleKind::WHITESPACE);
+    assert!(RULE_MAP.get("Statement").is_none());
+}
@@ -0,0 +1,33 @@
+//! `RuleKind::from_name()` against the sorted table it searches, on the 500-rule grammar of `benches/names.rs`.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_rules;
+
+#[pest_rules(grammar = "benches/names.pest")]
+pub struct NamesParser;
+
+#[test]
+fn every_name_resolves_to_its_rule() {
+    assert_eq!(RuleKind::ALL.len(), 500);
+    for kind in RuleKind::ALL {
+        assert_eq!(RuleKind::from_name(kind.name()), Some(kind), "{kind:?}");
+    }
+}
+
+#[test]
+fn resolves_both_ends_of_the_sorted_table() {
+    let mut names = RuleKind::ALL.map(|kind| kind.name());
+    names.sort_unstable();
+    assert_eq!([names[0], names[499]], ["rule_0", "rule_99"]);
+    assert_eq!(RuleKind::from_name("rule_0"), Some(RuleKind::rule_0));
+    assert_eq!(RuleKind::from_name("rule_99"), Some(RuleKind::rule_99));
+}
+
+#[test]
+fn other_names_are_not_rules() {
+    for name in [
+        "", "A", "zzz", "rule_", "rule_500", "Rule_0", "rule_0 ", "EOI ",
+    ] {
+        assert_eq!(RuleKind::from_name(name), None, "`{name}` is not a rule");
+    }
+}
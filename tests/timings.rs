@@ -0,0 +1,93 @@
+//! The report of `ENUM_DISPATCH_PEST_TIMINGS`, printed while a crate using the macros is built.
+
+use std::path::Path;
+use std::process::Command;
+
+const MANIFEST: &str = r#"
+[package]
+name = "timings_fixture"
+version = "0.0.0"
+edition = "2021"
+publish = false
+
+[dependencies]
+enum_dispatch = "0.3"
+enum_dispatch_pest_parser = { path = MACROS }
+pest = "2.5"
+
+[workspace]
+"#;
+
+const LIB: &str = r#"
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[enum_dispatch]
+pub trait WordInterface {}
+
+#[pest_parser(grammar_inline = "word = { ASCII_ALPHA+ }", interface = "WordInterface")]
+pub struct WordParser;
+
+impl WordInterface for word {}
+"#;
+
+/// builds the fixture with `ENUM_DISPATCH_PEST_TIMINGS` set, and returns the lines of the report.
+fn build_reports() -> Vec<String> {
+    let root = Path::new(env!("CARGO_TARGET_TMPDIR")).join("timings_fixture");
+    std::fs::create_dir_all(root.join("src")).unwrap();
+    let macros = format!("{:?}", env!("CARGO_MANIFEST_DIR"));
+    std::fs::write(root.join("Cargo.toml"), MANIFEST.replace("MACROS", &macros)).unwrap();
+    std::fs::copy(
+        Path::new(env!("CARGO_MANIFEST_DIR")).join("Cargo.lock"),
+        root.join("Cargo.lock"),
+    )
+    .unwrap();
+    // written on every run, so the fixture is rebuilt and the macros expanded again.
+    std::fs::write(root.join("src/lib.rs"), LIB).unwrap();
+
+    let output = Command::new(std::env::var_os("CARGO").unwrap_or("cargo".into()))
+        .args(["build", "--offline", "--quiet"])
+        .current_dir(&root)
+        .env("CARGO_TARGET_DIR", root.join("target"))
+        .env("ENUM_DISPATCH_PEST_TIMINGS", "1")
+        .env_remove("ENUM_DISPATCH_PEST_NO_CACHE")
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(output.status.success(), "{stderr}");
+    stderr
+        .lines()
+        .filter(|line| line.starts_with("enum_dispatch_pest_parser: "))
+        .map(String::from)
+        .collect()
+}
+
+/// returns the names of the phases of a report line, in order.
+fn phases(line: &str) -> Vec<&str> {
+    let (_, phases) = line.split_once("rules): ").unwrap();
+    phases
+        .split(", ")
+        .map(|phase| phase.split_once(' ').unwrap().0)
+        .collect()
+}
+
+#[test]
+fn names_every_phase() {
+    let reports = build_reports();
+    assert_eq!(reports.len(), 1, "{reports:#?}");
+    assert!(reports[0].starts_with("enum_dispatch_pest_parser: WordParser (`<inline>`, 1 rules): "));
+    assert_eq!(
+        phases(&reports[0]),
+        [
+            "grammar",
+            "derive_parser",
+            "extraction",
+            "hooking",
+            "check",
+            "cache",
+            "structs",
+            "helpers",
+            "total"
+        ]
+    );
+}
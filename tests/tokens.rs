@@ -0,0 +1,59 @@
+//! `tokens()` and `tokens_with()`, the leaves of a parse tree in source order.
+
+use enum_dispatch::enum_dispatch;
+use pest::Parser;
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+language_parser!(generate = "tokens");
+
+const SCRIPT: &str = "@print(\"hi\", 1);\n@exit();";
+
+fn script() -> pest::iterators::Pairs<'static, Rule> {
+    LanguageParser::parse(Rule::Script(Script), SCRIPT).unwrap()
+}
+
+#[test]
+fn yields_the_leaves_in_source_order() {
+    let tokens = tokens(script())
+        .map(|token| (token.kind, token.text))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        [
+            (RuleKind::Identifier, "print"),
+            (RuleKind::Strings, "\"hi\""),
+            (RuleKind::Number, "1"),
+            (RuleKind::Identifier, "exit"),
+            (RuleKind::EOI, "")
+        ]
+    );
+}
+
+#[test]
+fn spans_the_text_of_the_tokens() {
+    for token in tokens(script()) {
+        assert_eq!(token.span.as_str(), token.text);
+        assert_eq!(&SCRIPT[token.span.start()..token.span.end()], token.text);
+    }
+    let eoi = tokens(script()).last().unwrap();
+    assert_eq!(eoi.span.start(), SCRIPT.len());
+}
+
+#[test]
+fn keeps_the_whole_kinds_in_one_piece() {
+    let tokens = tokens_with(script(), &[RuleKind::Arguments])
+        .map(|token| (token.kind, token.text))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        tokens,
+        [
+            (RuleKind::Identifier, "print"),
+            (RuleKind::Arguments, "\"hi\", 1"),
+            (RuleKind::Identifier, "exit"),
+            (RuleKind::EOI, "")
+        ]
+    );
+}
@@ -0,0 +1,79 @@
+//! Two parsers over one grammar dispatching different interfaces, sharing the cached hooking.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+use pest::Parser;
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+#[enum_dispatch]
+pub trait CountingInterface {
+    fn count(&self) -> usize {
+        0
+    }
+}
+
+#[enum_dispatch]
+pub trait NamingInterface {
+    fn label(&self) -> Option<&'static str> {
+        None
+    }
+}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "CountingInterface",
+    namespaced = "true"
+)]
+pub struct CountingParser;
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    interface = "NamingInterface",
+    namespaced = "true"
+)]
+pub struct NamingParser;
+
+impl CountingInterface for counting_parser::Number {
+    fn count(&self) -> usize {
+        1
+    }
+}
+
+impl NamingInterface for naming_parser::Identifier {
+    fn label(&self) -> Option<&'static str> {
+        Some("identifier")
+    }
+}
+
+rule_structs!(
+    CountingInterface for counting_parser: EOI, WHITESPACE, Script, Statement, Command, Arguments,
+    Argument, Strings, Identifier
+);
+rule_structs!(
+    NamingInterface for naming_parser: EOI, WHITESPACE, Script, Statement, Command, Arguments,
+    Argument, Strings, Number
+);
+
+const SCRIPT: &str = "@add(1, 2, x);";
+
+#[test]
+fn dispatches_the_interface_of_each_parser() {
+    let rule = counting_parser::Rule::Script(counting_parser::Script);
+    let pairs = CountingParser::parse(rule, SCRIPT).unwrap();
+    let numbers = pairs
+        .flatten()
+        .map(|pair| pair.as_rule().count())
+        .sum::<usize>();
+    assert_eq!(numbers, 2);
+
+    let rule = naming_parser::Rule::Script(naming_parser::Script);
+    let pairs = NamingParser::parse(rule, SCRIPT).unwrap();
+    let labels = pairs
+        .flatten()
+        .filter_map(|pair| pair.as_rule().label().map(|label| (label, pair.as_str())))
+        .collect::<Vec<_>>();
+    assert_eq!(labels, [("identifier", "add"), ("identifier", "x")]);
+}
@@ -0,0 +1,6 @@
+//! Expansions which must fail to compile, with the errors they are reported with.
+
+#[test]
+fn ui() {
+    trybuild::TestCases::new().compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,26 @@
+//! A rule added to the grammar (`Float`) without an arm in `all_variants!` fails to compile.
+
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", generate = "all_variants")]
+    pub struct NumberParser;
+
+    grammar {
+        Number = @{ ASCII_DIGIT+ }
+        Float = @{ ASCII_DIGIT+ ~ "." ~ ASCII_DIGIT+ }
+    }
+}
+
+fn describe(kind: RuleKind) -> &'static str {
+    all_variants!(kind, {
+        Number => "number",
+    })
+}
+
+fn main() {
+    describe(RuleKind::Number);
+}
@@ -0,0 +1,25 @@
+//! Rules without an impl of the interface (`Float` and `Word`) are all reported by `assert_impls`, in one build.
+
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+pub trait ParserInterface {
+    fn describe(&self) -> &'static str {
+        "rule"
+    }
+}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", assert_impls = "true")]
+    pub struct NumberParser;
+
+    grammar {
+        Number = @{ ASCII_DIGIT+ }
+        Float = @{ ASCII_DIGIT+ ~ "." ~ ASCII_DIGIT+ }
+        Word = @{ ASCII_ALPHA+ }
+    }
+}
+
+impl ParserInterface for Number {}
+
+fn main() {}
@@ -0,0 +1,30 @@
+//! Two rules whose names only differ in case (`value` and `Value`) would delegate to the same function, which is
+//! reported naming both.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_dispatch;
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_rules(generate = "delegate")]
+    pub struct ValueParser;
+
+    grammar {
+        Value = { value ~ ("," ~ value)* }
+        value = @{ ASCII_DIGIT+ }
+    }
+}
+
+#[pest_dispatch(rules = "ValueParser", delegate_to = "Handlers")]
+pub trait Handle {
+    fn handle(&self, text: &str) -> usize;
+}
+
+struct Handlers;
+
+impl Handlers {
+    fn value(text: &str) -> usize {
+        text.len()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,29 @@
+//! A rule (`Float`) without a function of its name on the `delegate_to` type fails to compile.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_dispatch;
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_rules(generate = "delegate")]
+    pub struct NumberParser;
+
+    grammar {
+        Number = @{ ASCII_DIGIT+ }
+        Float = @{ ASCII_DIGIT+ ~ "." ~ ASCII_DIGIT+ }
+    }
+}
+
+#[pest_dispatch(rules = "NumberParser", delegate_to = "Handlers")]
+pub trait Handle {
+    fn handle(&self, text: &str) -> usize;
+}
+
+struct Handlers;
+
+impl Handlers {
+    fn number(text: &str) -> usize {
+        text.len()
+    }
+}
+
+fn main() {}
@@ -0,0 +1,19 @@
+//! An `example:` which is not a string literal is reported naming the rule, pointing at its line.
+
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", doc_examples = "true")]
+    pub struct CommandParser;
+
+    grammar {
+        /// example: "@exit()"
+        /// example: @cmd(1, 2)
+        Command = { "@" ~ ASCII_ALPHA+ ~ "(" ~ (ASCII_DIGIT+ ~ ("," ~ " "? ~ ASCII_DIGIT+)*)? ~ ")" }
+    }
+}
+
+fn main() {}
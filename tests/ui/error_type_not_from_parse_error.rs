@@ -0,0 +1,22 @@
+//! An `error_type` without `From<pest::error::Error<Rule>>` is reported naming the argument and the missing impl.
+
+use enum_dispatch::enum_dispatch;
+
+#[derive(Debug)]
+pub struct ScriptError;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", error_type = "crate::ScriptError", generate = "parse")]
+    pub struct NumberParser;
+
+    grammar {
+        Number = @{ ASCII_DIGIT+ }
+    }
+}
+
+impl ParserInterface for Number {}
+
+fn main() {}
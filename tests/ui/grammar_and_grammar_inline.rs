@@ -0,0 +1,16 @@
+//! A grammar file and an inline grammar are given to the same parser.
+
+use enum_dispatch::enum_dispatch;
+use enum_dispatch_pest_parser::pest_parser;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[pest_parser(
+    grammar = "examples/grammar.pest",
+    grammar_inline = "number = @{ ASCII_DIGIT+ }",
+    interface = "ParserInterface"
+)]
+pub struct NumberParser;
+
+fn main() {}
@@ -0,0 +1,24 @@
+//! An overridden rule whose type is not `Copy`, which `pest` requires of `Rule`, is reported naming the rule.
+
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+#[derive(Clone, Debug, Default)]
+pub struct Word {
+    word: String,
+}
+
+impl ParserInterface for Word {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", override(Word = "crate::Word"))]
+    pub struct WordParser;
+
+    grammar {
+        Word = @{ ASCII_ALPHA+ }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+//! `pest_version` declaring another version than the one of `pest_generator` is reported with both.
+
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", pest_version = "2.5")]
+    pub struct NumberParser;
+
+    grammar {
+        Number = @{ ASCII_DIGIT+ }
+    }
+}
+
+fn main() {}
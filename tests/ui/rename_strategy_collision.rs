@@ -0,0 +1,18 @@
+//! Two rules renamed to the same struct (`tok_number` and `number`, both `Number`) are reported naming both.
+
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", rename_strategy = "strip_prefix:tok_, pascal_case")]
+    pub struct NumberParser;
+
+    grammar {
+        tok_number = @{ ASCII_DIGIT+ }
+        number = @{ "0x" ~ ASCII_HEX_DIGIT+ }
+    }
+}
+
+fn main() {}
@@ -0,0 +1,23 @@
+//! A type other than the rule structs cannot implement a sealed interface.
+
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+pub trait ParserInterface: sealed::Sealed {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", sealed = "true")]
+    pub struct WordParser;
+
+    grammar {
+        Word = @{ ASCII_ALPHA+ }
+    }
+}
+
+impl ParserInterface for Word {}
+
+pub struct Impostor;
+
+impl ParserInterface for Impostor {}
+
+fn main() {}
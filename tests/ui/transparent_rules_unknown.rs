@@ -0,0 +1,18 @@
+//! A rule of `transparent_rules` missing from the grammar is reported on the value of the argument.
+
+use enum_dispatch::enum_dispatch;
+
+#[enum_dispatch]
+pub trait ParserInterface {}
+
+enum_dispatch_pest_parser::pest_parser_tokens! {
+    #[pest_parser(interface = "ParserInterface", transparent_rules = "call, argument")]
+    pub struct CallParser;
+
+    grammar {
+        call = { ident ~ "(" ~ ident? ~ ")" }
+        ident = @{ ASCII_ALPHA+ }
+    }
+}
+
+fn main() {}
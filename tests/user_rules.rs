@@ -0,0 +1,40 @@
+//! `Rule::user_rules()`, the rules of the grammar without `EOI`.
+
+use enum_dispatch::enum_dispatch;
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+language_parser!();
+
+#[test]
+fn leaves_out_eoi() {
+    assert!(Rule::user_rules()
+        .iter()
+        .all(|rule| RuleKind::from(*rule) != RuleKind::EOI));
+    assert_eq!(Rule::user_rules().len(), Rule::USER_RULE_COUNT);
+    assert_eq!(Rule::USER_RULE_COUNT, Rule::RULE_COUNT - 1);
+}
+
+#[test]
+fn lists_the_rules_in_definition_order() {
+    let kinds = Rule::user_rules()
+        .iter()
+        .map(|rule| RuleKind::from(*rule))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        kinds,
+        [
+            RuleKind::WHITESPACE,
+            RuleKind::Script,
+            RuleKind::Statement,
+            RuleKind::Command,
+            RuleKind::Arguments,
+            RuleKind::Argument,
+            RuleKind::Strings,
+            RuleKind::Number,
+            RuleKind::Identifier
+        ]
+    );
+}
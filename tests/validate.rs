@@ -0,0 +1,56 @@
+//! `validate()`, checking a table of samples against the rules of the grammar.
+
+use enum_dispatch::enum_dispatch;
+use pest::error::{ErrorVariant, InputLocation};
+
+#[macro_use]
+#[path = "../examples/common/mod.rs"]
+mod common;
+
+language_parser!(generate = "validate");
+
+#[test]
+fn accepts_matching_samples() {
+    let samples = [
+        (RuleKind::Script, "@print(\"hi\");\n@exit(0);"),
+        (RuleKind::Command, "@f(1, x)"),
+        (RuleKind::Number, "42"),
+        (RuleKind::WHITESPACE, "\t"),
+    ];
+    assert_eq!(LanguageParser::validate(&samples), Ok(()));
+}
+
+#[test]
+fn reports_every_failed_sample() {
+    let samples = [
+        (RuleKind::Number, "42"),
+        (RuleKind::Identifier, "1abc"),
+        (RuleKind::Number, "42abc"),
+        (RuleKind::Command, "@f(1)"),
+    ];
+    let failures = LanguageParser::validate(&samples).unwrap_err();
+    let failures = failures
+        .iter()
+        .map(|(kind, error)| (*kind, error.location.clone()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        failures,
+        [
+            (RuleKind::Identifier, InputLocation::Pos(0)),
+            (RuleKind::Number, InputLocation::Pos(2))
+        ]
+    );
+}
+
+#[test]
+fn reports_a_partial_match() {
+    let failures = LanguageParser::validate(&[(RuleKind::Number, "42abc")]).unwrap_err();
+    let (kind, error) = &failures[0];
+    assert_eq!(*kind, RuleKind::Number);
+    assert_eq!(
+        error.variant,
+        ErrorVariant::CustomError {
+            message: "`Number` does not match the whole sample".into()
+        }
+    );
+}